@@ -0,0 +1,5 @@
+//! Arcsat API Server
+//!
+//! Partes do binário `arcsat-server` compartilhadas com os testes
+
+pub mod migrate;
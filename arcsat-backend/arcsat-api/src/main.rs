@@ -2,65 +2,89 @@
 //!
 //! Servidor principal do ERP/CRM com Market Intelligence integrado
 
+use arcsat_api::migrate;
 use axum::{
     routing::get,
     Router,
 };
+use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Carregar .env
-    dotenv::dotenv().ok();
+    // Config (TOML + .env + ambiente); carregada antes do logging para que
+    // RUST_LOG definido no .env seja respeitado
+    let config = arcsat_core::config::Config::load()?;
 
     // Setup logging
     tracing_subscriber::registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "arcsat=debug,tower_http=debug".into()),
+            config
+                .var("RUST_LOG")
+                .and_then(|filter| tracing_subscriber::EnvFilter::try_new(filter).ok())
+                .unwrap_or_else(|| "arcsat=debug,tower_http=debug".into()),
         )
         .with(tracing_subscriber::fmt::layer().json())
         .init();
 
+    let pool = PgPoolOptions::new()
+        .max_connections(config.database.max_connections)
+        .connect(&config.database.url)
+        .await?;
+
+    // Subcomando: arcsat-server migrate [run|status|revert]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migrate::command(&pool, &args[1..]).await;
+    }
+
     tracing::info!("🚀 Starting Arcsat API Server");
+    config.log_sources();
 
-    // Config
-    let config = arcsat_core::config::Config::default();
-    let redis_url = std::env::var("REDIS_URL")
-        .unwrap_or_else(|_| config.redis.url.clone());
+    if config.database.auto_migrate {
+        migrate::run(&pool).await?;
+    }
 
     // Market Intelligence Engine
     let proxy_config = if config.market_intelligence.proxy_enabled {
-        config.market_intelligence.proxy_url.as_ref().map(|url| {
-            arcsat_market_intelligence::proxy::ProxyConfig::new(url.clone())
+        config.market_intelligence.proxy_url.as_deref().and_then(|urls| {
+            arcsat_market_intelligence::proxy::ProxyPool::from_urls(urls).first().cloned()
         })
     } else {
         None
     };
 
-    let mi_engine = Arc::new(
-        arcsat_market_intelligence::MarketIntelligenceEngine::new(&redis_url, proxy_config)
-            .await?
-    );
+    let mut mi_engine =
+        arcsat_market_intelligence::MarketIntelligenceEngine::new(&config.redis.url, pool, proxy_config)
+            .await?;
+    mi_engine.max_pages = config.market_intelligence.max_pages_per_job;
+    let mi_engine = Arc::new(mi_engine);
 
     tracing::info!("✅ Market Intelligence Engine initialized");
 
+    // Cotações de câmbio do arquivo (também podem ser enviadas por POST /fx-rates)
+    if let Some(path) = &config.market_intelligence.fx_rates_file {
+        let csv = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("cannot read FX rates file {}: {}", path.display(), e))?;
+        let loaded = mi_engine.load_fx_rates(&csv).await?;
+        tracing::info!("💱 Loaded {} FX rates from {}", loaded, path.display());
+    }
+
+    // JWT: sem chave configurada a API não sobe
+    let jwt_auth = arcsat_core::auth::JwtAuth::from_config(&config.auth)?;
+
     // Router principal
     let app = Router::new()
         .route("/", get(root))
         .route("/health", get(health))
         .route("/api/v1/health", get(health))  // Health check alternativo
-        .merge(arcsat_market_intelligence::api::router(mi_engine.clone()))
+        .merge(arcsat_market_intelligence::api::router(mi_engine.clone(), jwt_auth))
         .layer(CorsLayer::permissive());
 
-    // Usar PORT do environment (Railway) ou config
-    let port = std::env::var("PORT")
-        .ok()
-        .and_then(|p| p.parse::<u16>().ok())
-        .unwrap_or(config.server.port);
-    let addr = format!("{}:{}", config.server.host, port);
+    // PORT (Railway) tem precedência sobre SERVER_PORT, ver Config::load
+    let addr = format!("{}:{}", config.server.host, config.server.port);
     tracing::info!("🌐 Server listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
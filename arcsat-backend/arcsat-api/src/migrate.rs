@@ -0,0 +1,116 @@
+//! Migrations do banco (`arcsat-backend/migrations`)
+//!
+//! Uso: `arcsat-server migrate [run|status|revert [--target <versão>]]`
+
+use anyhow::{bail, Context};
+use sqlx::migrate::{Migrate, Migrator};
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+/// Migrations embutidas no binário em tempo de compilação
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// Uma migration conhecida pelo binário e se já está aplicada no banco
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+/// Aplica todas as migrations pendentes
+pub async fn run(pool: &PgPool) -> anyhow::Result<()> {
+    MIGRATOR.run(pool).await.context("failed to apply migrations")?;
+    tracing::info!("✅ Database migrations up to date");
+    Ok(())
+}
+
+/// Executa o subcomando `migrate` com os argumentos após ele
+pub async fn command(pool: &PgPool, args: &[String]) -> anyhow::Result<()> {
+    match args.first().map(String::as_str) {
+        None | Some("run") => run(pool).await,
+        Some("status") => {
+            for migration in status(pool).await? {
+                let state = if migration.applied { "applied" } else { "pending" };
+                println!("{:>4}  {:<8}  {}", migration.version, state, migration.description);
+            }
+            Ok(())
+        }
+        Some("revert") => {
+            let target = match args.get(1).map(String::as_str) {
+                Some("--target") => Some(
+                    args.get(2)
+                        .context("--target requires a version")?
+                        .parse::<i64>()
+                        .context("--target must be a migration version number")?,
+                ),
+                Some(other) => bail!("unknown argument for migrate revert: {}", other),
+                None => None,
+            };
+            match revert(pool, target).await? {
+                Some(target) => println!("Reverted migrations above version {}", target),
+                None => println!("No applied migrations to revert"),
+            }
+            Ok(())
+        }
+        Some(other) => bail!(
+            "unknown migrate command: {} (expected run, status or revert)",
+            other
+        ),
+    }
+}
+
+/// Migrations conhecidas, em ordem de versão, e se já foram aplicadas
+pub async fn status(pool: &PgPool) -> anyhow::Result<Vec<MigrationStatus>> {
+    let applied = applied_versions(pool).await?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            applied: applied.contains(&m.version),
+        })
+        .collect())
+}
+
+/// Reverte até `target` (exclusivo) ou, sem alvo, apenas a última migration
+/// aplicada. Retorna a versão em que o banco ficou, ou `None` se não havia
+/// migration aplicada para reverter.
+pub async fn revert(pool: &PgPool, target: Option<i64>) -> anyhow::Result<Option<i64>> {
+    let applied = applied_versions(pool).await?;
+
+    let target = match target {
+        Some(target) => target,
+        None => {
+            let Some(latest) = applied.iter().max().copied() else {
+                return Ok(None);
+            };
+            applied.iter().filter(|v| **v < latest).max().copied().unwrap_or(0)
+        }
+    };
+
+    MIGRATOR
+        .undo(pool, target)
+        .await
+        .context("failed to revert migrations")?;
+
+    Ok(Some(target))
+}
+
+async fn applied_versions(pool: &PgPool) -> anyhow::Result<HashSet<i64>> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+
+    if let Some(version) = conn.dirty_version().await? {
+        bail!("migration {} is partially applied (dirty); fix it manually", version);
+    }
+
+    Ok(conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|m| m.version)
+        .collect())
+}
@@ -0,0 +1,147 @@
+//! Subcomando `migrate`: argumentos, `status` e `revert [--target]`.
+//!
+//! Os testes de banco ficam `#[ignore]` e rodam cada um num schema próprio
+//! (`search_path`), criado e apagado pelo teste, para que reverter migrations
+//! não derrube as tabelas usadas pelos outros testes:
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... cargo test -p arcsat-api --test migrate -- --ignored
+//! ```
+
+use arcsat_api::migrate::{self, MIGRATOR};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Banco isolado num schema novo; `drop` apaga o schema
+struct Scratch {
+    admin: PgPool,
+    pool: PgPool,
+    schema: String,
+}
+
+impl Scratch {
+    async fn new() -> Self {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        let admin = PgPool::connect(&url).await.expect("connect to Postgres");
+
+        let schema = format!("migrate_test_{}", Uuid::new_v4().simple());
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(&admin)
+            .await
+            .expect("create scratch schema");
+
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+
+        Self { admin, pool, schema }
+    }
+
+    async fn drop(self) {
+        self.pool.close().await;
+        sqlx::query(&format!("DROP SCHEMA {} CASCADE", self.schema))
+            .execute(&self.admin)
+            .await
+            .unwrap();
+    }
+
+    /// Versões aplicadas segundo `migrate::status`
+    async fn applied(&self) -> Vec<i64> {
+        migrate::status(&self.pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|migration| migration.applied)
+            .map(|migration| migration.version)
+            .collect()
+    }
+
+    async fn has_table(&self, table: &str) -> bool {
+        sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(format!("{}.{}", self.schema, table))
+            .fetch_one(&self.pool)
+            .await
+            .unwrap()
+    }
+}
+
+fn versions() -> Vec<i64> {
+    MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version)
+        .collect()
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[tokio::test]
+async fn invalid_arguments_fail_before_touching_the_database() {
+    // Nunca conecta: o erro sai na leitura dos argumentos
+    let pool = PgPoolOptions::new().connect_lazy("postgres://nobody@127.0.0.1:1/none").unwrap();
+
+    for (arguments, message) in [
+        (&["revert", "--target"][..], "--target requires a version"),
+        (&["revert", "--target", "latest"][..], "--target must be a migration version number"),
+        (&["revert", "--all"][..], "unknown argument for migrate revert: --all"),
+        (&["undo"][..], "unknown migrate command: undo (expected run, status or revert)"),
+    ] {
+        let error = migrate::command(&pool, &args(arguments)).await.unwrap_err();
+        assert_eq!(error.to_string(), message, "{:?}", arguments);
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn status_lists_every_migration_as_pending_then_applied() {
+    let scratch = Scratch::new().await;
+
+    let status = migrate::status(&scratch.pool).await.unwrap();
+    assert_eq!(status.iter().map(|m| m.version).collect::<Vec<_>>(), versions());
+    assert!(status.iter().all(|m| !m.applied));
+    assert_eq!(status[0].description, "market intelligence");
+
+    migrate::run(&scratch.pool).await.unwrap();
+    assert_eq!(scratch.applied().await, versions());
+
+    scratch.drop().await;
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn revert_undoes_the_latest_or_everything_above_the_target() {
+    let scratch = Scratch::new().await;
+    let all = versions();
+    let latest = *all.last().unwrap();
+
+    // Sem nada aplicado não há o que reverter
+    assert_eq!(migrate::revert(&scratch.pool, None).await.unwrap(), None);
+
+    migrate::run(&scratch.pool).await.unwrap();
+
+    // Sem alvo: só a última
+    assert_eq!(migrate::revert(&scratch.pool, None).await.unwrap(), Some(latest - 1));
+    assert_eq!(scratch.applied().await, all[..all.len() - 1]);
+
+    // --target 7: ficam 1..=7; as tabelas das seguintes somem
+    migrate::command(&scratch.pool, &args(&["revert", "--target", "7"])).await.unwrap();
+    assert_eq!(scratch.applied().await, (1..=7).collect::<Vec<_>>());
+    assert!(scratch.has_table("selector_health").await);
+    assert!(!scratch.has_table("fx_rates").await);
+
+    // As down migrations deixam o banco pronto para reaplicar
+    migrate::run(&scratch.pool).await.unwrap();
+    assert_eq!(scratch.applied().await, all);
+    assert!(scratch.has_table("fx_rates").await);
+
+    assert_eq!(migrate::revert(&scratch.pool, Some(0)).await.unwrap(), Some(0));
+    assert!(scratch.applied().await.is_empty());
+    assert!(!scratch.has_table("scraping_jobs").await);
+
+    scratch.drop().await;
+}
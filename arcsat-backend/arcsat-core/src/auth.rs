@@ -0,0 +1,193 @@
+//! Autenticação JWT (HS256/RS256) para handlers axum
+//!
+//! O middleware `require_auth` valida o header `Authorization: Bearer <token>`
+//! e disponibiliza um `AuthContext` (tenant, usuário e claims) para os handlers.
+
+use crate::config::AuthConfig;
+use crate::{ArcsatError, Result, TenantId, UserId};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{errors::ErrorKind, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Claims esperadas no token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// ID do usuário
+    pub sub: UserId,
+    pub tenant_id: TenantId,
+    pub exp: i64,
+    #[serde(default)]
+    pub iat: Option<i64>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Papel do operador da plataforma, para escrever dados compartilhados por
+/// todos os tenants (ex.: cotações de câmbio). Diferente de `admin`, que o
+/// tenant concede aos próprios usuários, só o provedor de identidade da
+/// plataforma o emite.
+pub const PLATFORM_ADMIN_ROLE: &str = "platform_admin";
+
+/// Identidade do chamador, extraída do JWT pelo middleware
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub user_id: UserId,
+    pub tenant_id: TenantId,
+    pub claims: Claims,
+}
+
+impl AuthContext {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.claims.roles.iter().any(|r| r == role)
+    }
+
+    /// Erro 403 se o token não traz o papel
+    pub fn require_role(&self, role: &str) -> Result<()> {
+        if self.has_role(role) {
+            Ok(())
+        } else {
+            Err(ArcsatError::Forbidden(format!("role {} required", role)))
+        }
+    }
+}
+
+impl From<Claims> for AuthContext {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: claims.sub,
+            tenant_id: claims.tenant_id,
+            claims,
+        }
+    }
+}
+
+/// Falhas de autenticação (sempre respondidas com 401)
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("Missing bearer token")]
+    MissingToken,
+
+    #[error("Token expired")]
+    TokenExpired,
+
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        ArcsatError::from(self).into_response()
+    }
+}
+
+/// Validador de tokens, compartilhado entre requests
+#[derive(Clone)]
+pub struct JwtAuth {
+    key: Arc<DecodingKey>,
+    validation: Arc<Validation>,
+}
+
+impl JwtAuth {
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self::new(DecodingKey::from_secret(secret), Algorithm::HS256)
+    }
+
+    pub fn rs256(public_key_pem: &[u8]) -> Result<Self> {
+        let key = DecodingKey::from_rsa_pem(public_key_pem)
+            .map_err(|e| ArcsatError::Config(format!("invalid RS256 public key: {}", e)))?;
+
+        Ok(Self::new(key, Algorithm::RS256))
+    }
+
+    /// Monta o validador a partir de `auth.*`; falha se a chave exigida pelo
+    /// algoritmo não estiver configurada
+    pub fn from_config(config: &AuthConfig) -> Result<Self> {
+        match config.jwt_algorithm.as_str() {
+            "HS256" => {
+                let secret = config
+                    .jwt_secret
+                    .as_deref()
+                    .ok_or_else(|| ArcsatError::Config("auth.jwt_secret is required for HS256".to_string()))?;
+                Ok(Self::hs256(secret.as_bytes()))
+            }
+            "RS256" => {
+                let path = config.jwt_public_key_path.as_ref().ok_or_else(|| {
+                    ArcsatError::Config("auth.jwt_public_key_path is required for RS256".to_string())
+                })?;
+                let pem = std::fs::read(path).map_err(|e| {
+                    ArcsatError::Config(format!("cannot read {}: {}", path.display(), e))
+                })?;
+                Self::rs256(&pem)
+            }
+            other => Err(ArcsatError::Config(format!("unsupported JWT algorithm {}", other))),
+        }
+    }
+
+    fn new(key: DecodingKey, algorithm: Algorithm) -> Self {
+        Self {
+            key: Arc::new(key),
+            validation: Arc::new(Validation::new(algorithm)),
+        }
+    }
+
+    pub fn validate(&self, token: &str) -> std::result::Result<Claims, AuthError> {
+        jsonwebtoken::decode::<Claims>(token, &self.key, &self.validation)
+            .map(|data| data.claims)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+                _ => AuthError::InvalidToken(e.to_string()),
+            })
+    }
+}
+
+/// Middleware: exige um JWT válido e insere o `AuthContext` no request.
+///
+/// Uso: `.route_layer(axum::middleware::from_fn_with_state(jwt_auth, require_auth))`
+pub async fn require_auth(
+    State(auth): State<JwtAuth>,
+    mut request: Request,
+    next: Next,
+) -> std::result::Result<Response, AuthError> {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim_start().split_once(' '))
+        // O esquema não diferencia maiúsculas (RFC 7235)
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+        .map(|(_, token)| token.trim())
+        .filter(|token| !token.is_empty())
+        .ok_or(AuthError::MissingToken)?;
+
+    let claims = auth.validate(token)?;
+    request.extensions_mut().insert(AuthContext::from(claims));
+
+    Ok(next.run(request).await)
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthContext
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        // Sem o middleware na rota, tratamos como request não autenticado
+        parts
+            .extensions
+            .get::<AuthContext>()
+            .cloned()
+            .ok_or(AuthError::MissingToken)
+    }
+}
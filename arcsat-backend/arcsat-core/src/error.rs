@@ -1,3 +1,11 @@
+use crate::auth::AuthError;
+use crate::ApiResponse;
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,23 +13,215 @@ pub enum ArcsatError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// Falha transitória do scraping (navegação, bloqueio, browser que caiu)
     #[error("Scraping error: {0}")]
     Scraping(String),
 
+    /// Página que não dá para ler do jeito esperado (seletor que não existe
+    /// mais, layout novo); repetir dá o mesmo resultado
+    #[error("Parse error: {0}")]
+    Parse(String),
+
     #[error("Authentication error: {0}")]
-    Auth(String),
+    Auth(#[from] AuthError),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Job {0} not found")]
+    JobNotFound(String),
 
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
 pub type Result<T> = std::result::Result<T, ArcsatError>;
+
+/// Declara `ErrorCode` a partir de uma única lista `Variante => "CÓDIGO"`:
+/// o enum, `as_str`, `from_code` e a serialização saem todos dela
+macro_rules! error_codes {
+    ($($variant:ident => $code:literal,)*) => {
+        /// Códigos de erro expostos na API (`ApiResponse::code`).
+        ///
+        /// São estáveis: clientes decidem pelo código, nunca pela mensagem. Novos
+        /// códigos podem ser adicionados; os existentes não mudam de nome.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum ErrorCode {
+            $($variant,)*
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+
+            pub fn from_code(code: &str) -> Option<Self> {
+                match code {
+                    $($code => Some(ErrorCode::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    AuthTokenMissing => "AUTH_TOKEN_MISSING",
+    AuthTokenExpired => "AUTH_TOKEN_EXPIRED",
+    AuthTokenInvalid => "AUTH_TOKEN_INVALID",
+    Forbidden => "FORBIDDEN",
+    ValidationFailed => "VALIDATION_FAILED",
+    NotFound => "NOT_FOUND",
+    MiJobNotFound => "MI_JOB_NOT_FOUND",
+    Unsupported => "UNSUPPORTED",
+    ScrapingFailed => "SCRAPING_FAILED",
+    ParseFailed => "PARSE_FAILED",
+    UpstreamHttpError => "UPSTREAM_HTTP_ERROR",
+    DatabaseError => "DATABASE_ERROR",
+    RedisError => "REDIS_ERROR",
+    SerializationError => "SERIALIZATION_ERROR",
+    ConfigError => "CONFIG_ERROR",
+    InternalError => "INTERNAL_ERROR",
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        ErrorCode::from_code(&code).ok_or_else(|| serde::de::Error::custom(format!("unknown error code {:?}", code)))
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ArcsatError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ArcsatError::Database(_) => ErrorCode::DatabaseError,
+            ArcsatError::Redis(_) => ErrorCode::RedisError,
+            ArcsatError::Http(_) => ErrorCode::UpstreamHttpError,
+            ArcsatError::Serialization(_) => ErrorCode::SerializationError,
+            ArcsatError::Scraping(_) => ErrorCode::ScrapingFailed,
+            ArcsatError::Parse(_) => ErrorCode::ParseFailed,
+            ArcsatError::Auth(AuthError::MissingToken) => ErrorCode::AuthTokenMissing,
+            ArcsatError::Auth(AuthError::TokenExpired) => ErrorCode::AuthTokenExpired,
+            ArcsatError::Auth(AuthError::InvalidToken(_)) => ErrorCode::AuthTokenInvalid,
+            ArcsatError::Forbidden(_) => ErrorCode::Forbidden,
+            ArcsatError::Config(_) => ErrorCode::ConfigError,
+            ArcsatError::Validation(_) => ErrorCode::ValidationFailed,
+            ArcsatError::JobNotFound(_) => ErrorCode::MiJobNotFound,
+            ArcsatError::NotFound(_) => ErrorCode::NotFound,
+            ArcsatError::Unsupported(_) => ErrorCode::Unsupported,
+            ArcsatError::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ArcsatError::Auth(_) => StatusCode::UNAUTHORIZED,
+            ArcsatError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ArcsatError::Validation(_) => StatusCode::BAD_REQUEST,
+            ArcsatError::JobNotFound(_) | ArcsatError::NotFound(_) => StatusCode::NOT_FOUND,
+            ArcsatError::Unsupported(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ArcsatError::Scraping(_) | ArcsatError::Parse(_) | ArcsatError::Http(_) => StatusCode::BAD_GATEWAY,
+            ArcsatError::Redis(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ArcsatError::Database(_)
+            | ArcsatError::Serialization(_)
+            | ArcsatError::Config(_)
+            | ArcsatError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Falha transitória, que pode passar numa nova tentativa (timeout,
+    /// proxy recusando, 5xx do marketplace). Erros de entrada, configuração
+    /// ou dados se repetiriam iguais e são definitivos.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ArcsatError::Http(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|status| {
+                        // 407: proxy recusou; outro IP do pool pode passar
+                        matches!(status.as_u16(), 407 | 408 | 429) || status.is_server_error()
+                    })
+            }
+            ArcsatError::Redis(e) => {
+                e.is_io_error()
+                    || e.is_timeout()
+                    || e.is_connection_dropped()
+                    || e.is_connection_refusal()
+                    || matches!(e.kind(), redis::ErrorKind::BusyLoadingError | redis::ErrorKind::TryAgain)
+            }
+            ArcsatError::Database(e) => matches!(e, sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut),
+            // Navegação, seletores que não carregaram, browser que caiu
+            ArcsatError::Scraping(_) => true,
+            ArcsatError::Serialization(_)
+            | ArcsatError::Parse(_)
+            | ArcsatError::Auth(_)
+            | ArcsatError::Forbidden(_)
+            | ArcsatError::Config(_)
+            | ArcsatError::Validation(_)
+            | ArcsatError::JobNotFound(_)
+            | ArcsatError::NotFound(_)
+            | ArcsatError::Unsupported(_)
+            | ArcsatError::Internal(_) => false,
+        }
+    }
+}
+
+impl IntoResponse for ArcsatError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let code = self.code();
+
+        // Erros 5xx vão completos para o log; o cliente recebe só o código
+        let message = if status.is_server_error() {
+            tracing::error!(code = code.as_str(), "{}", self);
+            "Internal server error".to_string()
+        } else {
+            self.to_string()
+        };
+
+        let body = Json(ApiResponse::<()>::error(message).with_code(code));
+
+        if status == StatusCode::UNAUTHORIZED {
+            (status, [(header::WWW_AUTHENTICATE, "Bearer")], body).into_response()
+        } else {
+            (status, body).into_response()
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Extractors axum com rejeição no envelope de erro da API
+//!
+//! `axum::Json`, `Query` e `Path` respondem corpo malformado, query inválida
+//! ou parâmetro de rota errado com texto puro. Os daqui têm o mesmo uso, mas
+//! a rejeição vira `ArcsatError::Validation` (400, `VALIDATION_FAILED`).
+//! `Json` também serve de resposta, como o do axum.
+
+use crate::ArcsatError;
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, FromRequestParts, Request},
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Corpo JSON (`axum::Json`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+/// Query string (`axum::extract::Query`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+/// Parâmetros da rota (`axum::extract::Path`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+fn rejected(rejection: impl std::fmt::Display) -> ArcsatError {
+    ArcsatError::Validation(rejection.to_string())
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ArcsatError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(request, state)
+            .await
+            .map(|axum::Json(value)| Json(value))
+            .map_err(rejected)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ArcsatError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Query(value))
+            .map_err(rejected)
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ArcsatError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Path::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Path(value)| Path(value))
+            .map_err(rejected)
+    }
+}
@@ -3,6 +3,10 @@
 pub mod error;
 pub mod types;
 pub mod config;
+pub mod auth;
+pub mod money;
+pub mod extract;
 
-pub use error::{ArcsatError, Result};
+pub use error::{ArcsatError, ErrorCode, Result};
+pub use money::{Currency, Money};
 pub use types::*;
@@ -0,0 +1,210 @@
+//! Valores monetários
+//!
+//! `Money` é um `Decimal` com o código ISO 4217 da moeda, arredondado para
+//! centavos como as colunas `DECIMAL(10, 2)` do banco. Operações entre
+//! moedas diferentes não acontecem implicitamente: somas e comparações
+//! devolvem `None` quando as moedas não batem.
+//!
+//! No banco o valor ocupa duas colunas, o `DECIMAL` e a moeda (`VARCHAR(3)`);
+//! ambas têm suporte direto no sqlx (`Decimal` e `Currency`). Em JSON é
+//! `{"amount": "1299.90", "currency": "BRL"}`, com o valor em string para
+//! não passar por ponto flutuante.
+
+use crate::error::{ArcsatError, Result};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Casas decimais guardadas (centavos)
+const SCALE: u32 = 2;
+
+/// Código ISO 4217 da moeda ("BRL", "USD")
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const BRL: Currency = Currency(*b"BRL");
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+
+    /// Três letras, em qualquer caixa; `None` para o resto
+    pub fn new(code: &str) -> Option<Self> {
+        let code: [u8; 3] = code.trim().as_bytes().try_into().ok()?;
+
+        code.iter()
+            .all(u8::is_ascii_alphabetic)
+            .then(|| Currency(code.map(|c| c.to_ascii_uppercase())))
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Só letras ASCII entram em `new`
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::BRL
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = ArcsatError;
+
+    fn from_str(code: &str) -> Result<Self> {
+        Currency::new(code).ok_or_else(|| ArcsatError::Validation(format!("Invalid currency code {:?}", code)))
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Type<Postgres> for Currency {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Currency {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> std::result::Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl Decode<'_, Postgres> for Currency {
+    fn decode(value: PgValueRef<'_>) -> std::result::Result<Self, BoxDynError> {
+        let code = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(code.parse()?)
+    }
+}
+
+/// Valor em uma moeda, em centavos exatos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "MoneyFields")]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+/// Forma serializada; a desserialização passa por `Money::new` para arredondar
+#[derive(Deserialize)]
+struct MoneyFields {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl From<MoneyFields> for Money {
+    fn from(fields: MoneyFields) -> Self {
+        Money::new(fields.amount, fields.currency)
+    }
+}
+
+impl Money {
+    /// Arredonda para centavos (meio centavo para cima)
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        let mut amount = amount.round_dp_with_strategy(SCALE, RoundingStrategy::MidpointAwayFromZero);
+        amount.rescale(SCALE);
+
+        Self { amount, currency }
+    }
+
+    pub fn brl(amount: Decimal) -> Self {
+        Self::new(amount, Currency::BRL)
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    /// Valor vindo de uma API em ponto flutuante; `None` para NaN/infinito
+    pub fn from_f64(amount: f64, currency: Currency) -> Option<Self> {
+        Decimal::try_from(amount).ok().map(|amount| Self::new(amount, currency))
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.amount > Decimal::ZERO
+    }
+
+    /// Soma na mesma moeda; `None` se as moedas diferem
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        (self.currency == other.currency).then(|| Money::new(self.amount + other.amount, self.currency))
+    }
+
+    /// Diferença na mesma moeda; `None` se as moedas diferem
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        (self.currency == other.currency).then(|| Money::new(self.amount - other.amount, self.currency))
+    }
+
+    /// Multiplica por um fator (margem, desconto, quantidade)
+    pub fn scale(self, factor: Decimal) -> Money {
+        Money::new(self.amount * factor, self.currency)
+    }
+
+    /// Média dos valores; `None` sem valores ou com moedas misturadas
+    pub fn average(values: &[Money]) -> Option<Money> {
+        let (first, rest) = values.split_first()?;
+        let total = rest.iter().try_fold(*first, |total, value| total.checked_add(*value))?;
+
+        Some(Money::new(total.amount / Decimal::from(values.len()), first.currency))
+    }
+
+    /// Variação percentual em relação a `base` (`None` entre moedas diferentes
+    /// ou com `base` zero)
+    pub fn percent_of(self, base: Money) -> Option<Decimal> {
+        let diff = self.checked_sub(base)?;
+        (!base.amount.is_zero()).then(|| diff.amount / base.amount * Decimal::ONE_HUNDRED)
+    }
+}
+
+/// Só valores da mesma moeda são comparáveis
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.amount.cmp(&other.amount))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.currency, self.amount)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::ErrorCode;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
@@ -14,6 +15,9 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Código estável do erro (ver `ErrorCode`); ausente em respostas de sucesso
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -23,6 +27,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
             timestamp: Utc::now(),
         }
     }
@@ -32,9 +37,15 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message.into()),
+            code: None,
             timestamp: Utc::now(),
         }
     }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
 }
 
 /// Paginação padrão
@@ -51,3 +62,11 @@ pub struct PaginatedResponse<T> {
     pub items: Vec<T>,
     pub pagination: Pagination,
 }
+
+/// Listagem paginada por cursor (keyset)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    /// Cursor opaco da próxima página; ausente na última
+    pub next_cursor: Option<String>,
+}
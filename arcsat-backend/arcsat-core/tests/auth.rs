@@ -0,0 +1,142 @@
+//! Middleware `require_auth`: respostas 401 (com `WWW-Authenticate`) para
+//! token ausente, expirado, com assinatura inválida ou outro algoritmo, e o
+//! `AuthContext` entregue ao handler quando o token é válido.
+
+use arcsat_core::auth::{require_auth, AuthContext, Claims, JwtAuth};
+use arcsat_core::config::AuthConfig;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde_json::Value;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const SECRET: &[u8] = b"auth-test-secret";
+
+fn app() -> Router {
+    Router::new()
+        .route("/me", get(|auth: AuthContext| async move { auth.tenant_id.to_string() }))
+        .route_layer(middleware::from_fn_with_state(JwtAuth::hs256(SECRET), require_auth))
+}
+
+fn claims(tenant_id: Uuid, expires_in: i64) -> Claims {
+    Claims {
+        sub: Uuid::new_v4(),
+        tenant_id,
+        exp: chrono::Utc::now().timestamp() + expires_in,
+        iat: None,
+        roles: Vec::new(),
+    }
+}
+
+fn token(claims: &Claims, algorithm: Algorithm, secret: &[u8]) -> String {
+    jsonwebtoken::encode(&Header::new(algorithm), claims, &EncodingKey::from_secret(secret)).unwrap()
+}
+
+/// Status, `WWW-Authenticate` e corpo da resposta de `GET /me`
+async fn get_me(authorization: Option<String>) -> (StatusCode, Option<String>, Vec<u8>) {
+    let mut request = Request::get("/me");
+    if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+    }
+
+    let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let challenge = response
+        .headers()
+        .get(header::WWW_AUTHENTICATE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, challenge, body.to_vec())
+}
+
+/// Espera um 401 com o desafio Bearer e o código dado no envelope
+async fn assert_unauthorized(authorization: Option<String>, code: &str) {
+    let (status, challenge, body) = get_me(authorization).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(challenge.as_deref(), Some("Bearer"));
+
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["code"], code);
+}
+
+#[tokio::test]
+async fn valid_token_reaches_the_handler() {
+    let tenant_id = Uuid::new_v4();
+
+    let (status, challenge, body) =
+        get_me(Some(format!("Bearer {}", token(&claims(tenant_id, 300), Algorithm::HS256, SECRET)))).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(challenge, None);
+    assert_eq!(String::from_utf8(body).unwrap(), tenant_id.to_string());
+}
+
+#[tokio::test]
+async fn bearer_scheme_is_case_insensitive() {
+    let tenant_id = Uuid::new_v4();
+    let jwt = token(&claims(tenant_id, 300), Algorithm::HS256, SECRET);
+
+    for scheme in ["bearer", "BEARER", "BeArEr"] {
+        let (status, _, body) = get_me(Some(format!("{} {}", scheme, jwt))).await;
+
+        assert_eq!(status, StatusCode::OK, "{}", scheme);
+        assert_eq!(String::from_utf8(body).unwrap(), tenant_id.to_string());
+    }
+    assert_unauthorized(Some(format!("Bearerx {}", jwt)), "AUTH_TOKEN_MISSING").await;
+}
+
+#[tokio::test]
+async fn missing_token_is_rejected() {
+    assert_unauthorized(None, "AUTH_TOKEN_MISSING").await;
+    assert_unauthorized(Some("Bearer ".to_string()), "AUTH_TOKEN_MISSING").await;
+    // Outro esquema não conta como token
+    assert_unauthorized(Some("Basic dXNlcjpzZW5oYQ==".to_string()), "AUTH_TOKEN_MISSING").await;
+}
+
+#[tokio::test]
+async fn expired_token_is_rejected() {
+    // Além da margem de 60 s do validador
+    let expired = token(&claims(Uuid::new_v4(), -300), Algorithm::HS256, SECRET);
+
+    assert_unauthorized(Some(format!("Bearer {}", expired)), "AUTH_TOKEN_EXPIRED").await;
+}
+
+#[tokio::test]
+async fn token_signed_with_another_key_is_rejected() {
+    let forged = token(&claims(Uuid::new_v4(), 300), Algorithm::HS256, b"another-secret");
+
+    assert_unauthorized(Some(format!("Bearer {}", forged)), "AUTH_TOKEN_INVALID").await;
+    assert_unauthorized(Some("Bearer not-a-jwt".to_string()), "AUTH_TOKEN_INVALID").await;
+}
+
+#[tokio::test]
+async fn token_with_another_algorithm_is_rejected() {
+    // Mesmo segredo, mas o validador só aceita HS256
+    let hs512 = token(&claims(Uuid::new_v4(), 300), Algorithm::HS512, SECRET);
+
+    assert_unauthorized(Some(format!("Bearer {}", hs512)), "AUTH_TOKEN_INVALID").await;
+}
+
+#[test]
+fn validator_requires_the_key_of_its_algorithm() {
+    let config = |algorithm: &str| AuthConfig {
+        jwt_algorithm: algorithm.to_string(),
+        jwt_secret: None,
+        jwt_public_key_path: None,
+    };
+
+    for (algorithm, message) in [
+        ("HS256", "auth.jwt_secret is required for HS256"),
+        ("RS256", "auth.jwt_public_key_path is required for RS256"),
+        ("ES256", "unsupported JWT algorithm ES256"),
+    ] {
+        let error = JwtAuth::from_config(&config(algorithm)).err().unwrap();
+        assert_eq!(error.to_string(), format!("Configuration error: {}", message));
+    }
+}
@@ -0,0 +1,142 @@
+//! Configuração: leitura do TOML e precedência das camadas
+//! (padrões < TOML < `.env` < ambiente), sem tocar no ambiente do processo
+
+use arcsat_core::config::{Config, ConfigSource};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn example() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../arcsat.toml.example")
+}
+
+/// Arquivo temporário com o conteúdo dado, apagado no fim do teste
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, content: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("arcsat-{}-{}", uuid::Uuid::new_v4(), name));
+        std::fs::write(&path, content).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn shipped_example_loads() {
+    let config = Config::from_file(&example()).unwrap();
+
+    assert_eq!(config.market_intelligence.selector_drift_ratio, 0.5);
+    assert_eq!(config.market_intelligence.selector_min_samples, 5);
+    assert_eq!(config.source_of("server.port"), ConfigSource::File(example()));
+}
+
+#[test]
+fn float_and_integer_values_are_accepted() {
+    let file = TempFile::new(
+        "arcsat.toml",
+        "[market_intelligence]\nselector_drift_ratio = 0.25\nmax_attempts = 7\n",
+    );
+
+    let config = Config::from_file(&file.0).unwrap();
+    assert_eq!(config.market_intelligence.selector_drift_ratio, 0.25);
+    assert_eq!(config.market_intelligence.max_attempts, 7);
+
+    // Fora da faixa, a validação aponta a chave e o arquivo
+    let file = TempFile::new("arcsat.toml", "[market_intelligence]\nselector_drift_ratio = 1.5\n");
+    let error = Config::from_file(&file.0).unwrap_err().to_string();
+    assert!(error.contains("market_intelligence.selector_drift_ratio must be greater than 0 and at most 1"), "{}", error);
+}
+
+#[test]
+fn defaults_apply_without_other_layers() {
+    let config = Config::from_layers(None, HashMap::new(), HashMap::new()).unwrap();
+
+    assert_eq!(config.server.port, 3000);
+    assert_eq!(config.source_of("server.port"), ConfigSource::Default);
+}
+
+#[test]
+fn each_layer_overrides_the_previous_one() {
+    let file = TempFile::new("arcsat.toml", "[server]\nport = 4000\nworkers = 8\n");
+    let dotenv = Path::new("/srv/arcsat/.env");
+    let config_file = file.0.to_str().unwrap();
+
+    // Só o arquivo
+    let config = Config::from_layers(None, HashMap::new(), vars(&[("ARCSAT_CONFIG", config_file)])).unwrap();
+    assert_eq!(config.server.port, 4000);
+    assert_eq!(config.source_of("server.port"), ConfigSource::File(file.0.clone()));
+
+    // .env acima do arquivo
+    let config = Config::from_layers(
+        Some(dotenv),
+        vars(&[("SERVER_PORT", "5000")]),
+        vars(&[("ARCSAT_CONFIG", config_file)]),
+    )
+    .unwrap();
+    assert_eq!(config.server.port, 5000);
+    assert_eq!(config.source_of("server.port"), ConfigSource::DotEnv(dotenv.to_path_buf()));
+    // O que o .env não define continua vindo do arquivo
+    assert_eq!(config.server.workers, 8);
+
+    // Ambiente acima do .env; PORT vence SERVER_PORT
+    let config = Config::from_layers(
+        Some(dotenv),
+        vars(&[("SERVER_PORT", "5000")]),
+        vars(&[("ARCSAT_CONFIG", config_file), ("SERVER_PORT", "6000"), ("PORT", "7000")]),
+    )
+    .unwrap();
+    assert_eq!(config.server.port, 7000);
+    assert_eq!(config.source_of("server.port"), ConfigSource::Env("PORT".to_string()));
+}
+
+#[test]
+fn config_file_can_be_chosen_in_the_dotenv() {
+    let file = TempFile::new("arcsat.toml", "[redis]\nurl = \"redis://cache:6379\"\n");
+
+    let config = Config::from_layers(
+        Some(Path::new(".env")),
+        vars(&[("ARCSAT_CONFIG", file.0.to_str().unwrap())]),
+        HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(config.redis.url, "redis://cache:6379");
+}
+
+#[test]
+fn dotenv_vars_are_read_without_touching_the_process_env() {
+    let config = Config::from_layers(
+        Some(Path::new(".env")),
+        vars(&[("RUST_LOG", "arcsat=info"), ("ARCSAT_TEST_DOTENV_ONLY", "1")]),
+        vars(&[("RUST_LOG", "arcsat=trace")]),
+    )
+    .unwrap();
+
+    // O ambiente vence o .env
+    assert_eq!(config.var("RUST_LOG"), Some("arcsat=trace"));
+    assert_eq!(config.var("ARCSAT_TEST_DOTENV_ONLY"), Some("1"));
+    assert_eq!(config.var("ARCSAT_TEST_UNSET"), None);
+    assert!(std::env::var("ARCSAT_TEST_DOTENV_ONLY").is_err());
+}
+
+#[test]
+fn invalid_values_name_their_source() {
+    let error = Config::from_layers(None, HashMap::new(), vars(&[("SERVER_WORKERS", "0"), ("REDIS_URL", "localhost")]))
+        .unwrap_err()
+        .to_string();
+
+    assert!(error.contains("server.workers must be at least 1 (source: env SERVER_WORKERS)"), "{}", error);
+    assert!(error.contains("redis.url must be a redis:// or rediss:// URL (source: env REDIS_URL)"), "{}", error);
+
+    let error = Config::from_layers(None, HashMap::new(), vars(&[("SERVER_PORT", "porta")])).unwrap_err().to_string();
+    assert!(error.contains("invalid value for server.port (env SERVER_PORT)"), "{}", error);
+}
@@ -0,0 +1,160 @@
+//! Envelope de erro da API: código estável, status e corpo de cada
+//! `ArcsatError`, e as rejeições dos extractors (`arcsat_core::extract`) no
+//! mesmo formato.
+
+use arcsat_core::auth::AuthError;
+use arcsat_core::extract::{Json, Path, Query};
+use arcsat_core::{ArcsatError, ErrorCode};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn body_of(response: Response) -> Value {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+/// Status, código e mensagem da resposta do erro
+async fn respond(error: ArcsatError) -> (StatusCode, String, Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let body = body_of(response).await;
+
+    assert_eq!(body["success"], false);
+    assert_eq!(body["data"], Value::Null);
+
+    (status, body["code"].as_str().unwrap().to_string(), body["error"].clone())
+}
+
+#[test]
+fn codes_serialize_as_their_string() {
+    for code in ErrorCode::ALL {
+        let serialized = serde_json::to_value(code).unwrap();
+        assert_eq!(serialized, json!(code.as_str()));
+        assert_eq!(serde_json::from_value::<ErrorCode>(serialized).unwrap(), *code);
+        assert_eq!(code.to_string(), code.as_str());
+    }
+
+    assert_eq!(ErrorCode::from_code("MI_JOB_NOT_FOUND"), Some(ErrorCode::MiJobNotFound));
+    assert_eq!(ErrorCode::from_code("mi_job_not_found"), None);
+    assert!(serde_json::from_value::<ErrorCode>(json!("NOPE")).is_err());
+}
+
+#[tokio::test]
+async fn client_errors_keep_their_message() {
+    for (error, status, code, message) in [
+        (
+            ArcsatError::Validation("search_query must not be empty".to_string()),
+            StatusCode::BAD_REQUEST,
+            "VALIDATION_FAILED",
+            "Validation error: search_query must not be empty",
+        ),
+        (ArcsatError::Forbidden("role admin required".to_string()), StatusCode::FORBIDDEN, "FORBIDDEN", "Forbidden: role admin required"),
+        (ArcsatError::JobNotFound("3f2c".to_string()), StatusCode::NOT_FOUND, "MI_JOB_NOT_FOUND", "Job 3f2c not found"),
+        (ArcsatError::NotFound("alert".to_string()), StatusCode::NOT_FOUND, "NOT_FOUND", "Not found: alert"),
+        (
+            ArcsatError::Unsupported("olx".to_string()),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "UNSUPPORTED",
+            "Unsupported: olx",
+        ),
+    ] {
+        assert_eq!(respond(error).await, (status, code.to_string(), json!(message)));
+    }
+}
+
+#[tokio::test]
+async fn server_errors_hide_the_detail() {
+    for (error, status, code) in [
+        (ArcsatError::Internal("lock poisoned".to_string()), StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+        (ArcsatError::Config("auth.jwt_secret missing".to_string()), StatusCode::INTERNAL_SERVER_ERROR, "CONFIG_ERROR"),
+        (ArcsatError::Database(sqlx::Error::PoolTimedOut), StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR"),
+        (ArcsatError::Scraping("navigation timeout".to_string()), StatusCode::BAD_GATEWAY, "SCRAPING_FAILED"),
+    ] {
+        assert_eq!(respond(error).await, (status, code.to_string(), json!("Internal server error")));
+    }
+}
+
+#[tokio::test]
+async fn auth_errors_carry_the_bearer_challenge() {
+    let response = ArcsatError::from(AuthError::TokenExpired).into_response();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+    assert_eq!(body_of(response).await["code"], "AUTH_TOKEN_EXPIRED");
+
+    // Os demais status não têm o desafio
+    let response = ArcsatError::Forbidden("x".to_string()).into_response();
+    assert!(response.headers().get(header::WWW_AUTHENTICATE).is_none());
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    per_page: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewJob {
+    search_query: String,
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/jobs", post(|Json(job): Json<NewJob>| async move { Json(json!({ "query": job.search_query })) }))
+        .route("/products", get(|Query(page): Query<Page>| async move { page.per_page.to_string() }))
+        .route("/jobs/:job_id", get(|Path(job_id): Path<Uuid>| async move { job_id.to_string() }))
+}
+
+async fn call(request: Request<Body>) -> (StatusCode, Value) {
+    let response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn post_json(body: &str) -> Request<Body> {
+    Request::post("/jobs")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn extractors_accept_valid_input() {
+    let (status, body) = call(post_json(r#"{"search_query": "fone"}"#)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "query": "fone" }));
+
+    let response = app().oneshot(Request::get("/products?per_page=20").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn extractor_rejections_use_the_error_envelope() {
+    let requests = [
+        // JSON malformado, campo faltando e sem Content-Type
+        post_json("{"),
+        post_json(r#"{"query": "fone"}"#),
+        Request::post("/jobs").body(Body::from(r#"{"search_query": "fone"}"#)).unwrap(),
+        // Query e rota com tipo errado
+        Request::get("/products?per_page=muitos").body(Body::empty()).unwrap(),
+        Request::get("/jobs/nao-e-uuid").body(Body::empty()).unwrap(),
+    ];
+
+    for request in requests {
+        let uri = request.uri().to_string();
+        let (status, body) = call(request).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert_eq!(body["success"], false, "{}", uri);
+        assert_eq!(body["code"], "VALIDATION_FAILED", "{}", uri);
+        assert!(body["error"].as_str().unwrap().starts_with("Validation error: "), "{}", uri);
+    }
+}
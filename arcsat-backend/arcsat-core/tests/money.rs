@@ -0,0 +1,62 @@
+//! Arredondamento, serialização e regras de moeda de `Money`
+
+use arcsat_core::{Currency, Money};
+use rust_decimal_macros::dec;
+
+#[test]
+fn amounts_are_rounded_to_cents() {
+    assert_eq!(Money::brl(dec!(10.005)).amount(), dec!(10.01));
+    assert_eq!(Money::brl(dec!(10.004)).amount(), dec!(10.00));
+    assert_eq!(Money::brl(dec!(-0.125)).amount(), dec!(-0.13));
+    assert_eq!(Money::brl(dec!(7)).to_string(), "BRL 7.00");
+
+    // 0.1 + 0.2 em f64 não é 0.3
+    let sum = Money::brl(dec!(0.1)).checked_add(Money::brl(dec!(0.2))).unwrap();
+    assert_eq!(sum, Money::brl(dec!(0.3)));
+
+    assert_eq!(Money::from_f64(1299.9, Currency::BRL), Some(Money::brl(dec!(1299.90))));
+    assert_eq!(Money::from_f64(f64::NAN, Currency::BRL), None);
+}
+
+#[test]
+fn json_keeps_the_amount_as_a_string() {
+    let json = serde_json::to_value(Money::new(dec!(4.89), Currency::USD)).unwrap();
+    assert_eq!(json, serde_json::json!({ "amount": "4.89", "currency": "USD" }));
+
+    // Números também são aceitos na entrada e arredondados
+    let money: Money = serde_json::from_value(serde_json::json!({ "amount": 19.999, "currency": "brl" })).unwrap();
+    assert_eq!(money, Money::brl(dec!(20.00)));
+
+    assert!(serde_json::from_value::<Money>(serde_json::json!({ "amount": "1", "currency": "R$" })).is_err());
+}
+
+#[test]
+fn different_currencies_do_not_mix() {
+    let brl = Money::brl(dec!(10));
+    let usd = Money::new(dec!(10), Currency::USD);
+
+    assert_eq!(brl.checked_add(usd), None);
+    assert_eq!(brl.checked_sub(usd), None);
+    assert_eq!(brl.partial_cmp(&usd), None);
+    assert_eq!(Money::average(&[brl, usd]), None);
+    assert_eq!(Money::average(&[]), None);
+}
+
+#[test]
+fn average_and_percent_change() {
+    let prices = [Money::brl(dec!(10)), Money::brl(dec!(20)), Money::brl(dec!(20))];
+    assert_eq!(Money::average(&prices), Some(Money::brl(dec!(16.67))));
+
+    assert_eq!(Money::brl(dec!(120)).percent_of(Money::brl(dec!(100))), Some(dec!(20)));
+    assert_eq!(Money::brl(dec!(1)).percent_of(Money::zero(Currency::BRL)), None);
+    assert_eq!(Money::brl(dec!(100)).scale(dec!(0.95)), Money::brl(dec!(95)));
+}
+
+#[test]
+fn currency_codes() {
+    assert_eq!(Currency::new(" usd "), Some(Currency::USD));
+    assert_eq!(Currency::new("R$"), None);
+    assert_eq!(Currency::new("BRLL"), None);
+    assert_eq!("eur".parse::<Currency>().unwrap(), Currency::EUR);
+    assert_eq!(Currency::default(), Currency::BRL);
+}
@@ -1,65 +1,101 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
-    routing::{get, post},
+    extract::State,
+    middleware,
+    routing::get,
     Router,
 };
-use std::sync::Arc;
+use crate::insights::{market_average, CrmIntegrationService};
 use crate::models::*;
-use crate::insights::CrmIntegrationService;
-use arcsat_core::ApiResponse;
+use crate::repository::ProductRepository;
+use arcsat_core::auth::{require_auth, AuthContext, JwtAuth};
+use arcsat_core::extract::{Json, Path};
+use arcsat_core::{ApiResponse, ArcsatError, Result, TenantId};
+use arcsat_market_intelligence::models::ScrapedProduct;
+use arcsat_market_intelligence::repository::JobRepository;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
-pub fn router() -> Router {
+/// Anúncios da categoria comparados com o produto
+const MARKET_SAMPLE: u32 = 200;
+
+/// Catálogo do CRM e dados de mercado do Market Intelligence
+pub struct CrmState {
+    pub products: ProductRepository,
+    pub market: JobRepository,
+}
+
+impl CrmState {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            products: ProductRepository::new(pool.clone()),
+            market: JobRepository::new(pool),
+        }
+    }
+
+    /// Produto do tenant e os anúncios da categoria dele. Produto de outro
+    /// tenant é `NotFound`.
+    async fn product_with_market(
+        &self,
+        tenant_id: TenantId,
+        product_id: Uuid,
+    ) -> Result<(Product, Vec<ScrapedProduct>)> {
+        let product = self.products
+            .get_product(tenant_id, product_id)
+            .await?
+            .ok_or_else(|| ArcsatError::NotFound(format!("product {}", product_id)))?;
+        let market = self.market
+            .list_category_products(tenant_id, &product.category, MARKET_SAMPLE)
+            .await?;
+
+        Ok((product, market))
+    }
+}
+
+/// Rotas do CRM; todas exigem JWT (o tenant vem do `AuthContext`)
+pub fn router(state: Arc<CrmState>, auth: JwtAuth) -> Router {
     Router::new()
         .route("/api/v1/crm/products/:product_id/insights", get(get_product_insights))
         .route("/api/v1/crm/products/:product_id/suggested-price", get(get_suggested_price))
+        .route_layer(middleware::from_fn_with_state(auth, require_auth))
+        .with_state(state)
 }
 
 /// GET /api/v1/crm/products/:product_id/insights
 async fn get_product_insights(
+    State(state): State<Arc<CrmState>>,
+    auth: AuthContext,
     Path(product_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Vec<MarketInsight>>>, StatusCode> {
-    // TODO: Buscar produto do banco
-    // TODO: Buscar dados de mercado do Market Intelligence
-    // TODO: Gerar insights
+) -> Result<Json<ApiResponse<Vec<MarketInsight>>>> {
+    let (product, market) = state.product_with_market(auth.tenant_id, product_id).await?;
+    let trends = state.market
+        .list_analyses(auth.tenant_id, None, Some(&product.category), 1)
+        .await?;
 
-    // Mock para demonstração
-    let insights = vec![
-        MarketInsight {
-            id: Uuid::new_v4(),
-            product_id,
-            insight_type: InsightType::PricingOpportunity,
-            title: "Oportunidade de ajuste de preço".to_string(),
-            description: "Seu preço está 15% acima da média do mercado".to_string(),
-            suggested_action: "Reduzir preço para R$ 299.90".to_string(),
-            priority: InsightPriority::High,
-            data: serde_json::json!({
-                "current_price": 349.90,
-                "market_avg": 299.90,
-                "diff_percent": 15.0
-            }),
-            created_at: chrono::Utc::now(),
-        }
-    ];
+    let insights = CrmIntegrationService::analyze_product(&product, &market, trends.first()).await?;
 
     Ok(Json(ApiResponse::success(insights)))
 }
 
 /// GET /api/v1/crm/products/:product_id/suggested-price
 async fn get_suggested_price(
+    State(state): State<Arc<CrmState>>,
+    auth: AuthContext,
     Path(product_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
-    // TODO: Implementar cálculo real
+) -> Result<Json<ApiResponse<serde_json::Value>>> {
+    let (product, market) = state.product_with_market(auth.tenant_id, product_id).await?;
+    // Margem sobre o custo que o preço sugerido garante
+    let target_margin = Decimal::new(3, 1);
+    let suggested_price = CrmIntegrationService::suggest_optimal_price(&product, &market, target_margin);
 
     let response = serde_json::json!({
-        "product_id": product_id,
-        "current_price": 349.90,
-        "suggested_price": 299.90,
-        "market_avg": 310.00,
-        "reasoning": "Preço sugerido está 5% abaixo da média para aumentar competitividade",
-        "expected_margin": 28.5
+        "product_id": product.id,
+        "current_price": product.price,
+        "suggested_price": suggested_price,
+        "market_avg": market_average(&product, &market),
+        "competitors": market.len(),
+        "target_margin": target_margin,
     });
 
     Ok(Json(ApiResponse::success(response)))
@@ -4,7 +4,8 @@
 
 use crate::models::*;
 use arcsat_market_intelligence::models::{ScrapedProduct, TrendAnalysis, CompetitionLevel};
-use arcsat_core::Result;
+use arcsat_core::{Money, Result};
+use rust_decimal::Decimal;
 use uuid::Uuid;
 use chrono::Utc;
 
@@ -19,31 +20,29 @@ impl InsightGenerator {
     ) -> Vec<MarketInsight> {
         let mut insights = Vec::new();
 
-        if market_data.is_empty() {
+        // Calcular preço médio do mercado (na moeda do produto)
+        let Some(market_avg) = market_average(product, market_data) else {
             return insights;
-        }
-
-        // Calcular preço médio do mercado
-        let market_avg = market_data.iter()
-            .map(|p| p.price)
-            .sum::<f64>() / market_data.len() as f64;
+        };
 
-        let price_diff_percent = ((product.price - market_avg) / market_avg) * 100.0;
+        let Some(price_diff_percent) = product.price.percent_of(market_avg) else {
+            return insights;
+        };
 
         // Insight: Preço muito acima do mercado
-        if price_diff_percent > 20.0 {
+        if price_diff_percent > Decimal::from(20) {
             insights.push(MarketInsight {
                 id: Uuid::new_v4(),
                 product_id: product.id,
                 insight_type: InsightType::PricingOpportunity,
                 title: "Preço acima do mercado".to_string(),
                 description: format!(
-                    "Seu produto está {}% mais caro que a média do mercado (R$ {:.2}). \
+                    "Seu produto está {:.0}% mais caro que a média do mercado ({}). \
                     Considere ajustar para aumentar competitividade.",
-                    price_diff_percent.abs() as i32,
+                    price_diff_percent.abs().trunc(),
                     market_avg
                 ),
-                suggested_action: format!("Reduzir preço para R$ {:.2}", market_avg * 1.05),
+                suggested_action: format!("Reduzir preço para {}", market_avg.scale(Decimal::new(105, 2))),
                 priority: InsightPriority::High,
                 data: serde_json::json!({
                     "current_price": product.price,
@@ -56,41 +55,43 @@ impl InsightGenerator {
         }
 
         // Insight: Preço muito abaixo (oportunidade de aumentar margem)
-        if price_diff_percent < -15.0 {
+        if price_diff_percent < Decimal::from(-15) {
             insights.push(MarketInsight {
                 id: Uuid::new_v4(),
                 product_id: product.id,
                 insight_type: InsightType::PricingOpportunity,
                 title: "Oportunidade de aumentar margem".to_string(),
                 description: format!(
-                    "Seu preço está {}% abaixo da média (R$ {:.2}). \
+                    "Seu preço está {:.0}% abaixo da média ({}). \
                     Há espaço para aumentar margem sem perder competitividade.",
-                    price_diff_percent.abs() as i32,
+                    price_diff_percent.abs().trunc(),
                     market_avg
                 ),
-                suggested_action: format!("Aumentar preço para R$ {:.2}", market_avg * 0.95),
+                suggested_action: format!("Aumentar preço para {}", market_avg.scale(Decimal::new(95, 2))),
                 priority: InsightPriority::Medium,
                 data: serde_json::json!({
                     "current_price": product.price,
                     "market_avg": market_avg,
                     "diff_percent": price_diff_percent,
-                    "potential_revenue_gain": (market_avg * 0.95 - product.price) * product.stock as f64
+                    "potential_revenue_gain": market_avg
+                        .scale(Decimal::new(95, 2))
+                        .checked_sub(product.price)
+                        .map(|gain| gain.scale(Decimal::from(product.stock)))
                 }),
                 created_at: Utc::now(),
             });
         }
 
         // Insight: Preço competitivo (sweet spot)
-        if price_diff_percent.abs() <= 10.0 {
+        if price_diff_percent.abs() <= Decimal::from(10) {
             insights.push(MarketInsight {
                 id: Uuid::new_v4(),
                 product_id: product.id,
                 insight_type: InsightType::PricingOpportunity,
                 title: "Preço competitivo".to_string(),
-                description: format!(
-                    "Seu preço está alinhado com o mercado. \
+                description: "Seu preço está alinhado com o mercado. \
                     Continue monitorando para manter competitividade."
-                ),
+                    .to_string(),
                 suggested_action: "Manter estratégia atual".to_string(),
                 priority: InsightPriority::Low,
                 data: serde_json::json!({
@@ -141,9 +142,7 @@ impl InsightGenerator {
                 product_id: product.id,
                 insight_type: InsightType::LowCompetition,
                 title: "Baixa competição no nicho".to_string(),
-                description: format!(
-                    "Poucos vendedores nesta categoria. Oportunidade de dominar o nicho!"
-                ),
+                description: "Poucos vendedores nesta categoria. Oportunidade de dominar o nicho!".to_string(),
                 suggested_action: "Investir em SEO e anúncios para capturar mercado".to_string(),
                 priority: InsightPriority::High,
                 data: serde_json::json!({
@@ -191,25 +190,25 @@ impl InsightGenerator {
     ) -> Vec<MarketInsight> {
         let mut insights = Vec::new();
 
-        // Encontrar competidor mais barato
-        if let Some(cheapest) = competitors.iter().min_by(|a, b| {
-            a.price.partial_cmp(&b.price).unwrap()
-        }) {
-            if cheapest.price < product.price * 0.9 {
+        // Encontrar competidor mais barato (só preços na moeda do produto)
+        if let Some(cheapest) = competitors
+            .iter()
+            .filter(|c| c.price.currency() == product.price.currency())
+            .min_by_key(|c| c.price.amount())
+        {
+            if cheapest.price < product.price.scale(Decimal::new(9, 1)) {
                 insights.push(MarketInsight {
                     id: Uuid::new_v4(),
                     product_id: product.id,
                     insight_type: InsightType::PriceAlert,
                     title: "Competidor com preço muito menor".to_string(),
                     description: format!(
-                        "Competidor '{}' está vendendo por R$ {:.2} ({}% mais barato)",
+                        "Competidor '{}' está vendendo por {} ({:.0}% mais barato)",
                         cheapest.seller_name,
                         cheapest.price,
-                        ((product.price - cheapest.price) / product.price * 100.0) as i32
-                    ),
-                    suggested_action: format!(
-                        "Considere ajustar preço ou agregar valor para justificar diferença"
+                        product.price.percent_of(cheapest.price).unwrap_or_default().trunc()
                     ),
+                    suggested_action: "Considere ajustar preço ou agregar valor para justificar diferença".to_string(),
                     priority: InsightPriority::Critical,
                     data: serde_json::json!({
                         "your_price": product.price,
@@ -259,23 +258,34 @@ impl CrmIntegrationService {
     pub fn suggest_optimal_price(
         product: &Product,
         market_data: &[ScrapedProduct],
-        target_margin: f64, // 0.0 - 1.0 (ex: 0.3 = 30%)
-    ) -> f64 {
-        if market_data.is_empty() {
+        target_margin: Decimal, // 0.0 - 1.0 (ex: 0.3 = 30%)
+    ) -> Money {
+        let Some(market_avg) = market_average(product, market_data) else {
             return product.price;
-        }
-
-        let market_avg = market_data.iter()
-            .map(|p| p.price)
-            .sum::<f64>() / market_data.len() as f64;
+        };
 
         // Preço baseado em custo + margem desejada
-        let cost_based_price = product.cost * (1.0 + target_margin);
+        let cost_based_price = product.cost.scale(Decimal::ONE + target_margin);
 
         // Preço baseado em mercado (5% abaixo da média para competitividade)
-        let market_based_price = market_avg * 0.95;
+        let market_based_price = market_avg.scale(Decimal::new(95, 2));
 
         // Escolher o maior entre os dois (garantir margem mínima)
-        cost_based_price.max(market_based_price)
+        if market_based_price > cost_based_price {
+            market_based_price
+        } else {
+            cost_based_price
+        }
     }
 }
+
+/// Preço médio dos concorrentes na moeda do produto; `None` sem nenhum
+pub(crate) fn market_average(product: &Product, market_data: &[ScrapedProduct]) -> Option<Money> {
+    let prices: Vec<Money> = market_data
+        .iter()
+        .map(|p| p.price)
+        .filter(|price| price.currency() == product.price.currency())
+        .collect();
+
+    Money::average(&prices)
+}
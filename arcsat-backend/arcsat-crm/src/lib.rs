@@ -4,8 +4,7 @@
 
 pub mod models;
 pub mod insights;
+pub mod repository;
 pub mod api;
 
 pub use models::*;
-
-use arcsat_core::Result;
@@ -1,3 +1,4 @@
+use arcsat_core::Money;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
@@ -14,7 +15,7 @@ pub struct Lead {
     pub source: String,
     pub stage: LeadStage,
     pub score: i32,
-    pub value: f64,
+    pub value: Money,
     pub probability: i32,
     pub owner_id: Uuid,
     pub created_at: DateTime<Utc>,
@@ -42,8 +43,8 @@ pub struct Product {
     pub name: String,
     pub description: Option<String>,
     pub category: String,
-    pub price: f64,
-    pub cost: f64,
+    pub price: Money,
+    pub cost: Money,
     pub stock: i32,
     pub active: bool,
     pub created_at: DateTime<Utc>,
@@ -57,7 +58,7 @@ pub struct Opportunity {
     pub tenant_id: Uuid,
     pub lead_id: Uuid,
     pub product_id: Uuid,
-    pub value: f64,
+    pub value: Money,
     pub stage: LeadStage,
     pub expected_close_date: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
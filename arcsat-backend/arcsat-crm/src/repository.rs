@@ -0,0 +1,120 @@
+//! Catálogo do CRM em PostgreSQL (`crm_products`)
+//!
+//! Como no Market Intelligence, cada consulta roda numa transação com
+//! `app.tenant_id` definido para as policies de RLS, e os filtros
+//! `WHERE tenant_id` continuam explícitos.
+
+use crate::models::Product;
+use arcsat_core::{Currency, Money, Result, TenantId};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ProductRepository {
+    pool: PgPool,
+}
+
+impl ProductRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Abre uma transação restrita ao tenant
+    async fn begin(&self, tenant_id: TenantId) -> Result<Transaction<'static, Postgres>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+            .bind(tenant_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        Ok(tx)
+    }
+
+    /// Insere o produto ou atualiza o cadastro dele
+    pub async fn save_product(&self, product: &Product) -> Result<()> {
+        let mut tx = self.begin(product.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO crm_products (
+                id, tenant_id, sku, name, description, category, price, cost, currency,
+                stock, active, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (id) DO UPDATE SET
+                sku = EXCLUDED.sku,
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                category = EXCLUDED.category,
+                price = EXCLUDED.price,
+                cost = EXCLUDED.cost,
+                currency = EXCLUDED.currency,
+                stock = EXCLUDED.stock,
+                active = EXCLUDED.active,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(product.id)
+        .bind(product.tenant_id)
+        .bind(&product.sku)
+        .bind(&product.name)
+        .bind(&product.description)
+        .bind(&product.category)
+        .bind(product.price.amount())
+        .bind(product.cost.amount())
+        .bind(product.price.currency())
+        .bind(product.stock)
+        .bind(product.active)
+        .bind(product.created_at)
+        .bind(product.updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Produto do tenant; `None` se não existir ou for de outro tenant
+    pub async fn get_product(&self, tenant_id: TenantId, product_id: Uuid) -> Result<Option<Product>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT id, tenant_id, sku, name, description, category, price, cost, currency,
+                   stock, active, created_at, updated_at
+            FROM crm_products
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(product_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        row.map(|row| product_from_row(&row)).transpose()
+    }
+}
+
+fn product_from_row(row: &PgRow) -> Result<Product> {
+    // Preço e custo na mesma moeda
+    let currency: Currency = row.try_get("currency")?;
+
+    Ok(Product {
+        id: row.try_get("id")?,
+        tenant_id: row.try_get("tenant_id")?,
+        sku: row.try_get("sku")?,
+        name: row.try_get("name")?,
+        description: row.try_get("description")?,
+        category: row.try_get("category")?,
+        price: Money::new(row.try_get("price")?, currency),
+        cost: Money::new(row.try_get("cost")?, currency),
+        stock: row.try_get("stock")?,
+        active: row.try_get("active")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
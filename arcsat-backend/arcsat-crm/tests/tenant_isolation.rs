@@ -0,0 +1,148 @@
+//! Isolamento entre tenants nas rotas do CRM: produto de outro tenant é 404
+//! e os anúncios comparados são só os do tenant.
+//!
+//! Dependem do Postgres, por isso ficam `#[ignore]`:
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... cargo test -p arcsat-crm --test tenant_isolation -- --ignored
+//! ```
+
+use arcsat_core::auth::{Claims, JwtAuth};
+use arcsat_core::Money;
+use arcsat_crm::api::{router, CrmState};
+use arcsat_crm::Product;
+use arcsat_market_intelligence::models::*;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use rust_decimal_macros::dec;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const JWT_SECRET: &[u8] = b"crm-tenant-isolation-test";
+
+async fn pool() -> PgPool {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPool::connect(&url).await.expect("connect to Postgres");
+
+    sqlx::migrate!("../migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    pool
+}
+
+fn token(tenant_id: Uuid) -> String {
+    let claims = Claims {
+        sub: Uuid::new_v4(),
+        tenant_id,
+        exp: chrono::Utc::now().timestamp() + 300,
+        iat: None,
+        roles: Vec::new(),
+    };
+
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(JWT_SECRET),
+    )
+    .unwrap()
+}
+
+fn catalog_product(tenant_id: Uuid, category: &str) -> Product {
+    Product {
+        id: Uuid::new_v4(),
+        tenant_id,
+        sku: "NB-001".to_string(),
+        name: "Notebook 15".to_string(),
+        description: None,
+        category: category.to_string(),
+        price: Money::brl(dec!(3999.90)),
+        cost: Money::brl(dec!(2500.00)),
+        stock: 10,
+        active: true,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+/// Job concluído do tenant com um anúncio na categoria
+async fn scrape(state: &CrmState, tenant_id: Uuid, category: &str, price: Money) {
+    let job = ScrapingJob::new(tenant_id, Marketplace::MercadoLivre, "notebook".to_string(), 1);
+    state.market.save_job(&job).await.unwrap();
+
+    let listing = ScrapedProduct {
+        id: Uuid::new_v4(),
+        job_id: job.id,
+        marketplace: Marketplace::MercadoLivre,
+        external_id: "MLB123".to_string(),
+        title: "Notebook".to_string(),
+        price,
+        url: "https://www.mercadolivre.com.br/p/MLB123".to_string(),
+        image_url: None,
+        pricing: Pricing::default(),
+        seller_name: "Loja".to_string(),
+        seller_id: None,
+        seller_rating: None,
+        sales_rank: None,
+        rating: None,
+        num_reviews: 0,
+        availability: true,
+        category: Some(category.to_string()),
+        brand: None,
+        scraped_at: chrono::Utc::now(),
+        extra: serde_json::json!({}),
+    };
+    state.market.save_products(tenant_id, &[listing]).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn products_of_other_tenants_are_not_found() {
+    let state = Arc::new(CrmState::new(pool().await));
+    let app = router(state.clone(), JwtAuth::hs256(JWT_SECRET));
+    let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+    let category = format!("notebooks-{}", Uuid::new_v4());
+
+    let product = catalog_product(tenant_a, &category);
+    state.products.save_product(&product).await.unwrap();
+    scrape(&state, tenant_a, &category, Money::brl(dec!(3000.00))).await;
+    // Anúncio do outro tenant na mesma categoria não entra na conta
+    scrape(&state, tenant_b, &category, Money::brl(dec!(100.00))).await;
+
+    let get = |path: String, tenant_id: Uuid| {
+        let app = app.clone();
+        let request = Request::get(path)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token(tenant_id)))
+            .body(Body::empty())
+            .unwrap();
+
+        async move {
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+        }
+    };
+
+    for route in ["insights", "suggested-price"] {
+        let path = format!("/api/v1/crm/products/{}/{}", product.id, route);
+
+        let (status, _) = get(path.clone(), tenant_a).await;
+        assert_eq!(status, StatusCode::OK, "{}", route);
+
+        let (status, body) = get(path, tenant_b).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", route);
+        assert_eq!(body["code"], "NOT_FOUND");
+    }
+
+    let (_, body) = get(format!("/api/v1/crm/products/{}/suggested-price", product.id), tenant_a).await;
+    assert_eq!(body["data"]["competitors"], 1);
+    assert_eq!(body["data"]["market_avg"]["amount"], "3000.00");
+    assert_eq!(body["data"]["suggested_price"]["amount"], "3250.00");
+
+    // Também no banco, sem o filtro da aplicação
+    assert!(state.products.get_product(tenant_b, product.id).await.unwrap().is_none());
+}
@@ -0,0 +1,55 @@
+//! Transforma uma página salva do marketplace em fixture de teste
+//!
+//! ```bash
+//! cargo run -p arcsat-market-intelligence --example capture_fixture -- \
+//!     ~/Downloads/busca.html amazon/search.html 'https://www.amazon.com.br/s?k=fone+bluetooth'
+//! ```
+//!
+//! A página vem do navegador (aba anônima, sem login, CEP padrão): DevTools >
+//! Elements > `<html>` > Copy outerHTML, colado num arquivo. O resultado vai
+//! para `tests/fixtures/<marketplace>/`, sem scripts de rastreamento nem dado
+//! pessoal (ver `sanitize`) e com a URL e a data da captura na primeira
+//! linha, o que a tira de `tests/fixtures/SYNTHETIC`. Depois regrave os `.golden.json` com
+//! `UPDATE_GOLDEN=1` e revise o diff.
+
+mod sanitize;
+
+use anyhow::{bail, Context};
+use arcsat_market_intelligence::scrapers::is_bot_challenge;
+use reqwest::StatusCode;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [source, fixture, url] = args.as_slice() else {
+        bail!("usage: capture_fixture <saved page> <marketplace>/<name>.html <page url>");
+    };
+
+    let html = fs::read_to_string(source).with_context(|| format!("failed to read {}", source))?;
+
+    // Desafio anti-bot no lugar da busca não serve de fixture de parser
+    if is_bot_challenge(StatusCode::OK, &html) {
+        bail!("{} is a bot challenge, not the marketplace page; capture it again", source);
+    }
+
+    let captured_on = chrono::Utc::now().date_naive().to_string();
+    let body = sanitize::sanitize(&html);
+    let clean = sanitize::provenance(url, &captured_on) + &body;
+    let leftovers = sanitize::problems(&clean);
+    if !leftovers.is_empty() {
+        bail!("sanitized page still has: {}", leftovers.join("; "));
+    }
+
+    let target = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(fixture);
+    fs::create_dir_all(target.parent().unwrap_or(Path::new(".")))?;
+    fs::write(&target, &clean).with_context(|| format!("failed to write {}", target.display()))?;
+
+    println!(
+        "Wrote {} ({} bytes, {} removed)",
+        target.display(),
+        clean.len(),
+        html.len().saturating_sub(body.len())
+    );
+    Ok(())
+}
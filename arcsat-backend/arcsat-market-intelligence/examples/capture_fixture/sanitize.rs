@@ -0,0 +1,124 @@
+//! Limpeza de uma página capturada antes de virar fixture: sai o que rastreia
+//! e o que identifica alguém; fica o markup que os parsers leem.
+//!
+//! Compartilhado com `tests/fixture_hygiene.rs`, que roda `problems` sobre as
+//! fixtures versionadas.
+
+use regex::{Captures, Regex};
+
+/// Parâmetros de URL de rastreamento (campanha, clique, sessão)
+const TRACKING_PARAMS: &[&str] = &["gclid", "fbclid", "tracking_id", "_trkparms", "sid"];
+const TRACKING_PREFIXES: &[&str] = &["utm_", "pd_rd_", "pf_rd_"];
+
+/// Início da primeira linha de toda fixture gravada pela ferramenta
+pub const PROVENANCE_PREFIX: &str = "<!-- capture_fixture: ";
+
+struct Rule {
+    name: &'static str,
+    pattern: Regex,
+    replace: fn(&Captures) -> String,
+}
+
+fn rules() -> Vec<Rule> {
+    let rule = |name, pattern: &str, replace: fn(&Captures) -> String| Rule {
+        name,
+        pattern: Regex::new(pattern).expect("valid sanitizer pattern"),
+        replace,
+    };
+
+    vec![
+        // JSON de estado (__NEXT_DATA__) e JSON-LD alimentam os parsers;
+        // qualquer outro script é analytics, tag manager ou pixel
+        rule("script", r#"(?is)<script\b([^>]*)>.*?</script\s*>"#, |caps| {
+            let attributes = caps[1].to_ascii_lowercase();
+            if attributes.contains("application/json") || attributes.contains("application/ld+json") {
+                caps[0].to_string()
+            } else {
+                String::new()
+            }
+        }),
+        rule("noscript", r"(?is)<noscript\b.*?</noscript\s*>", |_| String::new()),
+        rule("iframe", r"(?is)<iframe\b.*?</iframe\s*>", |_| String::new()),
+        rule(
+            "tracking pixel",
+            r#"(?i)<img\b[^>]*\b(?:width="1"[^>]*height="1"|height="1"[^>]*width="1")[^>]*>"#,
+            |_| String::new(),
+        ),
+        // Tokens de sessão e CSRF dos formulários
+        rule("hidden input", r#"(?i)<input\b[^>]*type="hidden"[^>]*>"#, |_| String::new()),
+        rule("tracking parameter", r#"\b(href|src)="([^"]*)""#, |caps| {
+            format!(r#"{}="{}""#, &caps[1], strip_tracking(&caps[2]))
+        }),
+        // Saudação do usuário logado no cabeçalho
+        rule("greeting", r"(>\s*)Olá,\s*[^<]+", |caps| format!("{}Olá, visitante", &caps[1])),
+        // Domínio começando por letra: `imagem@2x.png` não é email
+        rule("email", r"[\w.+-]+@[a-zA-Z][\w-]*(?:\.[a-zA-Z]{2,})+", |_| "usuario@example.com".to_string()),
+        rule("cpf", r"\b\d{3}\.\d{3}\.\d{3}-\d{2}\b", |_| "000.000.000-00".to_string()),
+        rule("phone", r"\(\d{2}\)\s?\d{4,5}-\d{4}", |_| "(00) 00000-0000".to_string()),
+        rule("cep", r"\b\d{5}-\d{3}\b", |_| "00000-000".to_string()),
+    ]
+}
+
+/// Tira os parâmetros de rastreamento da query (em HTML, separados por
+/// `&amp;` ou `&`)
+fn strip_tracking(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let (query, fragment) = match query.split_once('#') {
+        Some((query, fragment)) => (query, Some(fragment)),
+        None => (query, None),
+    };
+
+    let kept: Vec<&str> = query
+        .split("&amp;")
+        .flat_map(|part| part.split('&'))
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !TRACKING_PARAMS.contains(&name) && !TRACKING_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+        })
+        .collect();
+
+    // Sem nada a tirar, a URL fica como veio (inclusive o separador)
+    if kept.len() == query.split("&amp;").flat_map(|part| part.split('&')).count() {
+        return url.to_string();
+    }
+
+    let mut clean = base.to_string();
+    if !kept.is_empty() {
+        clean.push('?');
+        clean.push_str(&kept.join("&amp;"));
+    }
+    if let Some(fragment) = fragment {
+        clean.push('#');
+        clean.push_str(fragment);
+    }
+    clean
+}
+
+/// Página pronta para virar fixture
+pub fn sanitize(html: &str) -> String {
+    rules()
+        .iter()
+        .fold(html.to_string(), |html, rule| rule.pattern.replace_all(&html, rule.replace).into_owned())
+}
+
+/// Linha de origem da fixture: URL capturada (sem rastreamento) e data
+pub fn provenance(url: &str, captured_on: &str) -> String {
+    format!("{}{} em {} -->\n", PROVENANCE_PREFIX, strip_tracking(url), captured_on)
+}
+
+/// O que `sanitize` ainda tiraria da página: vazio se ela já está limpa
+pub fn problems(html: &str) -> Vec<String> {
+    let mut found = Vec::new();
+
+    for rule in rules() {
+        for caps in rule.pattern.captures_iter(html) {
+            if (rule.replace)(&caps) != caps[0] {
+                found.push(format!("{}: {}", rule.name, caps[0].chars().take(80).collect::<String>()));
+            }
+        }
+    }
+
+    found
+}
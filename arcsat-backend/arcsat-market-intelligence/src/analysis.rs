@@ -1,45 +1,45 @@
 use crate::models::*;
+use arcsat_core::Money;
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 /// Analisador de tendências de mercado
 pub struct TrendAnalyzer {
-    // Cache de análises
-    cache: HashMap<String, TrendAnalysis>,
+    // Cache de análises (compartilhado entre os jobs do worker)
+    cache: Mutex<HashMap<String, TrendAnalysis>>,
 }
 
 impl TrendAnalyzer {
     pub fn new() -> Self {
         Self {
-            cache: HashMap::new(),
+            cache: Mutex::new(HashMap::new()),
         }
     }
 
     /// Analisa produtos coletados e gera insights
     pub fn analyze(
-        &mut self,
+        &self,
         tenant_id: uuid::Uuid,
         marketplace: Marketplace,
         category: &str,
         products: &[ScrapedProduct],
     ) -> TrendAnalysis {
-        let mut prices: Vec<f64> = products.iter().map(|p| p.price).collect();
-        prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        // Estatísticas na moeda do primeiro produto; preços em outra moeda
+        // não entram na conta (converta antes com `fx::normalize`)
+        let currency = products.first().map(|p| p.price.currency()).unwrap_or_default();
+        let mut prices: Vec<Money> = products
+            .iter()
+            .map(|p| p.price)
+            .filter(|price| price.currency() == currency)
+            .collect();
+        prices.sort_by_key(|price| price.amount());
 
         let total_products = products.len() as u64;
-        let avg_price = if !prices.is_empty() {
-            prices.iter().sum::<f64>() / prices.len() as f64
-        } else {
-            0.0
-        };
-
-        let median_price = if !prices.is_empty() {
-            prices[prices.len() / 2]
-        } else {
-            0.0
-        };
-
-        let min_price = prices.first().copied().unwrap_or(0.0);
-        let max_price = prices.last().copied().unwrap_or(0.0);
+        let zero = Money::zero(currency);
+        let avg_price = Money::average(&prices).unwrap_or(zero);
+        let median_price = prices.get(prices.len() / 2).copied().unwrap_or(zero);
+        let min_price = prices.first().copied().unwrap_or(zero);
+        let max_price = prices.last().copied().unwrap_or(zero);
 
         // Top sellers
         let mut seller_counts: HashMap<String, usize> = HashMap::new();
@@ -47,8 +47,9 @@ impl TrendAnalyzer {
             *seller_counts.entry(product.seller_name.clone()).or_insert(0) += 1;
         }
 
+        let unique_sellers = seller_counts.len();
         let mut top_sellers: Vec<(String, usize)> = seller_counts.into_iter().collect();
-        top_sellers.sort_by(|a, b| b.1.cmp(&a.1));
+        top_sellers.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
         let top_sellers: Vec<String> = top_sellers
             .into_iter()
             .take(10)
@@ -59,7 +60,6 @@ impl TrendAnalyzer {
         let trending_keywords = self.extract_keywords(products);
 
         // Nível de competição baseado no número de vendedores únicos
-        let unique_sellers = seller_counts.len();
         let competition_level = match unique_sellers {
             0..=10 => CompetitionLevel::Low,
             11..=50 => CompetitionLevel::Medium,
@@ -88,7 +88,10 @@ impl TrendAnalyzer {
 
         // Cache
         let cache_key = format!("{}:{:?}:{}", tenant_id, marketplace, category);
-        self.cache.insert(cache_key, analysis.clone());
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(cache_key, analysis.clone());
 
         analysis
     }
@@ -104,8 +107,8 @@ impl TrendAnalyzer {
         ].iter().copied().collect();
 
         for product in products {
-            let words: Vec<&str> = product.title
-                .to_lowercase()
+            let title = product.title.to_lowercase();
+            let words: Vec<&str> = title
                 .split(|c: char| !c.is_alphanumeric())
                 .filter(|w| w.len() > 3 && !stop_words.contains(w))
                 .collect();
@@ -116,7 +119,7 @@ impl TrendAnalyzer {
         }
 
         let mut keywords: Vec<(String, usize)> = word_counts.into_iter().collect();
-        keywords.sort_by(|a, b| b.1.cmp(&a.1));
+        keywords.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
 
         keywords
             .into_iter()
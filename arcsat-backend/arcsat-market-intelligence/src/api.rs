@@ -1,22 +1,46 @@
 use axum::{
-    extract::{Path, State, Query},
-    http::StatusCode,
-    response::Json,
+    extract::State,
+    http::header,
+    middleware,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
 use std::sync::Arc;
 use serde::Deserialize;
+use crate::fx::FxRate;
 use crate::models::*;
 use crate::MarketIntelligenceEngine;
-use arcsat_core::{ApiResponse, Result};
+use arcsat_core::auth::{require_auth, AuthContext, JwtAuth, PLATFORM_ADMIN_ROLE};
+use arcsat_core::extract::{Json, Path, Query};
+use arcsat_core::{ApiResponse, ArcsatError, CursorPage, PaginatedResponse, Result};
+use rust_decimal::Decimal;
 
-pub fn router(engine: Arc<MarketIntelligenceEngine>) -> Router {
-    Router::new()
-        .route("/api/v1/market-intelligence/jobs", post(create_job))
-        .route("/api/v1/market-intelligence/jobs/:job_id", get(get_job))
+/// Tamanho de página padrão e máximo das listagens (`GET /jobs` e
+/// `GET /jobs/:job_id/products`)
+const DEFAULT_PER_PAGE: u32 = 50;
+const MAX_PER_PAGE: u32 = 200;
+
+/// Rotas do módulo; todas exigem JWT, exceto o health check
+pub fn router(engine: Arc<MarketIntelligenceEngine>, auth: JwtAuth) -> Router {
+    let protected = Router::new()
+        .route("/api/v1/market-intelligence/jobs", get(list_jobs).post(create_job))
+        .route("/api/v1/market-intelligence/jobs/:job_id", get(get_job).delete(cancel_job))
         .route("/api/v1/market-intelligence/jobs/:job_id/status", get(get_job_status))
+        .route("/api/v1/market-intelligence/jobs/:job_id/products", get(get_job_products))
         .route("/api/v1/market-intelligence/trends", get(get_trends))
+        .route("/api/v1/market-intelligence/dead-letter", get(list_dead_letters))
+        .route("/api/v1/market-intelligence/dead-letter/:job_id/replay", post(replay_dead_letter))
+        .route("/api/v1/market-intelligence/workers/browser-pool", get(browser_pool_metrics))
+        .route("/api/v1/market-intelligence/alerts", get(list_alerts))
+        .route("/api/v1/market-intelligence/selector-health", get(selector_health))
+        .route("/api/v1/market-intelligence/selector-health/:marketplace/sample", get(selector_sample))
+        .route("/api/v1/market-intelligence/settings", get(get_settings).put(update_settings))
+        .route("/api/v1/market-intelligence/fx-rates", get(list_fx_rates).post(load_fx_rates))
+        .route_layer(middleware::from_fn_with_state(auth, require_auth));
+
+    Router::new()
+        .merge(protected)
         .route("/api/v1/market-intelligence/health", get(health_check))
         .with_state(engine)
 }
@@ -24,13 +48,18 @@ pub fn router(engine: Arc<MarketIntelligenceEngine>) -> Router {
 /// POST /api/v1/market-intelligence/jobs
 async fn create_job(
     State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
     Json(request): Json<CreateJobRequest>,
-) -> Result<Json<ApiResponse<CreateJobResponse>>, StatusCode> {
-    // TODO: Extrair tenant_id do JWT
-    let tenant_id = uuid::Uuid::new_v4();
+) -> Result<Json<ApiResponse<CreateJobResponse>>> {
+    if request.search_query.trim().is_empty() {
+        return Err(ArcsatError::Validation("search_query must not be empty".to_string()));
+    }
+    if request.max_pages == 0 || request.max_pages > engine.max_pages {
+        return Err(ArcsatError::Validation(format!("max_pages must be between 1 and {}", engine.max_pages)));
+    }
 
     let mut job = ScrapingJob::new(
-        tenant_id,
+        auth.tenant_id,
         request.marketplace,
         request.search_query,
         request.max_pages,
@@ -44,9 +73,10 @@ async fn create_job(
         job.priority = priority.clamp(1, 10);
     }
 
-    let job_id = engine.submit_job(job.clone())
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    job.enrich_details = request.enrich_details;
+    job.fetch_strategy = request.fetch_strategy;
+
+    engine.submit_job(job.clone()).await?;
 
     let response = CreateJobResponse {
         job_id: job.id,
@@ -57,14 +87,63 @@ async fn create_job(
     Ok(Json(ApiResponse::success(response)))
 }
 
+#[derive(Debug, Deserialize)]
+struct JobsQuery {
+    status: Option<JobStatus>,
+    marketplace: Option<Marketplace>,
+    category: Option<String>,
+    created_from: Option<chrono::DateTime<chrono::Utc>>,
+    created_to: Option<chrono::DateTime<chrono::Utc>>,
+    q: Option<String>,
+    #[serde(default)]
+    sort: JobSort,
+    #[serde(default)]
+    order: SortOrder,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+/// GET /api/v1/market-intelligence/jobs
+async fn list_jobs(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Query(query): Query<JobsQuery>,
+) -> Result<Json<ApiResponse<CursorPage<JobDetails>>>> {
+    let limit = query.limit.unwrap_or(DEFAULT_PER_PAGE);
+
+    if limit == 0 || limit > MAX_PER_PAGE {
+        return Err(ArcsatError::Validation(format!("limit must be between 1 and {}", MAX_PER_PAGE)));
+    }
+    if let (Some(from), Some(to)) = (query.created_from, query.created_to) {
+        if from > to {
+            return Err(ArcsatError::Validation("created_from must not be after created_to".to_string()));
+        }
+    }
+
+    let filter = JobFilter {
+        status: query.status,
+        marketplace: query.marketplace,
+        category: query.category.filter(|category| !category.trim().is_empty()),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        search: query.q.map(|q| q.trim().to_string()).filter(|q| !q.is_empty()),
+    };
+
+    let jobs = engine
+        .list_jobs(auth.tenant_id, &filter, query.sort, query.order, query.cursor.as_deref(), limit)
+        .await?;
+
+    Ok(Json(ApiResponse::success(jobs)))
+}
+
 /// GET /api/v1/market-intelligence/jobs/:job_id/status
 async fn get_job_status(
     State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
     Path(job_id): Path<String>,
-) -> Result<Json<ApiResponse<JobStatus>>, StatusCode> {
-    let status = engine.get_job_status(&job_id)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
+) -> Result<Json<ApiResponse<JobStatusResponse>>> {
+    // Jobs de outro tenant respondem 404, como se não existissem
+    let status = engine.get_job_status(auth.tenant_id, &job_id).await?;
 
     Ok(Json(ApiResponse::success(status)))
 }
@@ -72,10 +151,76 @@ async fn get_job_status(
 /// GET /api/v1/market-intelligence/jobs/:job_id
 async fn get_job(
     State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Path(job_id): Path<String>,
+) -> Result<Json<ApiResponse<JobDetails>>> {
+    let job = engine.get_job(auth.tenant_id, &job_id).await?;
+
+    Ok(Json(ApiResponse::success(job)))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProductsQuery {
+    page: Option<u32>,
+    per_page: Option<u32>,
+    min_price: Option<Decimal>,
+    max_price: Option<Decimal>,
+    seller: Option<String>,
+    available: Option<bool>,
+}
+
+/// GET /api/v1/market-intelligence/jobs/:job_id/products
+async fn get_job_products(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Path(job_id): Path<String>,
+    Query(query): Query<ProductsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<ScrapedProduct>>>> {
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE);
+
+    if page == 0 {
+        return Err(ArcsatError::Validation("page must be at least 1".to_string()));
+    }
+    if per_page == 0 || per_page > MAX_PER_PAGE {
+        return Err(ArcsatError::Validation(format!("per_page must be between 1 and {}", MAX_PER_PAGE)));
+    }
+    if let (Some(min), Some(max)) = (query.min_price, query.max_price) {
+        if min > max {
+            return Err(ArcsatError::Validation("min_price must not be greater than max_price".to_string()));
+        }
+    }
+
+    let filter = ProductFilter {
+        min_price: query.min_price,
+        max_price: query.max_price,
+        seller: query.seller.filter(|seller| !seller.trim().is_empty()),
+        available: query.available,
+    };
+
+    let products = engine.get_products(auth.tenant_id, &job_id, &filter, page, per_page).await?;
+
+    Ok(Json(ApiResponse::success(products)))
+}
+
+/// DELETE /api/v1/market-intelligence/jobs/:job_id
+async fn cancel_job(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
     Path(job_id): Path<String>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    // TODO: Implementar busca completa do job
-    Ok(Json(ApiResponse::success(format!("Job {}", job_id))))
+) -> Result<Json<ApiResponse<CancelJobResponse>>> {
+    let id = uuid::Uuid::parse_str(&job_id)
+        .map_err(|_| ArcsatError::JobNotFound(job_id.clone()))?;
+
+    let status = engine.cancel_job(auth.tenant_id, &job_id).await?;
+
+    let message = if status == JobStatus::Cancelled {
+        "Job cancelado".to_string()
+    } else {
+        "Cancelamento solicitado; o worker para antes da próxima página".to_string()
+    };
+
+    Ok(Json(ApiResponse::success(CancelJobResponse { job_id: id, status, message })))
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,12 +230,168 @@ struct TrendsQuery {
 }
 
 /// GET /api/v1/market-intelligence/trends
+///
+/// Análises gravadas pelo worker ao fim de cada job, da mais recente à mais antiga
 async fn get_trends(
     State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
     Query(query): Query<TrendsQuery>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    // TODO: Implementar análise de tendências
-    Ok(Json(ApiResponse::success("Trends analysis".to_string())))
+) -> Result<Json<ApiResponse<Vec<TrendAnalysis>>>> {
+    let analyses = engine.repository
+        .list_analyses(auth.tenant_id, query.marketplace, query.category.as_deref(), DEFAULT_PER_PAGE)
+        .await?;
+
+    Ok(Json(ApiResponse::success(analyses)))
+}
+
+/// GET /api/v1/market-intelligence/dead-letter
+async fn list_dead_letters(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+) -> Result<Json<ApiResponse<Vec<ScrapingJob>>>> {
+    let jobs = engine.dead_letters(auth.tenant_id).await?;
+
+    Ok(Json(ApiResponse::success(jobs)))
+}
+
+/// GET /api/v1/market-intelligence/workers/browser-pool
+///
+/// Métricas operacionais, iguais para todos os tenants
+async fn browser_pool_metrics(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    _auth: AuthContext,
+) -> Result<Json<ApiResponse<Vec<WorkerBrowserMetrics>>>> {
+    let metrics = engine.queue.browser_metrics().await?;
+
+    Ok(Json(ApiResponse::success(metrics)))
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertsQuery {
+    alert_type: Option<AlertType>,
+}
+
+/// GET /api/v1/market-intelligence/alerts
+async fn list_alerts(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Query(query): Query<AlertsQuery>,
+) -> Result<Json<ApiResponse<Vec<MarketAlert>>>> {
+    let alerts = engine.repository
+        .list_alerts(auth.tenant_id, query.alert_type, DEFAULT_PER_PAGE)
+        .await?;
+
+    Ok(Json(ApiResponse::success(alerts)))
+}
+
+/// GET /api/v1/market-intelligence/selector-health
+///
+/// Linha de base da extração de cada marketplace, igual para todos os tenants
+async fn selector_health(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    _auth: AuthContext,
+) -> Result<Json<ApiResponse<Vec<SelectorBaseline>>>> {
+    let baselines = engine.repository.list_selector_baselines().await?;
+
+    Ok(Json(ApiResponse::success(baselines)))
+}
+
+/// GET /api/v1/market-intelligence/selector-health/:marketplace/sample
+///
+/// Última página de busca com produtos do marketplace vinda de um job do
+/// tenant, para comparar com o markup atual quando um alerta de drift chega.
+/// O HTML é do marketplace, não nosso: sai como texto para download, nunca
+/// renderizado na origem da API.
+async fn selector_sample(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Path(marketplace): Path<Marketplace>,
+) -> Result<Response> {
+    let sample = engine.repository
+        .get_selector_sample(auth.tenant_id, marketplace)
+        .await?
+        .ok_or_else(|| ArcsatError::NotFound(format!("No sample page for {}", marketplace.as_str())))?;
+
+    let disposition = format!("attachment; filename=\"{}-sample.html\"", marketplace.as_str());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CONTENT_SECURITY_POLICY, "sandbox".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        sample.html,
+    )
+        .into_response())
+}
+
+/// GET /api/v1/market-intelligence/settings
+async fn get_settings(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+) -> Result<Json<ApiResponse<TenantSettings>>> {
+    let settings = engine.repository.get_tenant_settings(auth.tenant_id).await?;
+
+    Ok(Json(ApiResponse::success(settings)))
+}
+
+/// PUT /api/v1/market-intelligence/settings
+async fn update_settings(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Json(settings): Json<TenantSettings>,
+) -> Result<Json<ApiResponse<TenantSettings>>> {
+    engine.repository.save_tenant_settings(auth.tenant_id, &settings).await?;
+
+    Ok(Json(ApiResponse::success(settings)))
+}
+
+#[derive(Debug, Deserialize)]
+struct FxRatesQuery {
+    date: Option<chrono::NaiveDate>,
+}
+
+/// GET /api/v1/market-intelligence/fx-rates
+///
+/// Cotação vigente de cada par no dia (padrão: hoje), igual para todos os tenants
+async fn list_fx_rates(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    _auth: AuthContext,
+    Query(query): Query<FxRatesQuery>,
+) -> Result<Json<ApiResponse<Vec<FxRate>>>> {
+    let date = query.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let rates = engine.repository.list_fx_rates(date).await?;
+
+    Ok(Json(ApiResponse::success(rates)))
+}
+
+/// POST /api/v1/market-intelligence/fx-rates
+///
+/// Corpo em CSV `date,base,quote,rate`. As cotações valem para todos os
+/// tenants, então exige o papel de plataforma (`platform_admin`); o `admin`
+/// do tenant não basta.
+async fn load_fx_rates(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    csv: String,
+) -> Result<Json<ApiResponse<LoadFxRatesResponse>>> {
+    auth.require_role(PLATFORM_ADMIN_ROLE)?;
+
+    let loaded = engine.load_fx_rates(&csv).await?;
+
+    Ok(Json(ApiResponse::success(LoadFxRatesResponse { loaded })))
+}
+
+/// POST /api/v1/market-intelligence/dead-letter/:job_id/replay
+async fn replay_dead_letter(
+    State(engine): State<Arc<MarketIntelligenceEngine>>,
+    auth: AuthContext,
+    Path(job_id): Path<String>,
+) -> Result<Json<ApiResponse<ScrapingJob>>> {
+    let job = engine.replay_dead_letter(auth.tenant_id, &job_id).await?;
+
+    Ok(Json(ApiResponse::success(job)))
 }
 
 /// GET /api/v1/market-intelligence/health
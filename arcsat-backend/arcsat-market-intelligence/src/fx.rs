@@ -0,0 +1,152 @@
+//! Câmbio para comparar preços em moedas diferentes
+//!
+//! As cotações ficam na tabela `fx_rates`, uma por par de moedas e dia, e
+//! entram por CSV (`MI_FX_RATES_FILE` na subida da API ou
+//! `POST /fx-rates`); não há consulta a serviço externo. Cada linha diz
+//! quanto vale 1 unidade de `base` em `quote` naquele dia.
+//!
+//! Antes da análise de tendências os preços vão para a moeda base do tenant
+//! pela cotação mais recente até o dia da coleta (no fim de semana vale a de
+//! sexta). Quando só o sentido contrário foi carregado, usa o inverso.
+
+use crate::models::ScrapedProduct;
+use arcsat_core::{ArcsatError, Currency, Money, Result};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Cotação de um dia: 1 `base` = `rate` `quote`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxRate {
+    pub date: NaiveDate,
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: Decimal,
+}
+
+/// Lê o CSV `date,base,quote,rate` ("2026-10-16,USD,BRL,5.4312"). O
+/// cabeçalho é opcional e linhas vazias ou começando com `#` são ignoradas.
+pub fn parse_csv(text: &str) -> Result<Vec<FxRate>> {
+    let mut rates = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || (index == 0 && line.starts_with("date")) {
+            continue;
+        }
+
+        let invalid = |problem: &str| ArcsatError::Validation(format!("FX rates line {}: {}", index + 1, problem));
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [date, base, quote, rate] = fields[..] else {
+            return Err(invalid("expected date,base,quote,rate"));
+        };
+
+        let rate = FxRate {
+            date: date.parse().map_err(|_| invalid("date must be YYYY-MM-DD"))?,
+            base: base.parse().map_err(|_| invalid("invalid base currency"))?,
+            quote: quote.parse().map_err(|_| invalid("invalid quote currency"))?,
+            rate: rate.parse().map_err(|_| invalid("invalid rate"))?,
+        };
+
+        if rate.base == rate.quote {
+            return Err(invalid("base and quote must differ"));
+        }
+        if rate.rate <= Decimal::ZERO {
+            return Err(invalid("rate must be positive"));
+        }
+
+        rates.push(rate);
+    }
+
+    Ok(rates)
+}
+
+/// Histórico de cotações por par
+#[derive(Debug, Clone, Default)]
+pub struct FxRates {
+    rates: HashMap<(Currency, Currency), BTreeMap<NaiveDate, Decimal>>,
+}
+
+impl FxRates {
+    pub fn new(rates: impl IntoIterator<Item = FxRate>) -> Self {
+        let mut table = Self::default();
+        for rate in rates {
+            table.rates.entry((rate.base, rate.quote)).or_default().insert(rate.date, rate.rate);
+        }
+        table
+    }
+
+    /// Fator de `from` para `to` no dia: a cotação mais recente até a data
+    /// (direta ou o inverso da contrária, a que for mais nova)
+    pub fn rate(&self, from: Currency, to: Currency, date: NaiveDate) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+
+        let latest = |base, quote| {
+            self.rates
+                .get(&(base, quote))?
+                .range(..=date)
+                .next_back()
+                .map(|(day, rate)| (*day, *rate))
+        };
+
+        match (latest(from, to), latest(to, from)) {
+            (Some((day, rate)), Some((inverse_day, _))) if day >= inverse_day => Some(rate),
+            (Some((_, rate)), None) => Some(rate),
+            (_, Some((_, inverse))) => Some(Decimal::ONE / inverse),
+            (None, None) => None,
+        }
+    }
+
+    /// Valor em `to` pela cotação do dia; `None` sem cotação
+    pub fn convert(&self, money: Money, to: Currency, date: NaiveDate) -> Option<Money> {
+        self.rate(money.currency(), to, date)
+            .map(|rate| Money::new(money.amount() * rate, to))
+    }
+}
+
+/// Produtos de uma coleta com os preços numa moeda só
+#[derive(Debug, Clone, Default)]
+pub struct Normalized {
+    pub products: Vec<ScrapedProduct>,
+    /// Moedas sem cotação até o dia; os produtos nelas ficaram de fora
+    pub missing: Vec<Currency>,
+}
+
+/// Converte preço e condições (`pricing`) de cada produto para `to`, pela
+/// cotação do dia da coleta
+pub fn normalize(products: &[ScrapedProduct], rates: &FxRates, to: Currency) -> Normalized {
+    let mut normalized = Normalized::default();
+
+    for product in products {
+        let from = product.price.currency();
+
+        match rates.rate(from, to, product.scraped_at.date_naive()) {
+            Some(rate) => normalized.products.push(convert_product(product, from, to, rate)),
+            None if !normalized.missing.contains(&from) => normalized.missing.push(from),
+            None => {}
+        }
+    }
+
+    normalized
+}
+
+fn convert_product(product: &ScrapedProduct, from: Currency, to: Currency, rate: Decimal) -> ScrapedProduct {
+    // Valores em outra moeda que não a do preço não têm como ser comparados
+    let convert = |money: Money| (money.currency() == from).then(|| Money::new(money.amount() * rate, to));
+
+    let mut product = product.clone();
+    product.price = Money::new(product.price.amount() * rate, to);
+    product.pricing.list_price = product.pricing.list_price.and_then(convert);
+    product.pricing.pix_price = product.pricing.pix_price.and_then(convert);
+    product.pricing.max_price = product.pricing.max_price.and_then(convert);
+    product.pricing.installments = product.pricing.installments.and_then(|mut plan| {
+        plan.amount = convert(plan.amount)?;
+        Some(plan)
+    });
+
+    product
+}
@@ -11,37 +11,358 @@
 
 pub mod scrapers;
 pub mod analysis;
+pub mod fx;
 pub mod queue;
 pub mod proxy;
 pub mod models;
+pub mod repository;
+pub mod retry;
+pub mod selector_health;
 pub mod api;
 
 pub use models::*;
 pub use api::router;
 
-use arcsat_core::Result;
+use arcsat_core::{ArcsatError, CursorPage, PaginatedResponse, Pagination, Result, TenantId};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// `max_pages` aceito por padrão (`MI_MAX_PAGES_PER_JOB`)
+pub const DEFAULT_MAX_PAGES: u32 = 20;
 
 /// Engine principal de scraping
 pub struct MarketIntelligenceEngine {
     pub scrapers: scrapers::ScraperRegistry,
     pub queue: queue::JobQueue,
+    pub repository: repository::JobRepository,
     pub analysis: analysis::TrendAnalyzer,
+    pub selector_health: selector_health::SelectorHealthConfig,
+    /// Maior `max_pages` aceito na criação de um job
+    pub max_pages: u32,
 }
 
 impl MarketIntelligenceEngine {
-    pub async fn new(redis_url: &str, proxy_config: Option<proxy::ProxyConfig>) -> Result<Self> {
+    pub async fn new(
+        redis_url: &str,
+        pool: sqlx::PgPool,
+        proxy_config: Option<proxy::ProxyConfig>,
+    ) -> Result<Self> {
         Ok(Self {
-            scrapers: scrapers::ScraperRegistry::new(proxy_config),
+            scrapers: scrapers::ScraperRegistry::new(proxy_config)?,
             queue: queue::JobQueue::new(redis_url).await?,
+            repository: repository::JobRepository::new(pool),
             analysis: analysis::TrendAnalyzer::new(),
+            selector_health: selector_health::SelectorHealthConfig::default(),
+            max_pages: DEFAULT_MAX_PAGES,
         })
     }
 
     pub async fn submit_job(&self, job: models::ScrapingJob) -> Result<String> {
+        // Sem scraper o job falharia no worker; recusa já na criação
+        self.scrapers.ensure_supported(job.marketplace)?;
+
+        self.repository.save_job(&job).await?;
         self.queue.enqueue(job).await
     }
 
-    pub async fn get_job_status(&self, job_id: &str) -> Result<models::JobStatus> {
-        self.queue.get_status(job_id).await
+    /// Status do job: Redis enquanto o job está na fila, Postgres depois que
+    /// o hash expira. Jobs de outro tenant são `JobNotFound`.
+    pub async fn get_job_status(&self, tenant_id: TenantId, job_id: &str) -> Result<models::JobStatusResponse> {
+        match self.queue.get_status(tenant_id, job_id).await {
+            Err(ArcsatError::JobNotFound(_)) => {
+                let id = parse_job_id(job_id)?;
+
+                self.repository
+                    .get_status(tenant_id, id)
+                    .await?
+                    .ok_or_else(|| ArcsatError::JobNotFound(job_id.to_string()))
+            }
+            result => result,
+        }
+    }
+
+    /// Job completo (Postgres) com a contagem de produtos coletados
+    pub async fn get_job(&self, tenant_id: TenantId, job_id: &str) -> Result<models::JobDetails> {
+        let id = parse_job_id(job_id)?;
+
+        let job = self.repository
+            .get_job(tenant_id, id)
+            .await?
+            .ok_or_else(|| ArcsatError::JobNotFound(job_id.to_string()))?;
+        let results_count = self.repository.count_products(tenant_id, id).await?;
+
+        Ok(models::JobDetails { job, results_count })
+    }
+
+    /// Jobs do tenant, filtrados e paginados por cursor. O cursor vem da
+    /// página anterior (`next_cursor`); inválido é erro de validação.
+    pub async fn list_jobs(
+        &self,
+        tenant_id: TenantId,
+        filter: &models::JobFilter,
+        sort: models::JobSort,
+        order: models::SortOrder,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<CursorPage<models::JobDetails>> {
+        let after = cursor
+            .map(|cursor| {
+                models::JobCursor::decode(cursor)
+                    .ok_or_else(|| ArcsatError::Validation("invalid cursor".to_string()))
+            })
+            .transpose()?;
+
+        // Uma linha a mais diz se existe próxima página
+        let mut items = self.repository
+            .list_jobs(tenant_id, filter, sort, order, after, limit + 1)
+            .await?;
+
+        let next_cursor = if items.len() > limit as usize {
+            items.truncate(limit as usize);
+            items.last().map(|last| models::JobCursor::from_job(&last.job).encode())
+        } else {
+            None
+        };
+
+        Ok(CursorPage { items, next_cursor })
+    }
+
+    /// Produtos do job, paginados (`page` a partir de 1)
+    pub async fn get_products(
+        &self,
+        tenant_id: TenantId,
+        job_id: &str,
+        filter: &models::ProductFilter,
+        page: u32,
+        per_page: u32,
+    ) -> Result<PaginatedResponse<models::ScrapedProduct>> {
+        let id = parse_job_id(job_id)?;
+
+        let (items, total) = self.repository
+            .list_products(tenant_id, id, filter, page, per_page)
+            .await?
+            .ok_or_else(|| ArcsatError::JobNotFound(job_id.to_string()))?;
+
+        Ok(PaginatedResponse {
+            items,
+            pagination: Pagination {
+                page,
+                per_page,
+                total,
+                total_pages: total.div_ceil(per_page as u64) as u32,
+            },
+        })
+    }
+
+    /// Cancela o job do tenant. Na fila, sai dela e vira `Cancelled` na hora;
+    /// em execução, retorna `Running` e o worker para antes da próxima página.
+    /// Jobs já finalizados (exceto cancelados) são erro de validação.
+    pub async fn cancel_job(&self, tenant_id: TenantId, job_id: &str) -> Result<models::JobStatus> {
+        let status = match self.queue.cancel(tenant_id, job_id).await {
+            Ok(queue::Cancellation::Dequeued) => {
+                if let Some(mut job) = self.queue.load_job(tenant_id, job_id).await? {
+                    job.completed_at = Some(chrono::Utc::now());
+                    self.repository.save_job(&job).await?;
+                }
+                return Ok(models::JobStatus::Cancelled);
+            }
+            Ok(queue::Cancellation::Requested) => return Ok(models::JobStatus::Running),
+            Ok(queue::Cancellation::Finished(status)) => status,
+            // Hash já expirou: o job terminou há mais de um dia
+            Err(ArcsatError::JobNotFound(_)) => self.get_job_status(tenant_id, job_id).await?.status,
+            Err(e) => return Err(e),
+        };
+
+        match status {
+            models::JobStatus::Cancelled => Ok(status),
+            _ => Err(ArcsatError::Validation(format!(
+                "Job {} is {} and can no longer be cancelled",
+                job_id,
+                status.as_str()
+            ))),
+        }
+    }
+
+    /// Busca do job no marketplace. A linha de base do monitor de seletores
+    /// diz se página carregada sem cards é mudança de layout (ver
+    /// `ScraperRegistry::scrape_search_expecting`).
+    pub async fn scrape_search(
+        &self,
+        job: &models::ScrapingJob,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> Result<scrapers::SearchResult> {
+        let baseline = self.repository.get_selector_baseline(job.marketplace).await?;
+        let cards_expected = self.selector_health.expects_cards(baseline.as_ref());
+
+        self.scrapers.scrape_search_expecting(job, cancel, cards_expected).await
+    }
+
+    /// Confere a extração da busca do job contra a linha de base do
+    /// marketplace (ver `selector_health::check`)
+    pub async fn check_selector_health(
+        &self,
+        job: &models::ScrapingJob,
+        stats: &scrapers::SearchStats,
+    ) -> Result<selector_health::Verdict> {
+        selector_health::check(&self.repository, &self.selector_health, job, stats).await
+    }
+
+    /// Grava as cotações de um CSV `date,base,quote,rate` (ver `fx::parse_csv`).
+    /// Retorna quantas cotações foram gravadas.
+    pub async fn load_fx_rates(&self, csv: &str) -> Result<usize> {
+        let rates = fx::parse_csv(csv)?;
+        self.repository.save_fx_rates(&rates).await
+    }
+
+    /// Análise de tendências da coleta com os preços na moeda base do
+    /// tenant. Produtos numa moeda sem cotação ficam de fora; sem nenhum
+    /// produto convertido não há análise.
+    pub async fn analyze_products(
+        &self,
+        job: &models::ScrapingJob,
+        products: &[models::ScrapedProduct],
+    ) -> Result<Option<models::TrendAnalysis>> {
+        let days = products.iter().map(|p| p.scraped_at.date_naive());
+        let (Some(from), Some(until)) = (days.clone().min(), days.max()) else {
+            return Ok(None);
+        };
+
+        let settings = self.repository.get_tenant_settings(job.tenant_id).await?;
+        let rates = self.repository.fx_rates(from, until).await?;
+        let normalized = fx::normalize(products, &rates, settings.base_currency);
+
+        if !normalized.missing.is_empty() {
+            warn!(
+                "Job {}: no FX rate from {:?} to {}; {} of {} products left out of the analysis",
+                job.id,
+                normalized.missing,
+                settings.base_currency,
+                products.len() - normalized.products.len(),
+                products.len()
+            );
+        }
+        if normalized.products.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.analysis.analyze(
+            job.tenant_id,
+            job.marketplace,
+            job.category.as_deref().unwrap_or("general"),
+            &normalized.products,
+        )))
+    }
+
+    /// Trata a falha da tentativa atual de um job em execução no worker:
+    /// registra o erro e, conforme a política, agenda a retentativa, manda
+    /// para a dead-letter queue ou encerra como `Failed`. Tira o job da lista
+    /// de processamento (dispensa o `ack`).
+    pub async fn fail_attempt(
+        &self,
+        worker_id: &str,
+        job: &mut models::ScrapingJob,
+        error: &ArcsatError,
+        policy: &retry::RetryPolicy,
+    ) -> Result<retry::RetryDecision> {
+        let job_id = job.id.to_string();
+
+        let attempt_error = job.record_error(error.to_string());
+        self.queue.record_error(job.tenant_id, &job_id, &attempt_error).await?;
+
+        let decision = policy.decide(error, job.attempts);
+        match decision {
+            retry::RetryDecision::Retry(delay) => {
+                job.status = models::JobStatus::Pending;
+                self.queue.retry_later(worker_id, job.tenant_id, &job_id, delay).await?;
+            }
+            retry::RetryDecision::DeadLetter => {
+                job.status = models::JobStatus::Failed;
+                job.completed_at = Some(chrono::Utc::now());
+                self.queue.dead_letter(worker_id, job.tenant_id, &job_id).await?;
+            }
+            retry::RetryDecision::Fail => {
+                job.status = models::JobStatus::Failed;
+                job.completed_at = Some(chrono::Utc::now());
+                self.queue.update_status(job.tenant_id, &job_id, job.status).await?;
+                self.queue.ack(worker_id, job.tenant_id, &job_id).await?;
+            }
+        }
+
+        self.repository.save_job(job).await?;
+        Ok(decision)
     }
+
+    /// Recupera os jobs de workers sem heartbeat e os que passaram do prazo
+    /// há mais de `visibility_timeout`: registra a falha da tentativa e
+    /// atualiza o Postgres. Retorna quantos jobs foram recuperados.
+    pub async fn reap_stalled(&self, visibility_timeout: Duration, max_attempts: u32) -> Result<usize> {
+        let stalled = self.queue.reap_stalled(visibility_timeout, max_attempts).await?;
+        self.record_reaped(&stalled, "worker stopped sending heartbeats").await?;
+
+        let expired = self.queue.reap_expired(visibility_timeout, max_attempts).await?;
+        let reason = format!("job exceeded its {}s timeout", self.queue.job_timeout().as_secs());
+        self.record_reaped(&expired, &reason).await?;
+
+        Ok(stalled.len() + expired.len())
+    }
+
+    /// Devolve à fila os jobs em andamento de um worker que está desligando.
+    /// A tentativa não conta nem entra no histórico de erros; o Postgres volta
+    /// a `Pending`. Retorna quantos jobs foram devolvidos.
+    pub async fn release_worker(&self, worker_id: &str) -> Result<usize> {
+        let released = self.queue.release_worker(worker_id).await?;
+
+        for entry in &released {
+            let Some(mut job) = self.queue.load_job(entry.tenant_id, &entry.job_id).await? else {
+                continue;
+            };
+
+            info!("Job {} released back to the queue at attempt {}", job.id, job.attempts);
+            job.status = models::JobStatus::Pending;
+            self.repository.save_job(&job).await?;
+        }
+
+        Ok(released.len())
+    }
+
+    async fn record_reaped(&self, reaped: &[queue::ReapedJob], reason: &str) -> Result<()> {
+        for entry in reaped {
+            let Some(mut job) = self.queue.load_job(entry.tenant_id, &entry.job_id).await? else {
+                continue;
+            };
+
+            let attempt_error = job.record_error(reason);
+            self.queue.record_error(job.tenant_id, &entry.job_id, &attempt_error).await?;
+
+            if entry.dead {
+                error!("Job {} moved to the dead-letter queue after {} attempts", job.id, job.attempts);
+                job.status = models::JobStatus::Failed;
+                job.completed_at = Some(chrono::Utc::now());
+            } else {
+                warn!("Job {} requeued after attempt {}", job.id, job.attempts);
+                job.status = models::JobStatus::Pending;
+            }
+
+            self.repository.save_job(&job).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Jobs do tenant na dead-letter queue
+    pub async fn dead_letters(&self, tenant_id: TenantId) -> Result<Vec<models::ScrapingJob>> {
+        self.queue.dead_letters(tenant_id).await
+    }
+
+    /// Reenfileira um job da dead-letter queue do tenant
+    pub async fn replay_dead_letter(&self, tenant_id: TenantId, job_id: &str) -> Result<models::ScrapingJob> {
+        let job = self.queue.replay_dead_letter(tenant_id, job_id).await?;
+        self.repository.save_job(&job).await?;
+        Ok(job)
+    }
+}
+
+/// Id que não é UUID não existe em nenhum tenant
+fn parse_job_id(job_id: &str) -> Result<uuid::Uuid> {
+    uuid::Uuid::parse_str(job_id).map_err(|_| ArcsatError::JobNotFound(job_id.to_string()))
 }
@@ -1,9 +1,13 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use arcsat_core::{Currency, Money};
+use rust_decimal::Decimal;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 /// Marketplace suportado
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum Marketplace {
     Amazon,
@@ -25,6 +29,30 @@ impl Marketplace {
             Marketplace::AliExpress => "https://pt.aliexpress.com",
         }
     }
+
+    /// Identificador usado no banco (igual à serialização JSON)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Marketplace::Amazon => "amazon",
+            Marketplace::MercadoLivre => "mercado_livre",
+            Marketplace::B2W => "b2_w",
+            Marketplace::Magalu => "magalu",
+            Marketplace::Shopee => "shopee",
+            Marketplace::AliExpress => "ali_express",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "amazon" => Some(Marketplace::Amazon),
+            "mercado_livre" => Some(Marketplace::MercadoLivre),
+            "b2_w" => Some(Marketplace::B2W),
+            "magalu" => Some(Marketplace::Magalu),
+            "shopee" => Some(Marketplace::Shopee),
+            "ali_express" => Some(Marketplace::AliExpress),
+            _ => None,
+        }
+    }
 }
 
 /// Status do job de scraping
@@ -38,6 +66,63 @@ pub enum JobStatus {
     Cancelled,
 }
 
+impl JobStatus {
+    /// Identificador usado no banco (igual à serialização JSON)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(JobStatus::Pending),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "failed" => Some(JobStatus::Failed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Como as páginas de busca são baixadas (marketplaces de API JSON ignoram)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStrategy {
+    /// Só `reqwest`: o HTML renderizado no servidor já traz os produtos
+    Http,
+    /// Só o headless Chrome
+    Browser,
+    /// HTTP primeiro; o navegador só quando a página vem com desafio anti-bot
+    /// ou sem o seletor obrigatório do scraper
+    Auto,
+}
+
+impl FetchStrategy {
+    /// Identificador usado no banco (igual à serialização JSON)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FetchStrategy::Http => "http",
+            FetchStrategy::Browser => "browser",
+            FetchStrategy::Auto => "auto",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "http" => Some(FetchStrategy::Http),
+            "browser" => Some(FetchStrategy::Browser),
+            "auto" => Some(FetchStrategy::Auto),
+            _ => None,
+        }
+    }
+}
+
 /// Job de scraping
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScrapingJob {
@@ -48,11 +133,32 @@ pub struct ScrapingJob {
     pub category: Option<String>,
     pub max_pages: u32,
     pub priority: u8, // 1-10
+    /// Visita a página de cada produto para completar vendedor, marca, EAN etc.
+    #[serde(default)]
+    pub enrich_details: bool,
+    /// `None` usa a estratégia padrão do scraper do marketplace
+    #[serde(default)]
+    pub fetch_strategy: Option<FetchStrategy>,
     pub status: JobStatus,
     pub created_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Erro da última tentativa
     pub error: Option<String>,
+    /// Entregas a um worker (incrementado a cada dequeue)
+    #[serde(default)]
+    pub attempts: u32,
+    /// Erros de todas as tentativas, do mais antigo ao mais recente
+    #[serde(default)]
+    pub error_history: Vec<AttemptError>,
+}
+
+/// Falha de uma tentativa de execução do job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttemptError {
+    pub attempt: u32,
+    pub error: String,
+    pub at: DateTime<Utc>,
 }
 
 impl ScrapingJob {
@@ -70,13 +176,30 @@ impl ScrapingJob {
             category: None,
             max_pages,
             priority: 5,
+            enrich_details: false,
+            fetch_strategy: None,
             status: JobStatus::Pending,
             created_at: Utc::now(),
             started_at: None,
             completed_at: None,
             error: None,
+            attempts: 0,
+            error_history: Vec::new(),
         }
     }
+
+    /// Registra a falha da tentativa atual e a devolve para gravar na fila
+    pub fn record_error(&mut self, error: impl Into<String>) -> AttemptError {
+        let entry = AttemptError {
+            attempt: self.attempts,
+            error: error.into(),
+            at: Utc::now(),
+        };
+
+        self.error = Some(entry.error.clone());
+        self.error_history.push(entry.clone());
+        entry
+    }
 }
 
 /// Produto encontrado no scraping
@@ -89,10 +212,13 @@ pub struct ScrapedProduct {
     // Dados do produto
     pub external_id: String, // ID no marketplace
     pub title: String,
-    pub price: f64,
-    pub currency: String,
+    /// Preço de venda ("por"), na moeda do anúncio; numa faixa, o menor valor
+    pub price: Money,
     pub url: String,
     pub image_url: Option<String>,
+    /// Preço de lista, PIX e parcelamento, quando o anúncio mostra
+    #[serde(default)]
+    pub pricing: Pricing,
 
     // Vendedor
     pub seller_name: String,
@@ -114,6 +240,26 @@ pub struct ScrapedProduct {
     pub extra: serde_json::Value,
 }
 
+/// Condições de preço do anúncio além do preço de venda, na mesma moeda
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+    /// Preço "de" (riscado), sempre maior que o de venda
+    pub list_price: Option<Money>,
+    /// Preço à vista com desconto (PIX ou boleto)
+    pub pix_price: Option<Money>,
+    /// Teto de uma faixa de preço ("R$ 10 a R$ 20"); o piso é o preço de venda
+    pub max_price: Option<Money>,
+    pub installments: Option<InstallmentPlan>,
+}
+
+/// Parcelamento: `count` parcelas de `amount`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InstallmentPlan {
+    pub count: u32,
+    pub amount: Money,
+    pub interest_free: bool,
+}
+
 /// Análise de tendência
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrendAnalysis {
@@ -126,10 +272,10 @@ pub struct TrendAnalysis {
 
     // Estatísticas
     pub total_products: u64,
-    pub avg_price: f64,
-    pub median_price: f64,
-    pub min_price: f64,
-    pub max_price: f64,
+    pub avg_price: Money,
+    pub median_price: Money,
+    pub min_price: Money,
+    pub max_price: Money,
 
     // Insights
     pub top_sellers: Vec<String>,
@@ -149,6 +295,143 @@ pub enum CompetitionLevel {
     VeryHigh,
 }
 
+impl CompetitionLevel {
+    /// Identificador usado no banco (igual à serialização JSON)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompetitionLevel::Low => "low",
+            CompetitionLevel::Medium => "medium",
+            CompetitionLevel::High => "high",
+            CompetitionLevel::VeryHigh => "veryhigh",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "low" => Some(CompetitionLevel::Low),
+            "medium" => Some(CompetitionLevel::Medium),
+            "high" => Some(CompetitionLevel::High),
+            "veryhigh" => Some(CompetitionLevel::VeryHigh),
+            _ => None,
+        }
+    }
+}
+
+/// Tipo do alerta (`market_alerts.alert_type`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertType {
+    PriceDrop,
+    HighDemand,
+    LowCompetition,
+    /// A extração da busca de um marketplace caiu abaixo da linha de base:
+    /// provável mudança de layout
+    SelectorDrift,
+}
+
+impl AlertType {
+    /// Identificador usado no banco (igual à serialização JSON)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertType::PriceDrop => "price_drop",
+            AlertType::HighDemand => "high_demand",
+            AlertType::LowCompetition => "low_competition",
+            AlertType::SelectorDrift => "selector_drift",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "price_drop" => Some(AlertType::PriceDrop),
+            "high_demand" => Some(AlertType::HighDemand),
+            "low_competition" => Some(AlertType::LowCompetition),
+            "selector_drift" => Some(AlertType::SelectorDrift),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AlertSeverity {
+    /// Identificador usado no banco (igual à serialização JSON)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "info" => Some(AlertSeverity::Info),
+            "warning" => Some(AlertSeverity::Warning),
+            "critical" => Some(AlertSeverity::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Alerta para o tenant (`market_alerts`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketAlert {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub marketplace: Marketplace,
+    pub alert_type: AlertType,
+    pub product_id: Option<Uuid>,
+    pub title: String,
+    pub message: String,
+    pub severity: AlertSeverity,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Linha de base da extração de um marketplace (`selector_health`), mantida
+/// pelos jobs saudáveis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorBaseline {
+    pub marketplace: Marketplace,
+    /// Média móvel de produtos extraídos por card
+    pub parse_yield: f64,
+    /// Jobs que entraram na média
+    pub samples: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Última página de busca com produtos de um marketplace, por tenant
+/// (`selector_samples`), para depurar quando o layout mudar. A URL e o HTML
+/// trazem o termo buscado, por isso não ficam na linha de base global.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorSample {
+    pub tenant_id: Uuid,
+    pub marketplace: Marketplace,
+    pub job_id: Uuid,
+    pub url: String,
+    pub html: String,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Preferências do tenant no módulo (`tenant_settings`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenantSettings {
+    /// Moeda para a qual os preços são convertidos antes da análise
+    pub base_currency: Currency,
+}
+
+/// Response da carga de cotações (`POST /fx-rates`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadFxRatesResponse {
+    pub loaded: usize,
+}
+
 /// Request para criar job
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateJobRequest {
@@ -157,6 +440,10 @@ pub struct CreateJobRequest {
     pub category: Option<String>,
     pub max_pages: u32,
     pub priority: Option<u8>,
+    #[serde(default)]
+    pub enrich_details: bool,
+    #[serde(default)]
+    pub fetch_strategy: Option<FetchStrategy>,
 }
 
 /// Response com job criado
@@ -166,3 +453,116 @@ pub struct CreateJobResponse {
     pub status: JobStatus,
     pub message: String,
 }
+
+/// Job completo com a contagem de produtos coletados
+#[derive(Debug, Clone, Serialize)]
+pub struct JobDetails {
+    #[serde(flatten)]
+    pub job: ScrapingJob,
+    pub results_count: u64,
+}
+
+/// Filtros da listagem de produtos de um job
+#[derive(Debug, Clone, Default)]
+pub struct ProductFilter {
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    /// Nome ou id do vendedor (sem diferenciar maiúsculas no nome)
+    pub seller: Option<String>,
+    pub available: Option<bool>,
+}
+
+/// Filtros da listagem de jobs
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+    pub marketplace: Option<Marketplace>,
+    pub category: Option<String>,
+    /// Intervalo de `created_at`: início inclusivo, fim exclusivo
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    /// Texto buscado em `search_query` e `category`, sem diferenciar maiúsculas
+    pub search: Option<String>,
+}
+
+/// Ordenação da listagem de jobs (desempate sempre por `created_at` e `id`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobSort {
+    #[default]
+    CreatedAt,
+    Priority,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Posição na listagem de jobs: chave de ordenação do último job devolvido.
+/// Vai para o cliente como string opaca (`encode`/`decode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobCursor {
+    pub priority: u8,
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl JobCursor {
+    pub fn from_job(job: &ScrapingJob) -> Self {
+        Self {
+            priority: job.priority,
+            created_at: job.created_at,
+            id: job.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let raw = format!("{}|{}|{}", self.priority, self.created_at.to_rfc3339(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let mut parts = raw.splitn(3, '|');
+
+        Some(Self {
+            priority: parts.next()?.parse().ok()?,
+            created_at: DateTime::parse_from_rfc3339(parts.next()?).ok()?.with_timezone(&Utc),
+            id: parts.next()?.parse().ok()?,
+        })
+    }
+}
+
+/// Response do cancelamento de job
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelJobResponse {
+    pub job_id: Uuid,
+    /// `cancelled`, ou `running` enquanto o worker não chega à próxima página
+    pub status: JobStatus,
+    pub message: String,
+}
+
+/// Response do status do job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatusResponse {
+    pub status: JobStatus,
+    /// Tentativas já feitas (entregas a um worker)
+    pub attempts: u32,
+    /// Erro da última tentativa
+    pub last_error: Option<String>,
+    /// Quando a próxima tentativa volta para a fila, se o job aguarda retry
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+/// Métricas do pool de navegadores de um worker vivo
+/// (`GET /workers/browser-pool`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerBrowserMetrics {
+    pub worker_id: String,
+    #[serde(flatten)]
+    pub metrics: crate::scrapers::BrowserPoolMetrics,
+}
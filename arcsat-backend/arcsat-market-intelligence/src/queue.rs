@@ -1,95 +1,926 @@
-use arcsat_core::Result;
-use crate::models::{ScrapingJob, JobStatus};
+//! Fila de jobs no Redis com entrega confiável
+//!
+//! - `queue:priority:<n>`: jobs pendentes (entradas `<tenant_id>:<job_id>`)
+//! - `queue:processing:<worker>`: jobs em execução, movidos atomicamente pelo
+//!   `CLAIM_SCRIPT` do dequeue
+//! - `queue:notify`: um token por job que entra numa fila de prioridade; os
+//!   workers ociosos esperam nela com `BLPOP` em vez de consultar as filas
+//! - `queue:workers`: último heartbeat de cada worker; o reaper devolve à fila
+//!   os jobs de workers sem heartbeat dentro do visibility timeout
+//! - `queue:deadlines`: prazo de cada job entregue (score = unix timestamp);
+//!   o reaper também devolve os que passaram do prazo, mesmo com o worker
+//!   mandando heartbeat
+//! - `queue:delayed`: jobs aguardando retentativa (score = unix timestamp em
+//!   que voltam para a fila de prioridade)
+//! - `queue:workers:<worker>:browser_pool`: métricas do pool de navegadores
+//!   do worker (JSON), renovadas a cada heartbeat
+//! - `tenant:<id>:queue:dead`: jobs que excederam o máximo de tentativas
+
+use arcsat_core::{ArcsatError, Result, TenantId};
+use crate::models::{AttemptError, ScrapingJob, JobStatus, JobStatusResponse, WorkerBrowserMetrics};
+use crate::scrapers::BrowserPoolMetrics;
 use redis::aio::ConnectionManager;
-use redis::AsyncCommands;
-use tracing::{info, error};
+use redis::{AsyncCommands, Script};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+use tracing::{info, warn};
+
+/// Tempo que o hash de um job finalizado permanece no Redis
+const FINISHED_JOB_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Heartbeat de cada worker (score = unix timestamp em segundos)
+const WORKERS_KEY: &str = "queue:workers";
+
+/// Prioridades aceitas, da maior para a menor
+const PRIORITIES: std::ops::RangeInclusive<u8> = 1..=10;
+
+/// Avisa os workers ociosos que entrou job em alguma fila de prioridade
+const NOTIFY_KEY: &str = "queue:notify";
+
+/// Tokens acumulados em `queue:notify` (sobram quando o job sai por um
+/// worker que não estava esperando); acima disso são descartados
+const NOTIFY_LIMIT: usize = 1000;
+
+/// Espera mínima repassada ao `BLPOP`: abaixo de 1ms o Redis arredonda para
+/// zero, que significa esperar para sempre
+const MIN_BLOCKING_WAIT: Duration = Duration::from_millis(10);
+
+/// Prazo de cada job entregue (score = unix timestamp em segundos)
+const DEADLINES_KEY: &str = "queue:deadlines";
+
+/// Tempo padrão que um job pode ficar com o worker (ver `JobQueue::set_job_timeout`)
+pub const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Máximo de jobs vencidos recuperados por chamada de `reap_expired`
+const EXPIRE_BATCH: usize = 100;
+
+/// Tira o próximo job da fila de maior prioridade que não estiver vazia (a
+/// entrada mais antiga, na direita da lista), o coloca na lista de
+/// processamento do worker com prazo em `queue:deadlines` e conta a
+/// tentativa. Entrada sem hash do job sai da fila sem ser entregue.
+///
+/// KEYS[1] = lista de processamento, KEYS[2] = `queue:deadlines`,
+/// KEYS[3..] = filas de prioridade, da maior para a menor
+/// ARGV[1] = id do worker, ARGV[2] = prazo do job (unix timestamp)
+/// Retorna `[entrada, tentativas]` (tentativas = -1 se descartada) ou `nil`
+/// se todas as filas estiverem vazias
+const CLAIM_SCRIPT: &str = r#"
+for i = 3, #KEYS do
+    local entry = redis.call('RPOP', KEYS[i])
+    if entry then
+        local sep = string.find(entry, ':', 1, true)
+        if not sep then return {entry, -1} end
+
+        local key = 'tenant:' .. string.sub(entry, 1, sep - 1) .. ':job:' .. string.sub(entry, sep + 1)
+        if redis.call('HEXISTS', key, 'data') == 0 then return {entry, -1} end
+
+        redis.call('LPUSH', KEYS[1], entry)
+        redis.call('ZADD', KEYS[2], ARGV[2], entry)
+        redis.call('HSET', key, 'worker', ARGV[1])
+        return {entry, redis.call('HINCRBY', key, 'attempts', 1)}
+    end
+end
+return false
+"#;
+
+/// Jobs aguardando retentativa (score = unix timestamp em segundos)
+const DELAYED_KEY: &str = "queue:delayed";
+
+/// Máximo de jobs promovidos por chamada de `promote_due`
+const PROMOTE_BATCH: usize = 100;
+
+/// Move os jobs vencidos de `queue:delayed` para o início das suas filas de
+/// prioridade (saem depois dos que já estavam esperando).
+///
+/// KEYS[1] = `queue:delayed`, KEYS[2] = `queue:notify`
+/// ARGV[1] = agora (unix timestamp), ARGV[2] = máximo de jobs,
+/// ARGV[3] = limite de tokens em `queue:notify`
+/// Retorna quantos jobs foram promovidos
+const PROMOTE_SCRIPT: &str = r#"
+local due = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, tonumber(ARGV[2]))
+for _, entry in ipairs(due) do
+    redis.call('ZREM', KEYS[1], entry)
+
+    local sep = string.find(entry, ':', 1, true)
+    if sep then
+        local key = 'tenant:' .. string.sub(entry, 1, sep - 1) .. ':job:' .. string.sub(entry, sep + 1)
+        local priority = redis.call('HGET', key, 'priority') or '5'
+        redis.call('HDEL', key, 'retry_at')
+        redis.call('LPUSH', 'queue:priority:' .. priority, entry)
+        redis.call('LPUSH', KEYS[2], '1')
+    end
+end
+redis.call('LTRIM', KEYS[2], 0, tonumber(ARGV[3]) - 1)
+return #due
+"#;
+
+/// Cancela o job: se ainda está numa fila (de prioridade ou de retentativa)
+/// sai dela e vira `Cancelled`; se já foi entregue a um worker, marca
+/// `cancel_requested` para o worker interromper o scraping.
+///
+/// KEYS[1] = hash do job, KEYS[2] = lista de erros, KEYS[3] = `queue:delayed`
+/// ARGV[1] = entrada na fila, ARGV[2] = TTL de job finalizado
+/// Retorna `nil` (job inexistente), `"dequeued"`, `"requested"` ou o status
+/// do job já finalizado
+const CANCEL_SCRIPT: &str = r#"
+local status = redis.call('HGET', KEYS[1], 'status')
+if not status then return false end
+if status ~= 'Pending' and status ~= 'Running' then return status end
+
+if status == 'Pending' then
+    local priority = redis.call('HGET', KEYS[1], 'priority') or '5'
+    local removed = redis.call('LREM', 'queue:priority:' .. priority, 1, ARGV[1])
+        + redis.call('ZREM', KEYS[3], ARGV[1])
+    if removed > 0 then
+        redis.call('HSET', KEYS[1], 'status', 'Cancelled')
+        redis.call('HDEL', KEYS[1], 'retry_at')
+        redis.call('EXPIRE', KEYS[1], ARGV[2])
+        redis.call('EXPIRE', KEYS[2], ARGV[2])
+        return 'dequeued'
+    end
+end
+
+-- Em execução ou recém-saído da fila: o worker vê a marca e para
+redis.call('HSET', KEYS[1], 'cancel_requested', '1')
+return 'requested'
+"#;
+
+/// Esvazia a lista de processamento de um worker parado: cada job volta ao
+/// fim da sua fila de prioridade (é o próximo a sair) ou, sem tentativas
+/// restantes, vai para a dead-letter queue do tenant.
+///
+/// KEYS[1] = lista de processamento, KEYS[2] = `queue:workers`,
+/// KEYS[3] = `queue:notify`, KEYS[4] = `queue:deadlines`
+/// ARGV[1] = id do worker, ARGV[2] = máximo de tentativas,
+/// ARGV[3] = limite de tokens em `queue:notify`
+/// Retorna `[entrada, "requeued" | "dead", ...]`
+const REAP_SCRIPT: &str = r#"
+local reaped = {}
+while true do
+    local entry = redis.call('RPOP', KEYS[1])
+    if not entry then break end
+    redis.call('ZREM', KEYS[4], entry)
+
+    local sep = string.find(entry, ':', 1, true)
+    if sep then
+        local tenant = string.sub(entry, 1, sep - 1)
+        local job = string.sub(entry, sep + 1)
+        local key = 'tenant:' .. tenant .. ':job:' .. job
+        local attempts = tonumber(redis.call('HGET', key, 'attempts') or '0')
+
+        redis.call('HDEL', key, 'worker')
+        if attempts >= tonumber(ARGV[2]) then
+            redis.call('HSET', key, 'status', 'Failed')
+            redis.call('LPUSH', 'tenant:' .. tenant .. ':queue:dead', job)
+            table.insert(reaped, entry)
+            table.insert(reaped, 'dead')
+        else
+            local priority = redis.call('HGET', key, 'priority') or '5'
+            redis.call('HSET', key, 'status', 'Pending')
+            redis.call('RPUSH', 'queue:priority:' .. priority, entry)
+            redis.call('LPUSH', KEYS[3], '1')
+            table.insert(reaped, entry)
+            table.insert(reaped, 'requeued')
+        end
+    end
+end
+redis.call('LTRIM', KEYS[3], 0, tonumber(ARGV[3]) - 1)
+redis.call('ZREM', KEYS[2], ARGV[1])
+return reaped
+"#;
+
+/// Devolve os jobs de um worker que está desligando: como no `REAP_SCRIPT`,
+/// mas a tentativa em curso não conta (o job não falhou) e o job sempre volta
+/// para a fila, mesmo que estivesse na última tentativa.
+///
+/// KEYS[1] = lista de processamento, KEYS[2] = `queue:workers`,
+/// KEYS[3] = `queue:notify`, KEYS[4] = `queue:deadlines`
+/// ARGV[1] = id do worker, ARGV[2] = limite de tokens em `queue:notify`
+/// Retorna `[entrada, "requeued", ...]`
+const RELEASE_SCRIPT: &str = r#"
+local released = {}
+while true do
+    local entry = redis.call('RPOP', KEYS[1])
+    if not entry then break end
+    redis.call('ZREM', KEYS[4], entry)
+
+    local sep = string.find(entry, ':', 1, true)
+    if sep then
+        local key = 'tenant:' .. string.sub(entry, 1, sep - 1) .. ':job:' .. string.sub(entry, sep + 1)
+        if redis.call('HINCRBY', key, 'attempts', -1) < 0 then
+            redis.call('HSET', key, 'attempts', 0)
+        end
+
+        local priority = redis.call('HGET', key, 'priority') or '5'
+        redis.call('HDEL', key, 'worker')
+        redis.call('HSET', key, 'status', 'Pending')
+        redis.call('RPUSH', 'queue:priority:' .. priority, entry)
+        redis.call('LPUSH', KEYS[3], '1')
+        table.insert(released, entry)
+        table.insert(released, 'requeued')
+    end
+end
+redis.call('LTRIM', KEYS[3], 0, tonumber(ARGV[2]) - 1)
+redis.call('ZREM', KEYS[2], ARGV[1])
+return released
+"#;
+
+/// Recupera os jobs com prazo vencido: cada um sai da lista de processamento
+/// do worker que o recebeu e, como no `REAP_SCRIPT`, volta ao fim da sua fila
+/// de prioridade ou vai para a dead-letter queue. Prazo de job que já saiu da
+/// lista (ack no meio do caminho) só é descartado.
+///
+/// KEYS[1] = `queue:deadlines`, KEYS[2] = `queue:notify`
+/// ARGV[1] = prazo limite (unix timestamp), ARGV[2] = máximo de tentativas,
+/// ARGV[3] = limite de tokens em `queue:notify`, ARGV[4] = máximo de jobs
+/// Retorna `[entrada, "requeued" | "dead", ...]`
+const EXPIRE_SCRIPT: &str = r#"
+local reaped = {}
+local expired = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, tonumber(ARGV[4]))
+for _, entry in ipairs(expired) do
+    redis.call('ZREM', KEYS[1], entry)
+
+    local sep = string.find(entry, ':', 1, true)
+    if sep then
+        local tenant = string.sub(entry, 1, sep - 1)
+        local job = string.sub(entry, sep + 1)
+        local key = 'tenant:' .. tenant .. ':job:' .. job
+        local worker = redis.call('HGET', key, 'worker')
+
+        if worker and redis.call('LREM', 'queue:processing:' .. worker, 1, entry) > 0 then
+            local attempts = tonumber(redis.call('HGET', key, 'attempts') or '0')
+
+            redis.call('HDEL', key, 'worker')
+            if attempts >= tonumber(ARGV[2]) then
+                redis.call('HSET', key, 'status', 'Failed')
+                redis.call('LPUSH', 'tenant:' .. tenant .. ':queue:dead', job)
+                table.insert(reaped, entry)
+                table.insert(reaped, 'dead')
+            else
+                local priority = redis.call('HGET', key, 'priority') or '5'
+                redis.call('HSET', key, 'status', 'Pending')
+                redis.call('RPUSH', 'queue:priority:' .. priority, entry)
+                redis.call('LPUSH', KEYS[2], '1')
+                table.insert(reaped, entry)
+                table.insert(reaped, 'requeued')
+            end
+        end
+    end
+end
+redis.call('LTRIM', KEYS[2], 0, tonumber(ARGV[3]) - 1)
+return reaped
+"#;
+
+/// Hash do job, sempre sob o namespace do tenant
+fn job_key(tenant_id: TenantId, job_id: &str) -> String {
+    format!("tenant:{}:job:{}", tenant_id, job_id)
+}
+
+/// Erros de cada tentativa (`AttemptError` em JSON), só com `RPUSH`
+fn errors_key(tenant_id: TenantId, job_id: &str) -> String {
+    format!("tenant:{}:job:{}:errors", tenant_id, job_id)
+}
+
+fn priority_key(priority: u8) -> String {
+    format!("queue:priority:{}", priority)
+}
+
+fn processing_key(worker_id: &str) -> String {
+    format!("queue:processing:{}", worker_id)
+}
+
+fn browser_metrics_key(worker_id: &str) -> String {
+    format!("queue:workers:{}:browser_pool", worker_id)
+}
+
+fn dead_letter_key(tenant_id: TenantId) -> String {
+    format!("tenant:{}:queue:dead", tenant_id)
+}
+
+/// As filas de prioridade são compartilhadas pelos workers; cada entrada
+/// carrega o tenant para localizar o hash do job (`<tenant_id>:<job_id>`)
+fn queue_entry(tenant_id: TenantId, job_id: &str) -> String {
+    format!("{}:{}", tenant_id, job_id)
+}
+
+fn parse_queue_entry(entry: &str) -> Option<(TenantId, &str)> {
+    let (tenant_id, job_id) = entry.split_once(':')?;
+    Some((tenant_id.parse().ok()?, job_id))
+}
+
+/// Resposta do `REAP_SCRIPT`, do `RELEASE_SCRIPT` e do `EXPIRE_SCRIPT`:
+/// pares entrada/destino
+fn parse_reaped(reaped: &[String]) -> Vec<ReapedJob> {
+    reaped
+        .chunks(2)
+        .filter_map(|pair| {
+            let (tenant_id, job_id) = parse_queue_entry(pair.first()?)?;
+            Some(ReapedJob {
+                tenant_id,
+                job_id: job_id.to_string(),
+                dead: pair.get(1).map(String::as_str) == Some("dead"),
+            })
+        })
+        .collect()
+}
+
+fn parse_status(value: &str) -> JobStatus {
+    match value {
+        "Pending" => JobStatus::Pending,
+        "Running" => JobStatus::Running,
+        "Completed" => JobStatus::Completed,
+        "Failed" => JobStatus::Failed,
+        "Cancelled" => JobStatus::Cancelled,
+        _ => JobStatus::Pending,
+    }
+}
+
+/// Resultado de `JobQueue::cancel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cancellation {
+    /// Estava na fila e foi removido; já está `Cancelled`
+    Dequeued,
+    /// Está com um worker, que para antes da próxima página
+    Requested,
+    /// Já tinha terminado com este status
+    Finished(JobStatus),
+}
+
+/// Job recuperado pelo reaper (worker sem heartbeat ou prazo vencido)
+#[derive(Debug, Clone)]
+pub struct ReapedJob {
+    pub tenant_id: TenantId,
+    pub job_id: String,
+    /// `true` se foi para a dead-letter queue, `false` se voltou para a fila
+    pub dead: bool,
+}
 
 pub struct JobQueue {
+    client: redis::Client,
     redis: ConnectionManager,
+    /// Conexão exclusiva do `BLPOP`, que bloqueia a conexão enquanto espera
+    blocking: OnceCell<ConnectionManager>,
+    claim_script: Script,
+    cancel_script: Script,
+    reap_script: Script,
+    release_script: Script,
+    expire_script: Script,
+    promote_script: Script,
+    /// Prazo de cada job a partir da entrega (`queue:deadlines`)
+    job_timeout: Duration,
 }
 
 impl JobQueue {
     pub async fn new(redis_url: &str) -> Result<Self> {
-        let client = redis::Client::open(redis_url)
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        let client = redis::Client::open(redis_url)?;
+
+        let redis = ConnectionManager::new(client.clone()).await?;
+
+        Ok(Self {
+            client,
+            redis,
+            blocking: OnceCell::new(),
+            claim_script: Script::new(CLAIM_SCRIPT),
+            cancel_script: Script::new(CANCEL_SCRIPT),
+            reap_script: Script::new(REAP_SCRIPT),
+            release_script: Script::new(RELEASE_SCRIPT),
+            expire_script: Script::new(EXPIRE_SCRIPT),
+            promote_script: Script::new(PROMOTE_SCRIPT),
+            job_timeout: DEFAULT_JOB_TIMEOUT,
+        })
+    }
 
-        let redis = ConnectionManager::new(client).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+    /// Tempo que um job pode ficar com o worker a partir da entrega; depois
+    /// disso `reap_expired` o devolve à fila
+    pub fn set_job_timeout(&mut self, timeout: Duration) {
+        self.job_timeout = timeout;
+    }
 
-        Ok(Self { redis })
+    pub fn job_timeout(&self) -> Duration {
+        self.job_timeout
     }
 
-    pub async fn enqueue(&self, job: ScrapingJob) -> Result<String> {
+    pub async fn enqueue(&self, mut job: ScrapingJob) -> Result<String> {
         let mut conn = self.redis.clone();
         let job_id = job.id.to_string();
+
+        // Fora de 1-10 o job cairia numa fila que o dequeue não consulta
+        job.priority = job.priority.clamp(*PRIORITIES.start(), *PRIORITIES.end());
         let job_json = serde_json::to_string(&job)?;
 
         // Salvar job no Redis
-        conn.hset(&format!("job:{}", job_id), "data", &job_json).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        conn.hset_multiple::<_, _, _, ()>(
+            job_key(job.tenant_id, &job_id),
+            &[
+                ("data", job_json),
+                ("priority", job.priority.to_string()),
+                ("status", format!("{:?}", JobStatus::Pending)),
+            ],
+        ).await?;
 
-        // Adicionar à fila por prioridade
-        let queue_key = format!("queue:priority:{}", job.priority);
-        conn.lpush(&queue_key, &job_id).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        // Adicionar à fila por prioridade e acordar um worker ocioso
+        redis::pipe()
+            .atomic()
+            .lpush(priority_key(job.priority), queue_entry(job.tenant_id, &job_id))
+            .ignore()
+            .lpush(NOTIFY_KEY, "1")
+            .ignore()
+            .ltrim(NOTIFY_KEY, 0, NOTIFY_LIMIT as isize - 1)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
 
         info!("Job {} enqueued with priority {}", job_id, job.priority);
         Ok(job_id)
     }
 
-    pub async fn dequeue(&self, priority: u8) -> Result<Option<ScrapingJob>> {
+    /// Move o job mais antigo da fila de maior prioridade para a lista de
+    /// processamento do worker e conta a tentativa, no mesmo script.
+    ///
+    /// Com `wait` zero não bloqueia; caso contrário espera até `wait` por um
+    /// job, acordando pelos tokens de `queue:notify`. O job fica na lista até
+    /// `ack` ou até o reaper devolvê-lo à fila (worker sem heartbeat ou prazo
+    /// do job vencido).
+    pub async fn dequeue(&self, worker_id: &str, wait: Duration) -> Result<Option<ScrapingJob>> {
+        let mut conn = self.redis.clone();
+        let processing = processing_key(worker_id);
+        let wait_until = Instant::now() + wait;
+
+        let (entry, attempts) = loop {
+            // O token pode ser de um job que outro worker já levou: tenta de
+            // novo até o prazo
+            if let Some(claimed) = self.claim(worker_id, &processing).await? {
+                break claimed;
+            }
+
+            let remaining = wait_until.saturating_duration_since(Instant::now());
+            if remaining < MIN_BLOCKING_WAIT {
+                return Ok(None);
+            }
+
+            let mut blocking = self.blocking_connection().await?;
+            blocking
+                .blpop::<_, Option<(String, String)>>(NOTIFY_KEY, remaining.as_secs_f64())
+                .await?;
+        };
+
+        if attempts < 0 {
+            warn!("Discarding queue entry {:?}: job data not found", entry);
+            return Ok(None);
+        }
+
+        let loaded = match parse_queue_entry(&entry) {
+            Some((tenant_id, job_id)) => self.load_job(tenant_id, job_id).await?,
+            None => None,
+        };
+
+        // O hash sumiu entre o claim e a leitura
+        let Some(mut job) = loaded else {
+            warn!("Discarding queue entry {:?}: job data not found", entry);
+            redis::pipe()
+                .atomic()
+                .lrem(&processing, 1, &entry)
+                .ignore()
+                .zrem(DEADLINES_KEY, &entry)
+                .ignore()
+                .query_async::<_, ()>(&mut conn)
+                .await?;
+            return Ok(None);
+        };
+
+        job.attempts = attempts as u32;
+
+        Ok(Some(job))
+    }
+
+    /// Executa o `CLAIM_SCRIPT` sobre todas as filas de prioridade
+    async fn claim(&self, worker_id: &str, processing: &str) -> Result<Option<(String, i64)>> {
+        let mut conn = self.redis.clone();
+
+        let deadline = chrono::Utc::now().timestamp() + self.job_timeout.as_secs() as i64;
+
+        let mut invocation = self.claim_script.key(processing);
+        invocation.key(DEADLINES_KEY);
+        for priority in PRIORITIES.rev() {
+            invocation.key(priority_key(priority));
+        }
+        invocation.arg(worker_id).arg(deadline);
+
+        Ok(invocation.invoke_async(&mut conn).await?)
+    }
+
+    /// Confirma o fim do processamento (com sucesso ou não): o job sai da
+    /// lista de processamento e não será mais devolvido pelo reaper
+    pub async fn ack(&self, worker_id: &str, tenant_id: TenantId, job_id: &str) -> Result<()> {
+        let mut conn = self.redis.clone();
+
+        let entry = queue_entry(tenant_id, job_id);
+
+        redis::pipe()
+            .atomic()
+            .lrem(processing_key(worker_id), 1, &entry)
+            .ignore()
+            .zrem(DEADLINES_KEY, &entry)
+            .ignore()
+            .hdel(job_key(tenant_id, job_id), "worker")
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Tira o job da lista de processamento e o agenda para voltar à fila
+    /// depois de `delay` (ver `promote_due`). Substitui o `ack`.
+    pub async fn retry_later(&self, worker_id: &str, tenant_id: TenantId, job_id: &str, delay: Duration) -> Result<()> {
+        let mut conn = self.redis.clone();
+
+        let entry = queue_entry(tenant_id, job_id);
+        let key = job_key(tenant_id, job_id);
+        let run_at = chrono::Utc::now().timestamp() + delay.as_secs() as i64;
+
+        redis::pipe()
+            .atomic()
+            .lrem(processing_key(worker_id), 1, &entry)
+            .ignore()
+            .zrem(DEADLINES_KEY, &entry)
+            .ignore()
+            .hdel(&key, "worker")
+            .ignore()
+            .hset_multiple(&key, &[("status", format!("{:?}", JobStatus::Pending)), ("retry_at", run_at.to_string())])
+            .ignore()
+            .zadd(DELAYED_KEY, &entry, run_at)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        info!("Job {} scheduled for retry in {}s", job_id, delay.as_secs());
+        Ok(())
+    }
+
+    /// Tira o job da lista de processamento direto para a dead-letter queue
+    /// do tenant. Substitui o `ack`.
+    pub async fn dead_letter(&self, worker_id: &str, tenant_id: TenantId, job_id: &str) -> Result<()> {
+        let mut conn = self.redis.clone();
+
+        let entry = queue_entry(tenant_id, job_id);
+        let key = job_key(tenant_id, job_id);
+
+        redis::pipe()
+            .atomic()
+            .lrem(processing_key(worker_id), 1, &entry)
+            .ignore()
+            .zrem(DEADLINES_KEY, &entry)
+            .ignore()
+            .hdel(&key, "worker")
+            .ignore()
+            .hset(&key, "status", format!("{:?}", JobStatus::Failed))
+            .ignore()
+            .lpush(dead_letter_key(tenant_id), job_id)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Devolve às filas de prioridade os jobs cuja retentativa venceu.
+    /// Retorna quantos foram promovidos.
+    pub async fn promote_due(&self) -> Result<usize> {
+        let mut conn = self.redis.clone();
+
+        let promoted: usize = self.promote_script
+            .key(DELAYED_KEY)
+            .key(NOTIFY_KEY)
+            .arg(chrono::Utc::now().timestamp())
+            .arg(PROMOTE_BATCH)
+            .arg(NOTIFY_LIMIT)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(promoted)
+    }
+
+    /// Job completo (dados, status, tentativas e erros) ou `None` se o hash
+    /// não existir no namespace do tenant
+    pub async fn load_job(&self, tenant_id: TenantId, job_id: &str) -> Result<Option<ScrapingJob>> {
         let mut conn = self.redis.clone();
-        let queue_key = format!("queue:priority:{}", priority);
 
-        let job_id: Option<String> = conn.rpop(&queue_key, None).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        let fields: HashMap<String, String> = conn.hgetall(job_key(tenant_id, job_id)).await?;
+        let Some(data) = fields.get("data") else {
+            return Ok(None);
+        };
 
-        if let Some(job_id) = job_id {
-            let job_json: String = conn.hget(&format!("job:{}", job_id), "data").await
-                .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        let mut job: ScrapingJob = serde_json::from_str(data)?;
 
-            let job: ScrapingJob = serde_json::from_str(&job_json)?;
-            Ok(Some(job))
-        } else {
-            Ok(None)
+        if let Some(status) = fields.get("status") {
+            job.status = parse_status(status);
         }
+        if let Some(attempts) = fields.get("attempts") {
+            job.attempts = attempts.parse().unwrap_or(job.attempts);
+        }
+
+        let errors: Vec<String> = conn.lrange(errors_key(tenant_id, job_id), 0, -1).await?;
+        job.error_history = errors
+            .iter()
+            .map(|error| serde_json::from_str(error))
+            .collect::<std::result::Result<_, _>>()?;
+        job.error = job.error_history.last().map(|e| e.error.clone()).or(job.error);
+
+        Ok(Some(job))
+    }
+
+    pub async fn record_error(&self, tenant_id: TenantId, job_id: &str, error: &AttemptError) -> Result<()> {
+        let mut conn = self.redis.clone();
+
+        conn.rpush::<_, _, ()>(errors_key(tenant_id, job_id), serde_json::to_string(error)?).await?;
+
+        Ok(())
+    }
+
+    /// Registra que o worker está vivo; deve ser chamado bem abaixo do
+    /// visibility timeout
+    pub async fn heartbeat(&self, worker_id: &str) -> Result<()> {
+        let mut conn = self.redis.clone();
+
+        conn.zadd::<_, _, _, ()>(WORKERS_KEY, worker_id, chrono::Utc::now().timestamp()).await?;
+
+        Ok(())
     }
 
-    pub async fn update_status(&self, job_id: &str, status: JobStatus) -> Result<()> {
+    /// Publica as métricas do pool de navegadores do worker; expiram em `ttl`
+    /// se o worker parar
+    pub async fn publish_browser_metrics(
+        &self,
+        worker_id: &str,
+        metrics: &BrowserPoolMetrics,
+        ttl: Duration,
+    ) -> Result<()> {
         let mut conn = self.redis.clone();
 
-        conn.hset(&format!("job:{}", job_id), "status", format!("{:?}", status)).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        conn.set_ex::<_, _, ()>(browser_metrics_key(worker_id), serde_json::to_string(metrics)?, ttl.as_secs().max(1))
+            .await?;
 
         Ok(())
     }
 
-    pub async fn get_status(&self, job_id: &str) -> Result<JobStatus> {
+    /// Métricas do pool de navegadores dos workers com heartbeat
+    pub async fn browser_metrics(&self) -> Result<Vec<WorkerBrowserMetrics>> {
+        let mut conn = self.redis.clone();
+
+        let workers: Vec<String> = conn.zrange(WORKERS_KEY, 0, -1).await?;
+
+        let mut all = Vec::with_capacity(workers.len());
+        for worker_id in workers {
+            let raw: Option<String> = conn.get(browser_metrics_key(&worker_id)).await?;
+            if let Some(raw) = raw {
+                all.push(WorkerBrowserMetrics {
+                    worker_id,
+                    metrics: serde_json::from_str(&raw)?,
+                });
+            }
+        }
+
+        Ok(all)
+    }
+
+    /// Devolve à fila (ou à dead-letter queue) os jobs de todos os workers sem
+    /// heartbeat há mais de `visibility_timeout`
+    pub async fn reap_stalled(&self, visibility_timeout: Duration, max_attempts: u32) -> Result<Vec<ReapedJob>> {
+        let mut conn = self.redis.clone();
+
+        let deadline = chrono::Utc::now().timestamp() - visibility_timeout.as_secs() as i64;
+        let stalled: Vec<String> = conn.zrangebyscore(WORKERS_KEY, "-inf", deadline).await?;
+
+        let mut reaped = Vec::new();
+        for worker_id in stalled {
+            warn!("Worker {} missed its heartbeat, reaping its jobs", worker_id);
+            reaped.extend(self.reap_worker(&worker_id, max_attempts).await?);
+        }
+
+        Ok(reaped)
+    }
+
+    /// Esvazia a lista de processamento do worker (ver `REAP_SCRIPT`) e o
+    /// remove de `queue:workers`
+    pub async fn reap_worker(&self, worker_id: &str, max_attempts: u32) -> Result<Vec<ReapedJob>> {
+        let mut conn = self.redis.clone();
+
+        let reaped: Vec<String> = self.reap_script
+            .key(processing_key(worker_id))
+            .key(WORKERS_KEY)
+            .key(NOTIFY_KEY)
+            .key(DEADLINES_KEY)
+            .arg(worker_id)
+            .arg(max_attempts)
+            .arg(NOTIFY_LIMIT)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(parse_reaped(&reaped))
+    }
+
+    /// Devolve à fila os jobs do worker que está desligando, sem contar a
+    /// tentativa em curso (ver `RELEASE_SCRIPT`), e o remove de `queue:workers`
+    pub async fn release_worker(&self, worker_id: &str) -> Result<Vec<ReapedJob>> {
+        let mut conn = self.redis.clone();
+
+        let released: Vec<String> = self.release_script
+            .key(processing_key(worker_id))
+            .key(WORKERS_KEY)
+            .key(NOTIFY_KEY)
+            .key(DEADLINES_KEY)
+            .arg(worker_id)
+            .arg(NOTIFY_LIMIT)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(parse_reaped(&released))
+    }
+
+    /// Devolve à fila (ou à dead-letter queue) os jobs que passaram do prazo
+    /// há mais de `grace` (ver `EXPIRE_SCRIPT`). Pega o job travado dentro de
+    /// um worker que continua mandando heartbeat.
+    pub async fn reap_expired(&self, grace: Duration, max_attempts: u32) -> Result<Vec<ReapedJob>> {
         let mut conn = self.redis.clone();
 
-        let status_str: String = conn.hget(&format!("job:{}", job_id), "status").await
-            .map_err(|e| arcsat_core::ArcsatError::NotFound(format!("Job {} not found", job_id)))?;
+        let cutoff = chrono::Utc::now().timestamp() - grace.as_secs() as i64;
 
-        match status_str.as_str() {
-            "Pending" => Ok(JobStatus::Pending),
-            "Running" => Ok(JobStatus::Running),
-            "Completed" => Ok(JobStatus::Completed),
-            "Failed" => Ok(JobStatus::Failed),
-            "Cancelled" => Ok(JobStatus::Cancelled),
-            _ => Ok(JobStatus::Pending),
+        let reaped: Vec<String> = self.expire_script
+            .key(DEADLINES_KEY)
+            .key(NOTIFY_KEY)
+            .arg(cutoff)
+            .arg(max_attempts)
+            .arg(NOTIFY_LIMIT)
+            .arg(EXPIRE_BATCH)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(parse_reaped(&reaped))
+    }
+
+    /// Jobs do tenant na dead-letter queue, do mais recente ao mais antigo
+    pub async fn dead_letters(&self, tenant_id: TenantId) -> Result<Vec<ScrapingJob>> {
+        let mut conn = self.redis.clone();
+
+        let job_ids: Vec<String> = conn.lrange(dead_letter_key(tenant_id), 0, -1).await?;
+
+        let mut jobs = Vec::with_capacity(job_ids.len());
+        for job_id in job_ids {
+            if let Some(job) = self.load_job(tenant_id, &job_id).await? {
+                jobs.push(job);
+            }
+        }
+
+        Ok(jobs)
+    }
+
+    /// Tira o job da dead-letter queue e o reenfileira com as tentativas
+    /// zeradas e sem pedido de cancelamento, worker ou retentativa agendada
+    /// da execução anterior (o histórico de erros é mantido)
+    pub async fn replay_dead_letter(&self, tenant_id: TenantId, job_id: &str) -> Result<ScrapingJob> {
+        let mut conn = self.redis.clone();
+
+        let removed: i64 = conn.lrem(dead_letter_key(tenant_id), 1, job_id).await?;
+        if removed == 0 {
+            return Err(ArcsatError::JobNotFound(job_id.to_string()));
+        }
+
+        let Some(mut job) = self.load_job(tenant_id, job_id).await? else {
+            return Err(ArcsatError::JobNotFound(job_id.to_string()));
+        };
+
+        redis::pipe()
+            .atomic()
+            .hset_multiple(job_key(tenant_id, job_id), &[("attempts", "0"), ("status", "Pending")])
+            .ignore()
+            .hdel(job_key(tenant_id, job_id), &["cancel_requested", "worker", "retry_at"])
+            .ignore()
+            .lpush(priority_key(job.priority), queue_entry(tenant_id, job_id))
+            .ignore()
+            .lpush(NOTIFY_KEY, "1")
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        job.attempts = 0;
+        job.status = JobStatus::Pending;
+        job.started_at = None;
+        job.completed_at = None;
+
+        info!("Job {} replayed from the dead-letter queue", job_id);
+        Ok(job)
+    }
+
+    /// Cancela o job do tenant (ver `CANCEL_SCRIPT`); job de outro tenant é
+    /// `JobNotFound`
+    pub async fn cancel(&self, tenant_id: TenantId, job_id: &str) -> Result<Cancellation> {
+        let mut conn = self.redis.clone();
+
+        let outcome: Option<String> = self.cancel_script
+            .key(job_key(tenant_id, job_id))
+            .key(errors_key(tenant_id, job_id))
+            .key(DELAYED_KEY)
+            .arg(queue_entry(tenant_id, job_id))
+            .arg(FINISHED_JOB_TTL_SECS)
+            .invoke_async(&mut conn)
+            .await?;
+
+        match outcome.as_deref() {
+            None => Err(ArcsatError::JobNotFound(job_id.to_string())),
+            Some("dequeued") => {
+                info!("Job {} cancelled before running", job_id);
+                Ok(Cancellation::Dequeued)
+            }
+            Some("requested") => {
+                info!("Cancellation requested for running job {}", job_id);
+                Ok(Cancellation::Requested)
+            }
+            Some(status) => Ok(Cancellation::Finished(parse_status(status))),
+        }
+    }
+
+    /// Jobs na lista de processamento do worker com cancelamento pedido
+    pub async fn cancel_requests(&self, worker_id: &str) -> Result<Vec<Uuid>> {
+        let mut conn = self.redis.clone();
+
+        let entries: Vec<String> = conn.lrange(processing_key(worker_id), 0, -1).await?;
+        let jobs: Vec<(TenantId, &str)> = entries.iter().filter_map(|e| parse_queue_entry(e)).collect();
+        if jobs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for (tenant_id, job_id) in &jobs {
+            pipe.hexists(job_key(*tenant_id, job_id), "cancel_requested");
+        }
+        let flags: Vec<bool> = pipe.query_async(&mut conn).await?;
+
+        Ok(jobs
+            .iter()
+            .zip(flags)
+            .filter(|(_, requested)| *requested)
+            .filter_map(|((_, job_id), _)| job_id.parse().ok())
+            .collect())
+    }
+
+    pub async fn update_status(&self, tenant_id: TenantId, job_id: &str, status: JobStatus) -> Result<()> {
+        let mut conn = self.redis.clone();
+
+        let key = job_key(tenant_id, job_id);
+
+        conn.hset::<_, _, _, ()>(&key, "status", format!("{:?}", status)).await?;
+
+        // Job finalizado: o Postgres passa a ser a fonte da verdade
+        if matches!(status, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled) {
+            conn.expire::<_, ()>(&key, FINISHED_JOB_TTL_SECS).await?;
+            conn.expire::<_, ()>(errors_key(tenant_id, job_id), FINISHED_JOB_TTL_SECS).await?;
         }
+
+        Ok(())
     }
 
-    pub async fn save_results(&self, job_id: &str, products: &[crate::models::ScrapedProduct]) -> Result<()> {
+    /// Status do job no namespace do tenant; job de outro tenant é `JobNotFound`
+    pub async fn get_status(&self, tenant_id: TenantId, job_id: &str) -> Result<JobStatusResponse> {
         let mut conn = self.redis.clone();
-        let results_json = serde_json::to_string(products)?;
 
-        conn.hset(&format!("job:{}", job_id), "results", results_json).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        let (status, attempts, retry_at): (Option<String>, Option<u32>, Option<i64>) = conn
+            .hget(job_key(tenant_id, job_id), &["status", "attempts", "retry_at"])
+            .await?;
+
+        let status = status.ok_or_else(|| ArcsatError::JobNotFound(job_id.to_string()))?;
+
+        let last_error: Option<String> = conn.lindex(errors_key(tenant_id, job_id), -1).await?;
+        let last_error = last_error
+            .map(|error| serde_json::from_str::<AttemptError>(&error))
+            .transpose()?;
+
+        Ok(JobStatusResponse {
+            status: parse_status(&status),
+            attempts: attempts.unwrap_or(0),
+            last_error: last_error.map(|e| e.error),
+            next_retry_at: retry_at.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
+        })
+    }
+
+    /// Registra apenas a contagem de resultados; os produtos em si ficam no
+    /// Postgres (`JobRepository::save_products`)
+    pub async fn save_results(&self, tenant_id: TenantId, job_id: &str, results_count: usize) -> Result<()> {
+        let mut conn = self.redis.clone();
 
-        conn.hset(&format!("job:{}", job_id), "results_count", products.len()).await
-            .map_err(|e| arcsat_core::ArcsatError::Internal(e.to_string()))?;
+        conn.hset::<_, _, _, ()>(job_key(tenant_id, job_id), "results_count", results_count).await?;
 
         Ok(())
     }
+
+    async fn blocking_connection(&self) -> Result<ConnectionManager> {
+        let conn = self
+            .blocking
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await?;
+
+        Ok(conn.clone())
+    }
 }
@@ -0,0 +1,959 @@
+//! Persistência em PostgreSQL
+//!
+//! O Redis guarda apenas o estado transitório da fila; jobs, produtos e
+//! análises ficam nas tabelas de `migrations/001_market_intelligence.up.sql`.
+//!
+//! Toda consulta roda numa transação aberta por `begin`, que define
+//! `app.tenant_id` para as policies de RLS (`002_tenant_isolation.up.sql`);
+//! os filtros `WHERE tenant_id` continuam explícitos nas queries.
+
+use arcsat_core::{ArcsatError, Currency, Money, Result, TenantId};
+use crate::fx::{FxRate, FxRates};
+use crate::models::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Colunas de `scraped_products` no formato de `ScrapedProduct` (preços
+/// como DECIMAL, as notas como float8)
+const PRODUCT_COLUMNS: &str = "id, job_id, marketplace, external_id, title, price, currency, url, \
+    image_url, list_price, pix_price, max_price, installments, seller_name, seller_id, \
+    seller_rating::float8 AS seller_rating, sales_rank, rating::float8 AS rating, num_reviews, \
+    availability, category, brand, scraped_at, extra";
+
+/// Linhas por INSERT em lote (cada produto usa 25 parâmetros; limite do Postgres é 65535)
+const PRODUCT_BATCH_SIZE: usize = 500;
+
+/// Cotações por INSERT em lote (4 parâmetros cada)
+const FX_BATCH_SIZE: usize = 5000;
+
+#[derive(Clone)]
+pub struct JobRepository {
+    pool: PgPool,
+}
+
+impl JobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Abre uma transação restrita ao tenant
+    async fn begin(&self, tenant_id: TenantId) -> Result<Transaction<'static, Postgres>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+            .bind(tenant_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        Ok(tx)
+    }
+
+    /// Insere o job ou atualiza seu estado (status, timestamps, tentativas e erros)
+    pub async fn save_job(&self, job: &ScrapingJob) -> Result<()> {
+        let mut tx = self.begin(job.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO scraping_jobs (
+                id, tenant_id, marketplace, search_query, category, max_pages,
+                priority, status, created_at, started_at, completed_at, error,
+                attempts, error_history, enrich_details, fetch_strategy
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (id) DO UPDATE SET
+                status = EXCLUDED.status,
+                started_at = EXCLUDED.started_at,
+                completed_at = EXCLUDED.completed_at,
+                error = EXCLUDED.error,
+                attempts = EXCLUDED.attempts,
+                error_history = EXCLUDED.error_history
+            WHERE scraping_jobs.tenant_id = EXCLUDED.tenant_id
+            "#,
+        )
+        .bind(job.id)
+        .bind(job.tenant_id)
+        .bind(job.marketplace.as_str())
+        .bind(&job.search_query)
+        .bind(&job.category)
+        .bind(job.max_pages as i32)
+        .bind(job.priority as i16)
+        .bind(job.status.as_str())
+        .bind(job.created_at)
+        .bind(job.started_at)
+        .bind(job.completed_at)
+        .bind(&job.error)
+        .bind(job.attempts as i32)
+        .bind(serde_json::to_value(&job.error_history)?)
+        .bind(job.enrich_details)
+        .bind(job.fetch_strategy.map(|strategy| strategy.as_str()))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Job completo; `None` se o job não existir ou for de outro tenant
+    pub async fn get_job(&self, tenant_id: TenantId, job_id: Uuid) -> Result<Option<ScrapingJob>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT id, tenant_id, marketplace, search_query, category, max_pages, priority,
+                   status, created_at, started_at, completed_at, error, attempts, error_history,
+                   enrich_details, fetch_strategy
+            FROM scraping_jobs
+            WHERE tenant_id = $1 AND id = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(job_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        row.map(|row| job_from_row(&row)).transpose()
+    }
+
+    /// Jobs do tenant que atendem aos filtros, na ordem pedida, a partir do
+    /// cursor (exclusivo), cada um com a contagem de produtos
+    pub async fn list_jobs(
+        &self,
+        tenant_id: TenantId,
+        filter: &JobFilter,
+        sort: JobSort,
+        order: SortOrder,
+        after: Option<JobCursor>,
+        limit: u32,
+    ) -> Result<Vec<JobDetails>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new(
+            "SELECT j.id, j.tenant_id, j.marketplace, j.search_query, j.category, j.max_pages, \
+                j.priority, j.status, j.created_at, j.started_at, j.completed_at, j.error, \
+                j.attempts, j.error_history, j.enrich_details, j.fetch_strategy, \
+                (SELECT COUNT(*) FROM scraped_products p \
+                    WHERE p.tenant_id = j.tenant_id AND p.job_id = j.id) AS results_count \
+             FROM scraping_jobs j WHERE j.tenant_id = ",
+        );
+        query.push_bind(tenant_id);
+
+        if let Some(status) = filter.status {
+            query.push(" AND j.status = ").push_bind(status.as_str());
+        }
+        if let Some(marketplace) = filter.marketplace {
+            query.push(" AND j.marketplace = ").push_bind(marketplace.as_str());
+        }
+        if let Some(category) = filter.category.clone() {
+            query.push(" AND j.category = ").push_bind(category);
+        }
+        if let Some(created_from) = filter.created_from {
+            query.push(" AND j.created_at >= ").push_bind(created_from);
+        }
+        if let Some(created_to) = filter.created_to {
+            query.push(" AND j.created_at < ").push_bind(created_to);
+        }
+        if let Some(search) = &filter.search {
+            let pattern = format!("%{}%", escape_like(search));
+            query
+                .push(" AND (j.search_query ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR j.category ILIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+
+        // Keyset: compara a tupla de ordenação com a do último job da página anterior
+        let (columns, direction) = match (sort, order) {
+            (JobSort::CreatedAt, SortOrder::Desc) => ("j.created_at DESC, j.id DESC", "<"),
+            (JobSort::CreatedAt, SortOrder::Asc) => ("j.created_at ASC, j.id ASC", ">"),
+            (JobSort::Priority, SortOrder::Desc) => ("j.priority DESC, j.created_at DESC, j.id DESC", "<"),
+            (JobSort::Priority, SortOrder::Asc) => ("j.priority ASC, j.created_at ASC, j.id ASC", ">"),
+        };
+
+        if let Some(cursor) = after {
+            match sort {
+                JobSort::CreatedAt => {
+                    query.push(format!(" AND (j.created_at, j.id) {} (", direction))
+                        .push_bind(cursor.created_at)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push(")");
+                }
+                JobSort::Priority => {
+                    query.push(format!(" AND (j.priority, j.created_at, j.id) {} (", direction))
+                        .push_bind(cursor.priority as i16)
+                        .push(", ")
+                        .push_bind(cursor.created_at)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push(")");
+                }
+            }
+        }
+
+        query.push(" ORDER BY ").push(columns).push(" LIMIT ").push_bind(limit as i64);
+
+        let rows = query.build().fetch_all(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(JobDetails {
+                    job: job_from_row(row)?,
+                    results_count: row.try_get::<i64, _>("results_count")? as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Quantidade de produtos gravados para o job
+    pub async fn count_products(&self, tenant_id: TenantId, job_id: Uuid) -> Result<u64> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM scraped_products WHERE tenant_id = $1 AND job_id = $2",
+        )
+        .bind(tenant_id)
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(count as u64)
+    }
+
+    /// Página `page` (a partir de 1) dos produtos do job, do mais barato ao
+    /// mais caro, e o total que atende aos filtros. `None` se o job não
+    /// existir ou for de outro tenant.
+    pub async fn list_products(
+        &self,
+        tenant_id: TenantId,
+        job_id: Uuid,
+        filter: &ProductFilter,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Option<(Vec<ScrapedProduct>, u64)>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM scraping_jobs WHERE tenant_id = $1 AND id = $2)",
+        )
+        .bind(tenant_id)
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if !exists {
+            return Ok(None);
+        }
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM scraped_products");
+        push_product_filter(&mut count, tenant_id, job_id, filter);
+        let total: i64 = count.build_query_scalar().fetch_one(&mut *tx).await?;
+
+        let mut select = QueryBuilder::new(format!("SELECT {} FROM scraped_products", PRODUCT_COLUMNS));
+        push_product_filter(&mut select, tenant_id, job_id, filter);
+        select
+            .push(" ORDER BY price, id LIMIT ")
+            .push_bind(per_page as i64)
+            .push(" OFFSET ")
+            .push_bind(page.saturating_sub(1) as i64 * per_page as i64);
+
+        let rows = select.build().fetch_all(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        let products = rows.iter().map(product_from_row).collect::<Result<_>>()?;
+        Ok(Some((products, total as u64)))
+    }
+
+    /// Anúncios mais recentes do tenant na categoria, de todos os jobs
+    /// (concorrentes para os insights do CRM)
+    pub async fn list_category_products(
+        &self,
+        tenant_id: TenantId,
+        category: &str,
+        limit: u32,
+    ) -> Result<Vec<ScrapedProduct>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM scraped_products \
+             WHERE tenant_id = $1 AND category = $2 \
+             ORDER BY scraped_at DESC, id LIMIT $3",
+            PRODUCT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(category)
+        .bind(limit as i64)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        rows.iter().map(product_from_row).collect()
+    }
+
+    /// Status do job; `None` se o job não existir ou for de outro tenant
+    pub async fn get_status(&self, tenant_id: TenantId, job_id: Uuid) -> Result<Option<JobStatusResponse>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let row: Option<(String, i32, Option<String>)> = sqlx::query_as(
+            "SELECT status, attempts, error FROM scraping_jobs WHERE tenant_id = $1 AND id = $2",
+        )
+        .bind(tenant_id)
+        .bind(job_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(row.and_then(|(status, attempts, error)| {
+            Some(JobStatusResponse {
+                status: JobStatus::from_db(&status)?,
+                attempts: attempts as u32,
+                last_error: error,
+                // A agenda de retentativas só existe no Redis
+                next_retry_at: None,
+            })
+        }))
+    }
+
+    /// Grava os produtos do job, fazendo upsert em `(job_id, external_id)`.
+    /// Produtos sem `external_id` ficam de fora: todos cairiam na mesma linha.
+    ///
+    /// Retorna o número de linhas distintas gravadas.
+    pub async fn save_products(&self, tenant_id: TenantId, products: &[ScrapedProduct]) -> Result<usize> {
+        // O mesmo anúncio pode aparecer duas vezes na busca (ex: patrocinado);
+        // um único INSERT não pode atualizar a mesma linha duas vezes.
+        let mut unique: HashMap<(Uuid, &str), &ScrapedProduct> = HashMap::new();
+        for product in products.iter().filter(|product| !product.external_id.is_empty()) {
+            unique.insert((product.job_id, product.external_id.as_str()), product);
+        }
+        let unique: Vec<&ScrapedProduct> = unique.into_values().collect();
+
+        let mut tx = self.begin(tenant_id).await?;
+
+        for batch in unique.chunks(PRODUCT_BATCH_SIZE) {
+            let mut query: QueryBuilder<Postgres> = QueryBuilder::new(
+                "INSERT INTO scraped_products (
+                    id, tenant_id, job_id, marketplace, external_id, title, price, currency, url,
+                    image_url, list_price, pix_price, max_price, installments, seller_name,
+                    seller_id, seller_rating, sales_rank, rating, num_reviews, availability,
+                    category, brand, scraped_at, extra
+                ) ",
+            );
+
+            query.push_values(batch, |mut row, product| {
+                row.push_bind(product.id)
+                    .push_bind(tenant_id)
+                    .push_bind(product.job_id)
+                    .push_bind(product.marketplace.as_str())
+                    .push_bind(&product.external_id)
+                    .push_bind(&product.title)
+                    .push_bind(product.price.amount())
+                    .push_bind(product.price.currency())
+                    .push_bind(&product.url)
+                    .push_bind(&product.image_url)
+                    .push_bind(product.pricing.list_price.map(|m| m.amount()))
+                    .push_bind(product.pricing.pix_price.map(|m| m.amount()))
+                    .push_bind(product.pricing.max_price.map(|m| m.amount()))
+                    .push_bind(product.pricing.installments.map(Json))
+                    .push_bind(&product.seller_name)
+                    .push_bind(&product.seller_id)
+                    .push_bind(product.seller_rating)
+                    .push_bind(product.sales_rank)
+                    .push_bind(product.rating)
+                    .push_bind(product.num_reviews)
+                    .push_bind(product.availability)
+                    .push_bind(&product.category)
+                    .push_bind(&product.brand)
+                    .push_bind(product.scraped_at)
+                    .push_bind(&product.extra);
+            });
+
+            query.push(
+                " ON CONFLICT (job_id, external_id) DO UPDATE SET
+                    title = EXCLUDED.title,
+                    price = EXCLUDED.price,
+                    currency = EXCLUDED.currency,
+                    url = EXCLUDED.url,
+                    image_url = EXCLUDED.image_url,
+                    list_price = EXCLUDED.list_price,
+                    pix_price = EXCLUDED.pix_price,
+                    max_price = EXCLUDED.max_price,
+                    installments = EXCLUDED.installments,
+                    seller_name = EXCLUDED.seller_name,
+                    seller_id = EXCLUDED.seller_id,
+                    seller_rating = EXCLUDED.seller_rating,
+                    sales_rank = EXCLUDED.sales_rank,
+                    rating = EXCLUDED.rating,
+                    num_reviews = EXCLUDED.num_reviews,
+                    availability = EXCLUDED.availability,
+                    category = EXCLUDED.category,
+                    brand = EXCLUDED.brand,
+                    scraped_at = EXCLUDED.scraped_at,
+                    extra = EXCLUDED.extra
+                WHERE scraped_products.tenant_id = EXCLUDED.tenant_id",
+            );
+
+            query.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(unique.len())
+    }
+
+    pub async fn save_analysis(&self, analysis: &TrendAnalysis) -> Result<()> {
+        let mut tx = self.begin(analysis.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO trend_analyses (
+                id, tenant_id, marketplace, category, period_start, period_end,
+                total_products, avg_price, median_price, min_price, max_price, currency,
+                top_sellers, trending_keywords, growth_rate, competition_level, analyzed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            "#,
+        )
+        .bind(analysis.id)
+        .bind(analysis.tenant_id)
+        .bind(analysis.marketplace.as_str())
+        .bind(&analysis.category)
+        .bind(analysis.period_start)
+        .bind(analysis.period_end)
+        .bind(analysis.total_products as i64)
+        .bind(analysis.avg_price.amount())
+        .bind(analysis.median_price.amount())
+        .bind(analysis.min_price.amount())
+        .bind(analysis.max_price.amount())
+        .bind(analysis.avg_price.currency())
+        .bind(serde_json::to_value(&analysis.top_sellers)?)
+        .bind(serde_json::to_value(&analysis.trending_keywords)?)
+        .bind(analysis.growth_rate)
+        .bind(analysis.competition_level.as_str())
+        .bind(analysis.analyzed_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Análises do tenant, da mais recente à mais antiga
+    pub async fn list_analyses(
+        &self,
+        tenant_id: TenantId,
+        marketplace: Option<Marketplace>,
+        category: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<TrendAnalysis>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, marketplace, category, period_start, period_end,
+                   total_products, avg_price, median_price, min_price, max_price, currency,
+                   top_sellers, trending_keywords, growth_rate::float8 AS growth_rate,
+                   competition_level, analyzed_at
+            FROM trend_analyses
+            WHERE tenant_id = $1
+              AND ($2::text IS NULL OR marketplace = $2)
+              AND ($3::text IS NULL OR lower(category) = lower($3))
+            ORDER BY analyzed_at DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(marketplace.map(|marketplace| marketplace.as_str()))
+        .bind(category)
+        .bind(limit as i64)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        rows.iter().map(analysis_from_row).collect()
+    }
+
+    /// Linha de base da extração do marketplace. A tabela é global (sem
+    /// tenant), então não passa por `begin`.
+    pub async fn get_selector_baseline(&self, marketplace: Marketplace) -> Result<Option<SelectorBaseline>> {
+        let row = sqlx::query(
+            r#"
+            SELECT marketplace, parse_yield, samples, updated_at
+            FROM selector_health
+            WHERE marketplace = $1
+            "#,
+        )
+        .bind(marketplace.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| baseline_from_row(&row)).transpose()
+    }
+
+    /// Linhas de base de todos os marketplaces
+    pub async fn list_selector_baselines(&self) -> Result<Vec<SelectorBaseline>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT marketplace, parse_yield, samples, updated_at
+            FROM selector_health
+            ORDER BY marketplace
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(baseline_from_row).collect()
+    }
+
+    pub async fn save_selector_baseline(&self, baseline: &SelectorBaseline) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO selector_health (marketplace, parse_yield, samples, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (marketplace) DO UPDATE SET
+                parse_yield = EXCLUDED.parse_yield,
+                samples = EXCLUDED.samples,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(baseline.marketplace.as_str())
+        .bind(baseline.parse_yield)
+        .bind(baseline.samples)
+        .bind(baseline.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Amostra de HTML do tenant para o marketplace
+    pub async fn get_selector_sample(
+        &self,
+        tenant_id: TenantId,
+        marketplace: Marketplace,
+    ) -> Result<Option<SelectorSample>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT tenant_id, marketplace, job_id, url, html, captured_at
+            FROM selector_samples
+            WHERE tenant_id = $1 AND marketplace = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(marketplace.as_str())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        row.map(|row| sample_from_row(&row)).transpose()
+    }
+
+    /// Substitui a amostra do tenant para o marketplace
+    pub async fn save_selector_sample(&self, sample: &SelectorSample) -> Result<()> {
+        let mut tx = self.begin(sample.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO selector_samples (tenant_id, marketplace, job_id, url, html, captured_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (tenant_id, marketplace) DO UPDATE SET
+                job_id = EXCLUDED.job_id,
+                url = EXCLUDED.url,
+                html = EXCLUDED.html,
+                captured_at = EXCLUDED.captured_at
+            "#,
+        )
+        .bind(sample.tenant_id)
+        .bind(sample.marketplace.as_str())
+        .bind(sample.job_id)
+        .bind(&sample.url)
+        .bind(&sample.html)
+        .bind(sample.captured_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Grava as cotações (upsert por par e dia). Numa mesma carga, a última
+    /// linha de um par e dia vence. Retorna quantas cotações foram gravadas.
+    pub async fn save_fx_rates(&self, rates: &[FxRate]) -> Result<usize> {
+        let mut unique: HashMap<(Currency, Currency, NaiveDate), Decimal> = HashMap::new();
+        for rate in rates {
+            unique.insert((rate.base, rate.quote, rate.date), rate.rate);
+        }
+        let unique: Vec<_> = unique.into_iter().collect();
+
+        let mut tx = self.pool.begin().await?;
+
+        for chunk in unique.chunks(FX_BATCH_SIZE) {
+            QueryBuilder::<Postgres>::new("INSERT INTO fx_rates (base, quote, rate_date, rate) ")
+                .push_values(chunk, |mut row, ((base, quote, date), rate)| {
+                    row.push_bind(*base).push_bind(*quote).push_bind(*date).push_bind(*rate);
+                })
+                .push(" ON CONFLICT (base, quote, rate_date) DO UPDATE SET rate = EXCLUDED.rate, updated_at = NOW()")
+                .build()
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(unique.len())
+    }
+
+    /// Cotação vigente de cada par no dia (a mais recente até a data)
+    pub async fn list_fx_rates(&self, date: NaiveDate) -> Result<Vec<FxRate>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (base, quote) rate_date, base, quote, rate
+            FROM fx_rates
+            WHERE rate_date <= $1
+            ORDER BY base, quote, rate_date DESC
+            "#,
+        )
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(fx_rate_from_row).collect()
+    }
+
+    /// Cotações para converter valores coletados entre `from` e `until`: as
+    /// do período e, de cada par, a última antes dele
+    pub async fn fx_rates(&self, from: NaiveDate, until: NaiveDate) -> Result<FxRates> {
+        let rows = sqlx::query(
+            r#"
+            SELECT rate_date, base, quote, rate FROM fx_rates
+            WHERE rate_date BETWEEN $1 AND $2
+            UNION ALL
+            (
+                SELECT DISTINCT ON (base, quote) rate_date, base, quote, rate
+                FROM fx_rates
+                WHERE rate_date < $1
+                ORDER BY base, quote, rate_date DESC
+            )
+            "#,
+        )
+        .bind(from)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rates = rows.iter().map(fx_rate_from_row).collect::<Result<Vec<_>>>()?;
+        Ok(FxRates::new(rates))
+    }
+
+    /// Preferências do tenant; as padrão se ele nunca gravou
+    pub async fn get_tenant_settings(&self, tenant_id: TenantId) -> Result<TenantSettings> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let row = sqlx::query("SELECT base_currency FROM tenant_settings WHERE tenant_id = $1")
+            .bind(tenant_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        match row {
+            Some(row) => Ok(TenantSettings { base_currency: row.try_get("base_currency")? }),
+            None => Ok(TenantSettings::default()),
+        }
+    }
+
+    pub async fn save_tenant_settings(&self, tenant_id: TenantId, settings: &TenantSettings) -> Result<()> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_settings (tenant_id, base_currency, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (tenant_id) DO UPDATE SET
+                base_currency = EXCLUDED.base_currency,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(settings.base_currency)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Grava o alerta, a menos que o tenant já tenha um não lido do mesmo
+    /// tipo e marketplace criado depois de `since`. Retorna se gravou.
+    pub async fn save_alert_unless_open(&self, alert: &MarketAlert, since: DateTime<Utc>) -> Result<bool> {
+        let mut tx = self.begin(alert.tenant_id).await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO market_alerts (
+                id, tenant_id, marketplace, alert_type, product_id, title, message, severity, read_at, created_at
+            )
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+            WHERE NOT EXISTS (
+                SELECT 1 FROM market_alerts
+                WHERE tenant_id = $2 AND marketplace = $3 AND alert_type = $4
+                  AND read_at IS NULL AND created_at > $11
+            )
+            "#,
+        )
+        .bind(alert.id)
+        .bind(alert.tenant_id)
+        .bind(alert.marketplace.as_str())
+        .bind(alert.alert_type.as_str())
+        .bind(alert.product_id)
+        .bind(&alert.title)
+        .bind(&alert.message)
+        .bind(alert.severity.as_str())
+        .bind(alert.read_at)
+        .bind(alert.created_at)
+        .bind(since)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Alertas do tenant, mais recentes primeiro
+    pub async fn list_alerts(&self, tenant_id: TenantId, alert_type: Option<AlertType>, limit: u32) -> Result<Vec<MarketAlert>> {
+        let mut tx = self.begin(tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, marketplace, alert_type, product_id, title, message, severity, read_at, created_at
+            FROM market_alerts
+            WHERE tenant_id = $1 AND ($2::text IS NULL OR alert_type = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(alert_type.map(|alert_type| alert_type.as_str()))
+        .bind(limit as i64)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        rows.iter().map(alert_from_row).collect()
+    }
+}
+
+/// `WHERE` da listagem de produtos: sempre tenant e job, mais os filtros informados
+fn push_product_filter(query: &mut QueryBuilder<'_, Postgres>, tenant_id: TenantId, job_id: Uuid, filter: &ProductFilter) {
+    query
+        .push(" WHERE tenant_id = ")
+        .push_bind(tenant_id)
+        .push(" AND job_id = ")
+        .push_bind(job_id);
+
+    if let Some(min_price) = filter.min_price {
+        query.push(" AND price >= ").push_bind(min_price);
+    }
+    if let Some(max_price) = filter.max_price {
+        query.push(" AND price <= ").push_bind(max_price);
+    }
+    if let Some(seller) = filter.seller.clone() {
+        query
+            .push(" AND (lower(seller_name) = lower(")
+            .push_bind(seller.clone())
+            .push(") OR seller_id = ")
+            .push_bind(seller)
+            .push(")");
+    }
+    if let Some(available) = filter.available {
+        query.push(" AND availability = ").push_bind(available);
+    }
+}
+
+/// Escapa os curingas do `LIKE` (`\\` é o escape padrão do Postgres)
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn job_from_row(row: &PgRow) -> Result<ScrapingJob> {
+    let marketplace: String = row.try_get("marketplace")?;
+    let status: String = row.try_get("status")?;
+    let fetch_strategy: Option<String> = row.try_get("fetch_strategy")?;
+
+    Ok(ScrapingJob {
+        id: row.try_get("id")?,
+        tenant_id: row.try_get("tenant_id")?,
+        marketplace: Marketplace::from_db(&marketplace)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown marketplace {:?}", marketplace)))?,
+        search_query: row.try_get("search_query")?,
+        category: row.try_get("category")?,
+        max_pages: row.try_get::<i32, _>("max_pages")? as u32,
+        priority: row.try_get::<i16, _>("priority")? as u8,
+        enrich_details: row.try_get("enrich_details")?,
+        fetch_strategy: fetch_strategy
+            .map(|strategy| {
+                FetchStrategy::from_db(&strategy)
+                    .ok_or_else(|| ArcsatError::Internal(format!("Unknown fetch strategy {:?}", strategy)))
+            })
+            .transpose()?,
+        status: JobStatus::from_db(&status)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown job status {:?}", status)))?,
+        created_at: row.try_get("created_at")?,
+        started_at: row.try_get("started_at")?,
+        completed_at: row.try_get("completed_at")?,
+        error: row.try_get("error")?,
+        attempts: row.try_get::<i32, _>("attempts")? as u32,
+        error_history: serde_json::from_value(row.try_get("error_history")?)?,
+    })
+}
+
+fn analysis_from_row(row: &PgRow) -> Result<TrendAnalysis> {
+    let marketplace: String = row.try_get("marketplace")?;
+    let competition_level: String = row.try_get("competition_level")?;
+    let currency: Currency = row.try_get("currency")?;
+    let total_products: i64 = row.try_get("total_products")?;
+
+    Ok(TrendAnalysis {
+        id: row.try_get("id")?,
+        tenant_id: row.try_get("tenant_id")?,
+        marketplace: Marketplace::from_db(&marketplace)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown marketplace {:?}", marketplace)))?,
+        category: row.try_get("category")?,
+        period_start: row.try_get("period_start")?,
+        period_end: row.try_get("period_end")?,
+        total_products: total_products as u64,
+        avg_price: Money::new(row.try_get("avg_price")?, currency),
+        median_price: Money::new(row.try_get("median_price")?, currency),
+        min_price: Money::new(row.try_get("min_price")?, currency),
+        max_price: Money::new(row.try_get("max_price")?, currency),
+        top_sellers: serde_json::from_value(row.try_get("top_sellers")?)?,
+        trending_keywords: serde_json::from_value(row.try_get("trending_keywords")?)?,
+        growth_rate: row.try_get("growth_rate")?,
+        competition_level: CompetitionLevel::from_db(&competition_level)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown competition level {:?}", competition_level)))?,
+        analyzed_at: row.try_get("analyzed_at")?,
+    })
+}
+
+fn baseline_from_row(row: &PgRow) -> Result<SelectorBaseline> {
+    let marketplace: String = row.try_get("marketplace")?;
+
+    Ok(SelectorBaseline {
+        marketplace: Marketplace::from_db(&marketplace)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown marketplace {:?}", marketplace)))?,
+        parse_yield: row.try_get("parse_yield")?,
+        samples: row.try_get("samples")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn sample_from_row(row: &PgRow) -> Result<SelectorSample> {
+    let marketplace: String = row.try_get("marketplace")?;
+
+    Ok(SelectorSample {
+        tenant_id: row.try_get("tenant_id")?,
+        marketplace: Marketplace::from_db(&marketplace)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown marketplace {:?}", marketplace)))?,
+        job_id: row.try_get("job_id")?,
+        url: row.try_get("url")?,
+        html: row.try_get("html")?,
+        captured_at: row.try_get("captured_at")?,
+    })
+}
+
+fn alert_from_row(row: &PgRow) -> Result<MarketAlert> {
+    let marketplace: String = row.try_get("marketplace")?;
+    let alert_type: String = row.try_get("alert_type")?;
+    let severity: String = row.try_get("severity")?;
+
+    Ok(MarketAlert {
+        id: row.try_get("id")?,
+        tenant_id: row.try_get("tenant_id")?,
+        marketplace: Marketplace::from_db(&marketplace)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown marketplace {:?}", marketplace)))?,
+        alert_type: AlertType::from_db(&alert_type)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown alert type {:?}", alert_type)))?,
+        product_id: row.try_get("product_id")?,
+        title: row.try_get("title")?,
+        message: row.try_get("message")?,
+        severity: AlertSeverity::from_db(&severity)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown alert severity {:?}", severity)))?,
+        read_at: row.try_get("read_at")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn fx_rate_from_row(row: &PgRow) -> Result<FxRate> {
+    Ok(FxRate {
+        date: row.try_get("rate_date")?,
+        base: row.try_get("base")?,
+        quote: row.try_get("quote")?,
+        rate: row.try_get("rate")?,
+    })
+}
+
+fn product_from_row(row: &PgRow) -> Result<ScrapedProduct> {
+    let marketplace: String = row.try_get("marketplace")?;
+    // Os preços da linha estão todos na moeda do produto
+    let currency: Currency = row.try_get("currency")?;
+    let money = |column: &str| -> Result<Option<Money>> {
+        Ok(row.try_get::<Option<Decimal>, _>(column)?.map(|amount| Money::new(amount, currency)))
+    };
+
+    Ok(ScrapedProduct {
+        id: row.try_get("id")?,
+        job_id: row.try_get("job_id")?,
+        marketplace: Marketplace::from_db(&marketplace)
+            .ok_or_else(|| ArcsatError::Internal(format!("Unknown marketplace {:?}", marketplace)))?,
+        external_id: row.try_get("external_id")?,
+        title: row.try_get("title")?,
+        price: Money::new(row.try_get("price")?, currency),
+        url: row.try_get("url")?,
+        image_url: row.try_get("image_url")?,
+        pricing: Pricing {
+            list_price: money("list_price")?,
+            pix_price: money("pix_price")?,
+            max_price: money("max_price")?,
+            installments: row.try_get::<Option<Json<InstallmentPlan>>, _>("installments")?.map(|json| json.0),
+        },
+        seller_name: row.try_get("seller_name")?,
+        seller_id: row.try_get("seller_id")?,
+        seller_rating: row.try_get("seller_rating")?,
+        sales_rank: row.try_get("sales_rank")?,
+        rating: row.try_get("rating")?,
+        num_reviews: row.try_get("num_reviews")?,
+        availability: row.try_get("availability")?,
+        category: row.try_get("category")?,
+        brand: row.try_get("brand")?,
+        scraped_at: row.try_get("scraped_at")?,
+        extra: row.try_get::<Option<serde_json::Value>, _>("extra")?.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,63 @@
+//! Política de retentativas de jobs que falharam
+//!
+//! Falhas transitórias (`ArcsatError::is_retryable`) voltam para a fila após
+//! um backoff exponencial com jitter; falhas definitivas encerram o job na
+//! hora. Sem tentativas restantes, o job vai para a dead-letter queue.
+
+use arcsat_core::config::MarketIntelligenceConfig;
+use arcsat_core::ArcsatError;
+use rand::Rng;
+use std::time::Duration;
+
+/// O que fazer com um job após a falha de uma tentativa
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Reenfileirar depois da espera
+    Retry(Duration),
+    /// Transitória, mas sem tentativas restantes
+    DeadLetter,
+    /// Erro definitivo; repetir não adianta
+    Fail,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &MarketIntelligenceConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts,
+            base_delay: Duration::from_secs(config.retry_base_delay_secs),
+            max_delay: Duration::from_secs(config.retry_max_delay_secs),
+        }
+    }
+
+    /// `attempts` é o número de tentativas já feitas, incluindo a que falhou
+    pub fn decide(&self, error: &ArcsatError, attempts: u32) -> RetryDecision {
+        if !error.is_retryable() {
+            RetryDecision::Fail
+        } else if attempts >= self.max_attempts {
+            RetryDecision::DeadLetter
+        } else {
+            RetryDecision::Retry(self.backoff(attempts))
+        }
+    }
+
+    /// Espera antes da tentativa seguinte à `attempt`: `base * 2^(attempt-1)`,
+    /// limitada a `max_delay`, sorteada entre metade e o valor cheio para que
+    /// jobs que falharam juntos não voltem todos ao mesmo tempo
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        let half = delay / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+}
@@ -0,0 +1,203 @@
+//! AliExpress
+//!
+//! Busca pelo endpoint `fn/search-pc/index`, que devolve a listagem em JSON
+//! (`mods.itemList.content`). Os preços vêm na moeda da sessão, muitas vezes
+//! USD, e a moeda de cada item vai para `currency`.
+
+use super::json_api::{search_rank, ApiScraper};
+use super::listing_product;
+use crate::models::*;
+use arcsat_core::{ArcsatError, Currency, Money, Result};
+use reqwest::{Client, RequestBuilder};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Itens por página da busca
+const PAGE_SIZE: u32 = 60;
+
+pub struct AliExpressScraper {
+    base_url: String,
+}
+
+impl AliExpressScraper {
+    pub fn new() -> Self {
+        Self::with_base_url(Marketplace::AliExpress.base_url())
+    }
+
+    /// Scraper apontando para outro host (ex.: servidor de testes)
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self { base_url: base_url.into() }
+    }
+}
+
+impl Default for AliExpressScraper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApiScraper for AliExpressScraper {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::AliExpress
+    }
+
+    fn warmup_url(&self) -> Option<String> {
+        Some(format!("{}/", self.base_url))
+    }
+
+    fn search_request(&self, client: &Client, job: &ScrapingJob, page: u32) -> RequestBuilder {
+        client
+            .post(format!("{}/fn/search-pc/index", self.base_url))
+            .header(reqwest::header::ORIGIN, self.base_url.as_str())
+            .header(
+                reqwest::header::REFERER,
+                format!("{}/w/wholesale-{}.html", self.base_url, urlencoding::encode(&job.search_query.replace(' ', "-"))),
+            )
+            .json(&serde_json::json!({
+                "pageVersion": "7ece9c0cc9cf2052db74f0d1b26b7033",
+                "target": "root",
+                "data": {
+                    "page": page,
+                    "g": "y",
+                    "SearchText": job.search_query,
+                    "origin": "y",
+                },
+                "eventName": "onChange",
+                "dependency": [],
+            }))
+    }
+
+    fn parse_response(&self, job: &ScrapingJob, page: u32, body: &Value) -> Result<Vec<ScrapedProduct>> {
+        // Bloqueio (captcha/"punish") vem como 200 sem `data.result`
+        let result = body
+            .pointer("/data/result")
+            .ok_or_else(|| ArcsatError::Scraping("AliExpress search API returned no result".to_string()))?;
+        let items: Vec<SearchItem> = match result.pointer("/mods/itemList/content") {
+            Some(content) => serde_json::from_value(content.clone())?,
+            None => Vec::new(),
+        };
+
+        let products = items
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                let price = item.prices.sale_price?;
+                let url = format!("{}/item/{}.html", Marketplace::AliExpress.base_url(), item.product_id);
+
+                let price = Money::new(price.min_price, price.currency_code);
+
+                Some(ScrapedProduct {
+                    external_id: item.product_id,
+                    image_url: item.image.map(|image| absolute_image_url(&image.img_url)),
+                    seller_name: item
+                        .store
+                        .as_ref()
+                        .and_then(|store| store.store_name.clone())
+                        .unwrap_or_else(|| "AliExpress".to_string()),
+                    seller_id: item.store.and_then(|store| store.store_id).map(|id| id.to_string()),
+                    sales_rank: Some(search_rank(page, PAGE_SIZE, index)),
+                    rating: item.evaluation.and_then(|evaluation| evaluation.star_rating),
+                    pricing: Pricing {
+                        list_price: item.prices.original_price
+                            .map(|original| Money::new(original.min_price, original.currency_code))
+                            .filter(|list| *list > price),
+                        ..Pricing::default()
+                    },
+                    extra: serde_json::json!({
+                        "sold": item.trade.as_ref().and_then(|trade| sold_count(&trade.trade_desc)),
+                        "sold_text": item.trade.map(|trade| trade.trade_desc),
+                    }),
+                    ..listing_product(job, item.title.display_title, price, url)
+                })
+            })
+            .collect();
+
+        Ok(products)
+    }
+
+    fn has_next_page(&self, body: &Value, page: u32) -> bool {
+        match body.pointer("/data/result/pageInfo/totalResults").and_then(Value::as_u64) {
+            Some(total) => ((page * PAGE_SIZE) as u64) < total,
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchItem {
+    product_id: String,
+    title: Title,
+    prices: Prices,
+    image: Option<Image>,
+    evaluation: Option<Evaluation>,
+    trade: Option<Trade>,
+    store: Option<Store>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Title {
+    display_title: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Prices {
+    sale_price: Option<Price>,
+    original_price: Option<Price>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Price {
+    currency_code: Currency,
+    min_price: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Image {
+    img_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Evaluation {
+    star_rating: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Trade {
+    /// Ex.: "1.000+ vendidos"
+    trade_desc: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Store {
+    store_name: Option<String>,
+    store_id: Option<u64>,
+}
+
+/// Imagens vêm sem esquema (`//ae01.alicdn.com/...`)
+fn absolute_image_url(url: &str) -> String {
+    if url.starts_with("//") {
+        format!("https:{}", url)
+    } else {
+        url.to_string()
+    }
+}
+
+/// "1.000+ vendidos" → 1000
+fn sold_count(text: &str) -> Option<i64> {
+    let digits: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .filter(char::is_ascii_digit)
+        .collect();
+
+    digits.parse().ok()
+}
@@ -0,0 +1,257 @@
+//! Amazon BR
+
+use super::details::{count_in, set_extra, set_shipping, set_stock};
+use super::{detect_currency, json_ld, listing_product, parse_brl_parts, parse_brl_price, BrlPrice, CardFields, MarketplaceScraper, PageStats, Selectors, SelectorSpec};
+use crate::models::*;
+use arcsat_core::Result;
+use scraper::{ElementRef, Html};
+
+const SELECTORS: &SelectorSpec = &[
+    ("card", "div[data-component-type='s-search-result']"),
+    ("title", "h2 a span"),
+    // `a-offscreen` traz o preço inteiro; sem ele, símbolo, reais e centavos vêm separados
+    ("price", "span.a-price:not(.a-text-price) span.a-offscreen"),
+    ("price_symbol", "span.a-price:not(.a-text-price) span.a-price-symbol"),
+    ("price_whole", "span.a-price:not(.a-text-price) span.a-price-whole"),
+    ("price_fraction", "span.a-price:not(.a-text-price) span.a-price-fraction"),
+    ("list_price", "span.a-price.a-text-price span.a-offscreen"),
+    ("rating", "span.a-icon-alt"),
+    ("link", "h2 a"),
+    // "Próximo" da paginação; na última página vira um span desabilitado
+    ("next_page", "a.s-pagination-next:not(.s-pagination-disabled)"),
+    // Página de produto
+    ("detail_seller", "#sellerProfileTriggerId"),
+    ("detail_merchant", "#merchant-info, #merchantInfoFeature_feature_div .offer-display-feature-text"),
+    ("detail_brand", "#bylineInfo"),
+    ("detail_rating", "#acrPopover"),
+    ("detail_reviews", "#acrCustomerReviewText"),
+    ("detail_image", "#landingImage"),
+    ("detail_availability", "#availability"),
+    ("detail_shipping", "#mir-layout-DELIVERY_BLOCK [data-csa-c-delivery-price]"),
+    ("detail_offers", "#olpLinkWidget_feature_div"),
+    ("spec_row", "#productDetails_techSpec_section_1 tr, #productDetails_detailBullets_sections1 tr"),
+    ("spec_name", "th"),
+    ("spec_value", "td"),
+    ("bullet", "#detailBullets_feature_div li"),
+];
+
+/// Vendedor quando a própria Amazon vende e entrega
+const AMAZON_SELLER: &str = "Amazon.com.br";
+
+pub struct AmazonScraper {
+    selectors: Selectors,
+}
+
+impl AmazonScraper {
+    pub fn new() -> Result<Self> {
+        Ok(Self { selectors: Selectors::compile(SELECTORS)? })
+    }
+
+    /// Preço de venda do card
+    fn card_price(&self, card: ElementRef) -> Option<BrlPrice> {
+        self.selectors.text(card, "price").and_then(|text| parse_brl_price(&text)).or_else(|| {
+            let whole = self.selectors.text(card, "price_whole")?;
+            let currency = self.selectors
+                .text(card, "price_symbol")
+                .and_then(|symbol| detect_currency(&symbol))
+                .unwrap_or_default();
+            let price = parse_brl_parts(&whole, self.selectors.text(card, "price_fraction").as_deref(), currency)?;
+
+            price.is_positive().then(|| BrlPrice { price, pricing: Pricing::default() })
+        })
+    }
+}
+
+impl MarketplaceScraper for AmazonScraper {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::Amazon
+    }
+
+    fn search_url(&self, job: &ScrapingJob, page: u32) -> String {
+        let url = format!("{}/s?k={}", Marketplace::Amazon.base_url(), urlencoding::encode(&job.search_query));
+
+        if page == 1 {
+            url
+        } else {
+            format!("{}&page={}", url, page)
+        }
+    }
+
+    fn ready_selector(&self) -> Option<&str> {
+        Some("div[data-component-type='s-search-result']")
+    }
+
+    /// Banner "Nenhum resultado para ..." no topo da busca
+    fn no_results_selector(&self) -> Option<&str> {
+        Some("div[cel_widget_id^='MAIN-TOP_BANNER_MESSAGE']")
+    }
+
+    fn parse_listing(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct> {
+        let base_url = Marketplace::Amazon.base_url();
+
+        self.selectors
+            .all(document, "card")
+            .filter_map(|card| {
+                let title = self.selectors.text(card, "title")?;
+                let BrlPrice { price, mut pricing } = self.card_price(card)?;
+                pricing.list_price = self.selectors
+                    .text(card, "list_price")
+                    .and_then(|text| parse_brl_price(&text))
+                    .map(|list| list.price)
+                    .filter(|list| *list > price);
+
+                let rating = self.selectors
+                    .text(card, "rating")
+                    .and_then(|text| text.split_whitespace().next().and_then(|s| s.replace(',', ".").parse::<f64>().ok()));
+
+                let url = self.selectors
+                    .attr(card, "link", "href")
+                    .map(|href| {
+                        if href.starts_with("http") {
+                            href
+                        } else {
+                            format!("{}{}", base_url, href)
+                        }
+                    })
+                    .unwrap_or_default();
+
+                let external_id = card
+                    .value()
+                    .attr("data-asin")
+                    .filter(|asin| !asin.is_empty())
+                    .map(str::to_string)
+                    .or_else(|| asin_from_url(&url))
+                    .unwrap_or_default();
+
+                Some(ScrapedProduct {
+                    external_id,
+                    seller_name: "Amazon".to_string(),
+                    rating,
+                    pricing,
+                    ..listing_product(job, title, price, url)
+                })
+            })
+            .collect()
+    }
+
+    fn page_stats(&self, document: &Html) -> Option<PageStats> {
+        Some(PageStats::from_cards(self.selectors.all(document, "card"), |card| CardFields {
+            title: self.selectors.exists_in(card, "title"),
+            price: self.selectors.exists_in(card, "price") || self.selectors.exists_in(card, "price_whole"),
+            url: self.selectors.attr(card, "link", "href").is_some(),
+        }))
+    }
+
+    fn has_next_page(&self, document: &Html, _page: u32) -> bool {
+        self.selectors.exists(document, "next_page")
+    }
+
+    fn parse_detail(&self, product: &mut ScrapedProduct, document: &Html) {
+        json_ld::apply(product, document);
+
+        let page = document.root_element();
+        let selectors = &self.selectors;
+
+        // Oferta em destaque (buy box): loja parceira ou a própria Amazon
+        let seller = selectors.text(page, "detail_seller").or_else(|| {
+            selectors
+                .text(page, "detail_merchant")
+                .filter(|text| text.contains("Amazon"))
+                .map(|_| AMAZON_SELLER.to_string())
+        });
+        if let Some(seller) = seller {
+            set_extra(product, "buy_box_seller", seller.clone().into());
+            product.seller_name = seller;
+        }
+        if let Some(seller_id) = selectors
+            .attr(page, "detail_seller", "href")
+            .and_then(|href| query_value(&href, "seller"))
+        {
+            product.seller_id = Some(seller_id);
+        }
+
+        if let Some(byline) = selectors.text(page, "detail_brand") {
+            product.brand = Some(brand_from_byline(&byline));
+        }
+        if let Some(rating) = selectors
+            .attr(page, "detail_rating", "title")
+            .and_then(|title| title.split_whitespace().next().and_then(|s| s.replace(',', ".").parse().ok()))
+        {
+            product.rating = Some(rating);
+        }
+        if let Some(reviews) = selectors.text(page, "detail_reviews").and_then(|text| count_in(&text)) {
+            product.num_reviews = reviews as i32;
+        }
+        if let Some(image) = selectors.attr(page, "detail_image", "src") {
+            product.image_url = Some(image);
+        }
+
+        if let Some(availability) = selectors.text(page, "detail_availability") {
+            set_stock(product, &availability);
+        }
+        if let Some(shipping) = selectors.attr(page, "detail_shipping", "data-csa-c-delivery-price") {
+            set_shipping(product, &shipping);
+        }
+        if let Some(offers) = selectors.text(page, "detail_offers").and_then(|text| count_in(&text)) {
+            set_extra(product, "offer_count", offers.into());
+        }
+
+        if let Some(gtin) = self.ean(document) {
+            set_extra(product, "gtin", gtin.into());
+        }
+    }
+}
+
+impl AmazonScraper {
+    /// EAN na tabela de detalhes técnicos ou na lista "Detalhes do produto"
+    fn ean(&self, document: &Html) -> Option<String> {
+        let is_ean = |name: &str| name.contains("EAN") || name.contains("GTIN");
+
+        let from_table = self.selectors.all(document, "spec_row").find_map(|row| {
+            let name = self.selectors.text(row, "spec_name")?;
+            is_ean(&name).then(|| self.selectors.text(row, "spec_value")).flatten()
+        });
+
+        from_table.or_else(|| {
+            self.selectors.all(document, "bullet").find_map(|bullet| {
+                let text = bullet.text().collect::<String>();
+                let (name, value) = text.split_once(':')?;
+                is_ean(name).then(|| value.trim_matches(|c: char| c.is_whitespace() || c == '\u{200e}').to_string())
+            })
+        })
+    }
+}
+
+/// "Marca: JBL", "Visite a loja da JBL" → "JBL"
+fn brand_from_byline(byline: &str) -> String {
+    ["Marca:", "Visite a loja da", "Visite a loja"]
+        .iter()
+        .find_map(|prefix| byline.strip_prefix(prefix))
+        .unwrap_or(byline)
+        .trim()
+        .to_string()
+}
+
+/// ASIN do caminho da URL (`/dp/B0C4Q3S8N2/...`, `/gp/product/B0C4Q3S8N2`)
+fn asin_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let segments: Vec<&str> = path.split('/').collect();
+
+    segments
+        .windows(2)
+        .find(|pair| pair[0] == "dp" || pair[0] == "product")
+        .map(|pair| pair[1].to_string())
+        .filter(|asin| !asin.is_empty())
+}
+
+/// Valor de `key` na query string da URL
+fn query_value(url: &str, key: &str) -> Option<String> {
+    let (_, query) = url.split_once('?')?;
+
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,258 @@
+//! Americanas (B2W)
+//!
+//! A busca é renderizada com Next.js: os produtos vêm completos no JSON de
+//! `<script id="__NEXT_DATA__">`, que muda bem menos que as classes CSS
+//! (geradas no build). Os seletores ficam como fallback para páginas sem o
+//! estado embutido.
+
+use super::{listing_product, parse_brl_price, BrlPrice, CardFields, MarketplaceScraper, PageStats, Selectors, SelectorSpec};
+use crate::models::*;
+use arcsat_core::{Money, Result};
+use rust_decimal::Decimal;
+use scraper::Html;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Produtos por página da busca (`offset` anda de `PAGE_SIZE` em `PAGE_SIZE`)
+const PAGE_SIZE: u32 = 24;
+
+const SELECTORS: &SelectorSpec = &[
+    ("state", "script#__NEXT_DATA__"),
+    ("card", "div[class*='inStockCard']"),
+    ("title", "h3[class*='product-name']"),
+    ("price", "span[class*='PromotionalPrice']"),
+    ("link", "a[href*='/produto/']"),
+    ("image", "img"),
+];
+
+pub struct AmericanasScraper {
+    selectors: Selectors,
+}
+
+impl AmericanasScraper {
+    pub fn new() -> Result<Self> {
+        Ok(Self { selectors: Selectors::compile(SELECTORS)? })
+    }
+
+    /// Resultado da busca no estado do Next.js, se a página trouxer
+    fn search_state(&self, document: &Html) -> Option<SearchState> {
+        let script = self.selectors.all(document, "state").next()?;
+        let state: Value = serde_json::from_str(&script.text().collect::<String>()).ok()?;
+
+        // O caminho até a busca muda entre versões do site; procura o
+        // primeiro objeto com a lista `products`
+        serde_json::from_value(find_with_key(&state, "products")?.clone()).ok()
+    }
+
+    fn parse_cards(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct> {
+        let base_url = Marketplace::B2W.base_url();
+
+        self.selectors
+            .all(document, "card")
+            .filter_map(|card| {
+                let title = self.selectors.text(card, "title")?;
+                let BrlPrice { price, pricing } = self.selectors.text(card, "price").and_then(|text| parse_brl_price(&text))?;
+                let href = self.selectors.attr(card, "link", "href")?;
+                let url = absolute_url(base_url, &href);
+
+                Some(ScrapedProduct {
+                    external_id: product_id(&url).unwrap_or_default(),
+                    image_url: self.selectors.attr(card, "image", "src"),
+                    seller_name: DEFAULT_SELLER.to_string(),
+                    pricing,
+                    ..listing_product(job, title, price, url)
+                })
+            })
+            .collect()
+    }
+}
+
+impl MarketplaceScraper for AmericanasScraper {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::B2W
+    }
+
+    fn search_url(&self, job: &ScrapingJob, page: u32) -> String {
+        format!(
+            "{}/busca/{}?limit={}&offset={}",
+            Marketplace::B2W.base_url(),
+            urlencoding::encode(&job.search_query.replace(' ', "-")),
+            PAGE_SIZE,
+            (page - 1) * PAGE_SIZE
+        )
+    }
+
+    fn ready_selector(&self) -> Option<&str> {
+        Some("script#__NEXT_DATA__")
+    }
+
+    fn parse_listing(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct> {
+        match self.search_state(document) {
+            Some(state) => state.products.into_iter().filter_map(|p| p.into_product(job)).collect(),
+            None => self.parse_cards(job, document),
+        }
+    }
+
+    fn page_stats(&self, document: &Html) -> Option<PageStats> {
+        let stats = match self.search_state(document) {
+            // No estado, o "card" é o item da lista `products`
+            Some(state) => PageStats {
+                cards: state.products.len(),
+                missing_title: state.products.iter().filter(|p| p.name.trim().is_empty()).count(),
+                missing_price: state.products.iter().filter(|p| p.offers.is_empty()).count(),
+                ..PageStats::default()
+            },
+            None => PageStats::from_cards(self.selectors.all(document, "card"), |card| CardFields {
+                title: self.selectors.exists_in(card, "title"),
+                price: self.selectors.exists_in(card, "price"),
+                url: self.selectors.attr(card, "link", "href").is_some(),
+            }),
+        };
+
+        Some(stats)
+    }
+
+    /// O estado do Next.js vem com a lista `products` vazia
+    fn is_no_results(&self, document: &Html) -> bool {
+        self.search_state(document).is_some_and(|state| state.products.is_empty())
+    }
+
+    fn has_next_page(&self, document: &Html, page: u32) -> bool {
+        match self.search_state(document) {
+            Some(SearchState { total: Some(total), .. }) => (page * PAGE_SIZE) < total,
+            Some(state) => state.products.len() as u32 >= PAGE_SIZE,
+            None => self.selectors.exists(document, "card"),
+        }
+    }
+}
+
+/// Vendedor quando a oferta não informa (venda da própria Americanas)
+const DEFAULT_SELLER: &str = "Americanas";
+
+#[derive(Debug, Deserialize)]
+struct SearchState {
+    products: Vec<StateProduct>,
+    total: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateProduct {
+    id: String,
+    name: String,
+    url: Option<String>,
+    brand: Option<String>,
+    #[serde(default)]
+    images: Vec<StateImage>,
+    rating: Option<StateRating>,
+    /// Ofertas dos vendedores; a primeira é a que ganha a vitrine
+    #[serde(default)]
+    offers: Vec<StateOffer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateImage {
+    large: Option<String>,
+    small: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateRating {
+    average: Option<f64>,
+    #[serde(default)]
+    reviews: i32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateOffer {
+    sales_price: Decimal,
+    list_price: Option<Decimal>,
+    seller: Option<StateSeller>,
+    pix: Option<StatePix>,
+    installment: Option<StateInstallment>,
+    availability: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateSeller {
+    id: Option<String>,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatePix {
+    price: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateInstallment {
+    quantity: u32,
+    value: Decimal,
+    #[serde(default)]
+    interest_free: bool,
+}
+
+impl StateProduct {
+    /// Produto sem oferta está indisponível e não entra na coleta
+    fn into_product(self, job: &ScrapingJob) -> Option<ScrapedProduct> {
+        let offer = self.offers.into_iter().next()?;
+        let base_url = Marketplace::B2W.base_url();
+
+        let url = match &self.url {
+            Some(url) => absolute_url(base_url, url),
+            None => format!("{}/produto/{}", base_url, self.id),
+        };
+        let (seller_id, seller_name) = match offer.seller {
+            Some(seller) => (seller.id, seller.name),
+            None => (None, DEFAULT_SELLER.to_string()),
+        };
+
+        Some(ScrapedProduct {
+            external_id: self.id,
+            image_url: self.images.into_iter().next().and_then(|image| image.large.or(image.small)),
+            seller_name,
+            seller_id,
+            rating: self.rating.as_ref().and_then(|rating| rating.average),
+            num_reviews: self.rating.map(|rating| rating.reviews).unwrap_or(0),
+            availability: offer.availability.as_deref() != Some("out_of_stock"),
+            brand: self.brand,
+            pricing: Pricing {
+                list_price: offer.list_price.filter(|list| *list > offer.sales_price).map(Money::brl),
+                pix_price: offer.pix.map(|pix| pix.price).filter(|pix| *pix < offer.sales_price).map(Money::brl),
+                max_price: None,
+                installments: offer.installment.map(|i| InstallmentPlan {
+                    count: i.quantity,
+                    amount: Money::brl(i.value),
+                    interest_free: i.interest_free,
+                }),
+            },
+            ..listing_product(job, self.name, Money::brl(offer.sales_price), url)
+        })
+    }
+}
+
+/// Primeiro objeto (busca em profundidade) que tem `key`
+fn find_with_key<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) if map.contains_key(key) => Some(value),
+        Value::Object(map) => map.values().find_map(|v| find_with_key(v, key)),
+        Value::Array(items) => items.iter().find_map(|v| find_with_key(v, key)),
+        _ => None,
+    }
+}
+
+fn absolute_url(base_url: &str, href: &str) -> String {
+    if href.starts_with("http") {
+        href.to_string()
+    } else {
+        format!("{}{}", base_url, href)
+    }
+}
+
+/// Id do produto na URL: `/produto/<id>` (com ou sem query string)
+fn product_id(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("/produto/")?;
+    rest.split(['/', '?']).next().filter(|id| !id.is_empty()).map(str::to_string)
+}
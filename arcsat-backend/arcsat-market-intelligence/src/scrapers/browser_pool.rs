@@ -0,0 +1,301 @@
+//! Pool de navegadores headless compartilhado pelos jobs do worker
+//!
+//! Cada slot é um processo do Chrome com uma aba reaproveitada entre jobs. O
+//! Chrome só sobe no primeiro uso do slot e, a cada vez que sobe, pega o
+//! próximo proxy do `ProxyPool`; usuário e senha do proxy (que o
+//! `--proxy-server` ignora) são respondidos pela aba no `Fetch.authRequired`. Um job pega o slot emprestado com
+//! `acquire` e o devolve ao soltar o `BrowserLease`; antes de emprestar, o
+//! pool confere se o processo responde e o reinicia depois de
+//! `max_navigations` ou se tiver caído.
+
+use crate::proxy::{ProxyConfig, ProxyPool};
+use arcsat_core::{ArcsatError, Result};
+use headless_chrome::protocol::cdp::Network;
+use headless_chrome::{Browser, LaunchOptions, Tab};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+/// Sem comandos por esse tempo o `headless_chrome` encerra o processo; o
+/// health check do próximo `acquire` sobe outro
+const IDLE_BROWSER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy)]
+pub struct BrowserPoolConfig {
+    /// Processos do Chrome (e jobs de navegador ao mesmo tempo)
+    pub size: usize,
+    /// Navegações de um processo antes de reiniciá-lo
+    pub max_navigations: u32,
+}
+
+impl Default for BrowserPoolConfig {
+    fn default() -> Self {
+        Self {
+            size: 2,
+            max_navigations: 100,
+        }
+    }
+}
+
+impl BrowserPoolConfig {
+    pub fn from_config(config: &arcsat_core::config::MarketIntelligenceConfig) -> Self {
+        Self {
+            size: config.browser_pool_size.max(1),
+            max_navigations: config.browser_max_navigations.max(1),
+        }
+    }
+}
+
+/// Fotografia dos contadores do pool, publicada pelo worker
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrowserPoolMetrics {
+    pub size: usize,
+    pub in_use: usize,
+    /// Processos do Chrome iniciados (inclui reinícios)
+    pub launches: u64,
+    /// Reinícios por `max_navigations`
+    pub recycled: u64,
+    /// Reinícios porque o processo ou a aba pararam de responder
+    pub crashes: u64,
+    pub leases: u64,
+    pub navigations: u64,
+    /// Espera total por um slot livre
+    pub wait_ms: u64,
+}
+
+#[derive(Clone)]
+pub struct BrowserPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    config: BrowserPoolConfig,
+    /// Rodízio de proxies entre os processos do Chrome
+    proxies: Mutex<ProxyPool>,
+    idle: Mutex<Vec<Slot>>,
+    permits: Arc<Semaphore>,
+    launches: AtomicU64,
+    recycled: AtomicU64,
+    crashes: AtomicU64,
+    leases: AtomicU64,
+    navigations: AtomicU64,
+    wait_ms: AtomicU64,
+}
+
+struct Slot {
+    id: usize,
+    /// Proxy do processo em execução
+    proxy: Option<ProxyConfig>,
+    browser: Option<Browser>,
+    tab: Option<Arc<Tab>>,
+    navigations: u32,
+}
+
+impl Slot {
+    fn new(id: usize) -> Self {
+        Self {
+            id,
+            proxy: None,
+            browser: None,
+            tab: None,
+            navigations: 0,
+        }
+    }
+
+    /// Encerra o Chrome do slot (espera o processo sair)
+    fn shutdown(&mut self) {
+        self.tab = None;
+        self.browser = None;
+        self.navigations = 0;
+    }
+}
+
+impl BrowserPool {
+    /// Pool com `config.size` slots; cada Chrome iniciado usa o próximo proxy
+    pub fn new(config: BrowserPoolConfig, proxies: ProxyPool) -> Self {
+        let slots = (0..config.size).map(Slot::new).collect();
+
+        Self {
+            inner: Arc::new(PoolInner {
+                config,
+                proxies: Mutex::new(proxies),
+                idle: Mutex::new(slots),
+                permits: Arc::new(Semaphore::new(config.size)),
+                launches: AtomicU64::new(0),
+                recycled: AtomicU64::new(0),
+                crashes: AtomicU64::new(0),
+                leases: AtomicU64::new(0),
+                navigations: AtomicU64::new(0),
+                wait_ms: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Empresta um slot, esperando se todos estiverem em uso. A preparação
+    /// (subir ou reiniciar o Chrome, health check) é bloqueante e roda em
+    /// `spawn_blocking`.
+    pub async fn acquire(&self) -> Result<BrowserLease> {
+        let started = Instant::now();
+        let permit = self.inner.permits.clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ArcsatError::Internal("browser pool is closed".to_string()))?;
+        self.inner.wait_ms.fetch_add(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+
+        let mut slot = self.inner.idle.lock().unwrap()
+            .pop()
+            .expect("a permit always has an idle slot");
+        let id = slot.id;
+
+        let inner = self.inner.clone();
+        let prepared = tokio::task::spawn_blocking(move || {
+            let tab = inner.prepare(&mut slot);
+            (slot, tab)
+        })
+        .await;
+
+        match prepared {
+            Ok((slot, Ok(tab))) => {
+                self.inner.leases.fetch_add(1, Ordering::Relaxed);
+                Ok(BrowserLease {
+                    tab,
+                    slot: Some(slot),
+                    pool: self.inner.clone(),
+                    _permit: permit,
+                })
+            }
+            Ok((slot, Err(e))) => {
+                self.inner.idle.lock().unwrap().push(slot);
+                Err(e)
+            }
+            // A preparação entrou em pânico com o slot: volta um slot vazio
+            Err(e) => {
+                self.inner.idle.lock().unwrap().push(Slot::new(id));
+                Err(ArcsatError::Internal(format!("Browser task failed: {}", e)))
+            }
+        }
+    }
+
+    pub fn metrics(&self) -> BrowserPoolMetrics {
+        let inner = &self.inner;
+
+        BrowserPoolMetrics {
+            size: inner.config.size,
+            in_use: inner.config.size - inner.permits.available_permits(),
+            launches: inner.launches.load(Ordering::Relaxed),
+            recycled: inner.recycled.load(Ordering::Relaxed),
+            crashes: inner.crashes.load(Ordering::Relaxed),
+            leases: inner.leases.load(Ordering::Relaxed),
+            navigations: inner.navigations.load(Ordering::Relaxed),
+            wait_ms: inner.wait_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl PoolInner {
+    /// Deixa o slot pronto para um job e devolve a aba dele
+    fn prepare(&self, slot: &mut Slot) -> Result<Arc<Tab>> {
+        if slot.browser.is_some() && slot.navigations >= self.config.max_navigations {
+            info!("Recycling browser {} after {} navigations", slot.id, slot.navigations);
+            self.recycled.fetch_add(1, Ordering::Relaxed);
+            slot.shutdown();
+        }
+
+        if slot.browser.as_ref().is_some_and(|browser| browser.get_version().is_err()) {
+            warn!("Browser {} stopped responding, restarting it", slot.id);
+            self.crashes.fetch_add(1, Ordering::Relaxed);
+            slot.shutdown();
+        }
+
+        if slot.browser.is_none() {
+            slot.proxy = self.proxies.lock().unwrap().next_proxy().cloned();
+            slot.browser = Some(launch(slot.proxy.as_ref())?);
+            self.launches.fetch_add(1, Ordering::Relaxed);
+        }
+        let browser = slot.browser.as_ref().expect("browser was just launched");
+
+        // Aba reaproveitada entre jobs; aba fechada ou travada é trocada
+        let tab = match slot.tab.take() {
+            Some(tab) if tab.get_target_info().is_ok() => tab,
+            previous => {
+                if previous.is_some() {
+                    warn!("Tab of browser {} stopped responding, opening a new one", slot.id);
+                    self.crashes.fetch_add(1, Ordering::Relaxed);
+                }
+                let tab = browser.new_tab().map_err(|e| ArcsatError::Scraping(e.to_string()))?;
+                authenticate_proxy(&tab, slot.proxy.as_ref())?;
+                tab
+            }
+        };
+
+        // Cada job começa sem os cookies do anterior
+        tab.call_method(Network::ClearBrowserCookies(None))
+            .map_err(|e| ArcsatError::Scraping(e.to_string()))?;
+
+        slot.tab = Some(tab.clone());
+        Ok(tab)
+    }
+}
+
+/// Slot emprestado a um job; volta ao pool quando o lease é solto
+pub struct BrowserLease {
+    tab: Arc<Tab>,
+    slot: Option<Slot>,
+    pool: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl BrowserLease {
+    pub fn tab(&self) -> Arc<Tab> {
+        self.tab.clone()
+    }
+
+    /// Conta uma navegação do job para o reinício por `max_navigations`
+    pub fn record_navigation(&mut self) {
+        if let Some(slot) = &mut self.slot {
+            slot.navigations += 1;
+        }
+        self.pool.navigations.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for BrowserLease {
+    fn drop(&mut self) {
+        // O slot volta antes de o permit ser liberado
+        if let Some(slot) = self.slot.take() {
+            self.pool.idle.lock().unwrap().push(slot);
+        }
+    }
+}
+
+/// Sobe o Chrome com configurações stealth
+fn launch(proxy_config: Option<&ProxyConfig>) -> Result<Browser> {
+    let mut launch_options = LaunchOptions::default_builder()
+        .headless(true)
+        .window_size(Some((1920, 1080)))
+        .idle_browser_timeout(IDLE_BROWSER_TIMEOUT)
+        .build()
+        .map_err(|e| ArcsatError::Config(format!("invalid browser launch options: {}", e)))?;
+
+    // Adicionar proxy se configurado (sem credenciais, ver `authenticate_proxy`)
+    let proxy_server = proxy_config.map(ProxyConfig::server);
+    launch_options.proxy_server = proxy_server.as_deref();
+
+    Browser::new(launch_options)
+        .map_err(|e| ArcsatError::Scraping(e.to_string()))
+}
+
+/// Responde ao `Fetch.authRequired` do proxy com o usuário e a senha dele
+fn authenticate_proxy(tab: &Tab, proxy_config: Option<&ProxyConfig>) -> Result<()> {
+    let Some((username, password)) = proxy_config.and_then(ProxyConfig::credentials) else {
+        return Ok(());
+    };
+
+    tab.enable_fetch(None, Some(true))
+        .and_then(|tab| tab.authenticate(Some(username), Some(password)))
+        .map_err(|e| ArcsatError::Scraping(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Enriquecimento pela página de produto (`ScrapingJob::enrich_details`)
+//!
+//! Depois da busca, cada produto tem a sua página baixada e passada ao
+//! `MarketplaceScraper::parse_detail`, que completa vendedor real, marca,
+//! GTIN/EAN, avaliações, frete e estoque. Os campos sem coluna própria vão
+//! para `extra`: `buy_box_seller`, `offer_count`, `gtin`, `shipping_cost`
+//! (0 = grátis), `stock_status` e `stock_quantity`.
+
+use super::{parse_brl_amount, MarketplaceScraper};
+use crate::models::*;
+use arcsat_core::Money;
+use futures::StreamExt;
+use reqwest::Client;
+use scraper::Html;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Limites do enriquecimento, por job
+#[derive(Debug, Clone, Copy)]
+pub struct DetailLimits {
+    /// Páginas de produto baixadas ao mesmo tempo
+    pub concurrency: usize,
+    /// Intervalo mínimo entre o início de duas requisições
+    pub interval: Duration,
+}
+
+impl Default for DetailLimits {
+    fn default() -> Self {
+        Self {
+            concurrency: 2,
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl DetailLimits {
+    pub fn from_config(config: &arcsat_core::config::MarketIntelligenceConfig) -> Self {
+        Self {
+            concurrency: config.detail_concurrency.max(1),
+            interval: Duration::from_millis(config.detail_interval_ms),
+        }
+    }
+}
+
+/// Sinal de estoque da página
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StockStatus {
+    InStock,
+    /// Poucas unidades ("Apenas 3 em estoque", "Último disponível")
+    Limited,
+    OutOfStock,
+}
+
+impl StockStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockStatus::InStock => "in_stock",
+            StockStatus::Limited => "limited",
+            StockStatus::OutOfStock => "out_of_stock",
+        }
+    }
+
+    /// `availability` do schema.org (`https://schema.org/InStock` etc.)
+    pub fn from_schema(availability: &str) -> Self {
+        let kind = availability.rsplit('/').next().unwrap_or(availability);
+
+        match kind {
+            "OutOfStock" | "SoldOut" | "Discontinued" => StockStatus::OutOfStock,
+            "LimitedAvailability" => StockStatus::Limited,
+            _ => StockStatus::InStock,
+        }
+    }
+
+    /// Texto de disponibilidade da página ("Em estoque", "Apenas 3 em
+    /// estoque", "(23 disponíveis)", "Indisponível") e a quantidade, se houver
+    pub fn from_text(text: &str) -> (Self, Option<i64>) {
+        let lower = text.to_lowercase();
+        let quantity = lower
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|digits| !digits.is_empty())
+            .and_then(|digits| digits.parse().ok());
+
+        if lower.contains("indisponível") || lower.contains("não disponível") || lower.contains("esgotado") {
+            (StockStatus::OutOfStock, None)
+        } else if lower.contains("apenas") || lower.contains("último") || lower.contains("restam") {
+            (StockStatus::Limited, quantity.or(Some(1)))
+        } else {
+            (StockStatus::InStock, quantity)
+        }
+    }
+}
+
+/// Grava `key` em `product.extra` (que passa a ser objeto, se não era)
+pub fn set_extra(product: &mut ScrapedProduct, key: &str, value: Value) {
+    if !product.extra.is_object() {
+        product.extra = Value::Object(Default::default());
+    }
+    product.extra[key] = value;
+}
+
+/// Aplica o sinal de estoque ao produto
+pub fn set_stock(product: &mut ScrapedProduct, text: &str) {
+    let (status, quantity) = StockStatus::from_text(text);
+
+    product.availability = status != StockStatus::OutOfStock;
+    set_extra(product, "stock_status", status.as_str().into());
+    if let Some(quantity) = quantity {
+        set_extra(product, "stock_quantity", quantity.into());
+    }
+}
+
+/// Frete da página: "Frete grátis"/"GRÁTIS" é 0
+pub fn set_shipping(product: &mut ScrapedProduct, text: &str) {
+    let cost = if text.to_lowercase().contains("grátis") {
+        Some(Money::zero(product.price.currency()))
+    } else {
+        parse_brl_amount(text)
+    };
+
+    if let Some(cost) = cost {
+        set_extra(product, "shipping_cost", serde_json::json!(cost));
+    }
+}
+
+/// Primeiro número inteiro do texto, ignorando separador de milhar
+/// ("1.234 avaliações" → 1234, "Novo (5) a partir de" → 5)
+pub fn count_in(text: &str) -> Option<i64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+
+    text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// Espaça as requisições de um job: cada uma espera `interval` depois da anterior
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let at = {
+            let mut next = self.next.lock().await;
+            let at = (*next).max(Instant::now());
+            *next = at + self.interval;
+            at
+        };
+
+        tokio::time::sleep_until(at).await;
+    }
+}
+
+/// Baixa a página de cada produto e aplica `parse_detail`. Falhas numa
+/// página mantêm o produto como veio da busca. Retorna quantos foram
+/// enriquecidos.
+pub async fn enrich(
+    scraper: &dyn MarketplaceScraper,
+    client: &Client,
+    limits: DetailLimits,
+    products: &mut [ScrapedProduct],
+    cancel: &CancellationToken,
+) -> usize {
+    let limiter = RateLimiter::new(limits.interval);
+    let enriched = AtomicUsize::new(0);
+
+    futures::stream::iter(products.iter_mut())
+        .for_each_concurrent(limits.concurrency, |product| {
+            let limiter = &limiter;
+            let enriched = &enriched;
+
+            async move {
+                if cancel.is_cancelled() || product.url.is_empty() {
+                    return;
+                }
+
+                limiter.wait().await;
+
+                match fetch(client, &product.url).await {
+                    Ok(html) => {
+                        let document = Html::parse_document(&html);
+                        scraper.parse_detail(product, &document);
+                        enriched.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => warn!("Failed to fetch product page {}: {}", product.url, e),
+                }
+            }
+        })
+        .await;
+
+    let enriched = enriched.into_inner();
+    info!("Enriched {} of {} products from their pages", enriched, products.len());
+    enriched
+}
+
+async fn fetch(client: &Client, url: &str) -> reqwest::Result<String> {
+    client.get(url).send().await?.error_for_status()?.text().await
+}
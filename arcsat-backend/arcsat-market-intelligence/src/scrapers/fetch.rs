@@ -0,0 +1,89 @@
+//! Páginas de busca baixadas por HTTP (`FetchStrategy::Http` e `Auto`)
+//!
+//! Os marketplaces renderizam a busca no servidor, então o HTML do `reqwest`
+//! costuma já trazer os produtos. O navegador só é necessário quando a
+//! resposta é um desafio anti-bot ou vem sem o `ready_selector` do scraper
+//! (resultados montados no cliente).
+
+use super::{MarketplaceScraper, SearchPage};
+use crate::models::*;
+use arcsat_core::{ArcsatError, Result};
+use reqwest::{Client, StatusCode};
+
+/// Trechos (em minúsculas) das páginas de desafio dos marketplaces e das CDNs
+/// anti-bot
+const CHALLENGE_MARKERS: &[&str] = &[
+    // Amazon ("Robot Check")
+    "/errors/validatecaptcha",
+    // Mercado Livre
+    "/gz/account-verification",
+    // Cloudflare
+    "challenge-platform",
+    "cf-chl-",
+    // PerimeterX
+    "px-captcha",
+    // Imperva
+    "_incapsula_resource",
+    // Akamai ("Access Denied")
+    "errors.edgesuite.net",
+];
+
+/// Página baixada por HTTP
+pub enum HttpPage {
+    /// Extraída; `body` é o HTML, guardado como amostra do monitor de seletores
+    Parsed { page: SearchPage, body: String },
+    /// Precisa do navegador; o motivo vai para o log
+    NeedsBrowser(&'static str),
+}
+
+/// A resposta é um bloqueio ou desafio anti-bot em vez da página pedida?
+pub fn is_bot_challenge(status: StatusCode, body: &str) -> bool {
+    if matches!(status.as_u16(), 403 | 429 | 503) {
+        return true;
+    }
+
+    let body = body.to_lowercase();
+    CHALLENGE_MARKERS.iter().any(|marker| body.contains(marker))
+}
+
+/// Baixa e extrai a página `page` da busca do job
+pub async fn fetch_page(client: &Client, scraper: &dyn MarketplaceScraper, job: &ScrapingJob, page: u32) -> Result<HttpPage> {
+    let url = scraper.search_url(job, page);
+
+    let response = client.get(&url).send().await?;
+    let status = response.status();
+    // Erro HTTP como `reqwest::Error`: 5xx e 408 são retentados, 404 não
+    let http_error = response.error_for_status_ref().err();
+    let body = response.text().await?;
+
+    if is_bot_challenge(status, &body) {
+        return Ok(HttpPage::NeedsBrowser("bot challenge"));
+    }
+    if let Some(error) = http_error {
+        return Err(ArcsatError::Http(error));
+    }
+
+    let parsed = scraper.parse_search(job, &body, page);
+
+    // Sem o `ready_selector` do scraper, pede o navegador
+    if !parsed.ready {
+        return Ok(HttpPage::NeedsBrowser("required selector missing"));
+    }
+
+    Ok(HttpPage::Parsed { page: parsed, body })
+}
+
+/// Erro da página do navegador em que o `ready_selector` não apareceu no
+/// prazo. Desafio anti-bot, página lenta ou proxy instável passam numa nova
+/// tentativa (`Scraping`); só a página que terminou de carregar, sem desafio,
+/// num marketplace que costuma ter cards (`cards_expected`) é mudança de
+/// layout (`Parse`).
+pub fn unready_page_error(html: &str, loaded: bool, selector: &str, cards_expected: bool) -> ArcsatError {
+    if is_bot_challenge(StatusCode::OK, html) {
+        ArcsatError::Scraping(format!("bot challenge in the browser instead of {:?}", selector))
+    } else if loaded && cards_expected {
+        ArcsatError::Parse(format!("ready selector {:?} not found on a loaded page", selector))
+    } else {
+        ArcsatError::Scraping(format!("ready selector {:?} not found in time", selector))
+    }
+}
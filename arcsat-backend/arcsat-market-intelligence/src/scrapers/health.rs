@@ -0,0 +1,120 @@
+//! Estatísticas de extração da busca, para o monitor de seletores
+//! (`crate::selector_health`)
+//!
+//! Cada página conta os cards encontrados e, entre eles, quantos vieram sem
+//! título, preço, link ou id. O job soma as páginas e guarda o HTML da última
+//! página com produtos, que vira a amostra "boa" do marketplace.
+
+use crate::models::*;
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Campos obrigatórios presentes num card
+#[derive(Debug, Clone, Copy)]
+pub struct CardFields {
+    pub title: bool,
+    pub price: bool,
+    pub url: bool,
+}
+
+/// Extração de uma página de busca
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStats {
+    /// Cards (ou itens da API) encontrados
+    pub cards: usize,
+    /// Produtos extraídos dos cards
+    pub products: usize,
+    pub missing_title: usize,
+    pub missing_price: usize,
+    pub missing_url: usize,
+    /// Produtos descartados por não ter id no marketplace (sem ele, todos
+    /// cairiam na mesma linha de `(job_id, external_id)`)
+    #[serde(default)]
+    pub missing_id: usize,
+    /// Página de "nenhum resultado" do marketplace: zero cards não é falha
+    /// de extração
+    #[serde(default)]
+    pub no_results: bool,
+}
+
+impl PageStats {
+    /// Conta os cards e os campos ausentes em cada um (`products` é
+    /// preenchido por quem extraiu a página)
+    pub fn from_cards<'a>(cards: impl Iterator<Item = ElementRef<'a>>, fields: impl Fn(ElementRef<'a>) -> CardFields) -> Self {
+        cards.map(&fields).fold(Self::default(), |mut stats, card| {
+            stats.cards += 1;
+            stats.missing_title += usize::from(!card.title);
+            stats.missing_price += usize::from(!card.price);
+            stats.missing_url += usize::from(!card.url);
+            stats
+        })
+    }
+
+    /// Sem contagem de cards do scraper: cada produto extraído é um card
+    pub fn from_products(products: &[ScrapedProduct]) -> Self {
+        Self {
+            cards: products.len(),
+            products: products.len(),
+            missing_title: products.iter().filter(|p| p.title.is_empty()).count(),
+            missing_price: products.iter().filter(|p| !p.price.is_positive()).count(),
+            missing_url: products.iter().filter(|p| p.url.is_empty()).count(),
+            missing_id: 0,
+            no_results: false,
+        }
+    }
+
+    fn add(&mut self, other: &PageStats) {
+        self.cards += other.cards;
+        self.products += other.products;
+        self.missing_title += other.missing_title;
+        self.missing_price += other.missing_price;
+        self.missing_url += other.missing_url;
+        self.missing_id += other.missing_id;
+        self.no_results |= other.no_results;
+    }
+}
+
+/// Extração da busca de um job: soma das páginas e a amostra de HTML
+#[derive(Debug, Clone, Default)]
+pub struct SearchStats {
+    pub pages: u32,
+    pub totals: PageStats,
+    /// URL e HTML (ou JSON, nas APIs) da última página com produtos
+    pub sample: Option<(String, String)>,
+}
+
+impl SearchStats {
+    /// Registra a página `page` e loga a extração dela
+    pub fn record(&mut self, job: &ScrapingJob, page: u32, stats: &PageStats, url: String, body: String) {
+        info!(
+            "{:?} page {}: {} cards, {} products (missing title {}, price {}, url {}, id {})",
+            job.marketplace,
+            page,
+            stats.cards,
+            stats.products,
+            stats.missing_title,
+            stats.missing_price,
+            stats.missing_url,
+            stats.missing_id
+        );
+
+        self.pages += 1;
+        self.totals.add(stats);
+        if stats.products > 0 {
+            self.sample = Some((url, body));
+        }
+    }
+
+    /// Produtos por card; `None` sem nenhum card
+    pub fn parse_yield(&self) -> Option<f64> {
+        (self.totals.cards > 0).then(|| self.totals.products as f64 / self.totals.cards as f64)
+    }
+}
+
+/// Resultado da busca de um job
+#[derive(Debug, Clone, Default)]
+pub struct SearchResult {
+    pub products: Vec<ScrapedProduct>,
+    pub stats: SearchStats,
+}
@@ -0,0 +1,32 @@
+//! Cliente HTTP dos scrapers que não passam pelo navegador
+
+use crate::proxy::ProxyConfig;
+use arcsat_core::{ArcsatError, Result};
+use reqwest::Client;
+use std::time::Duration;
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+    (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Cliente HTTP de um job: cookies próprios e o proxy configurado
+pub fn http_client(proxy_config: Option<&ProxyConfig>) -> Result<Client> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .cookie_store(true)
+        .timeout(REQUEST_TIMEOUT);
+
+    if let Some(proxy) = proxy_config {
+        let mut reqwest_proxy = reqwest::Proxy::all(&proxy.url)
+            .map_err(|e| ArcsatError::Config(format!("Invalid proxy URL: {}", e)))?;
+
+        if let (Some(username), Some(password)) = (&proxy.username, &proxy.password) {
+            reqwest_proxy = reqwest_proxy.basic_auth(username, password);
+        }
+
+        builder = builder.proxy(reqwest_proxy);
+    }
+
+    Ok(builder.build()?)
+}
@@ -0,0 +1,34 @@
+//! Scrapers de marketplaces que montam a busca a partir de uma API JSON
+//!
+//! Shopee e AliExpress renderizam a listagem no cliente com o JSON das suas
+//! APIs de busca; chamar a API direto com `reqwest` dispensa o navegador.
+
+use crate::models::*;
+use arcsat_core::Result;
+use reqwest::{Client, RequestBuilder};
+use serde_json::Value;
+
+/// Extração de um marketplace via API JSON. A sessão HTTP (cookies, proxy)
+/// e o laço de páginas ficam com o `ScraperRegistry`.
+pub trait ApiScraper: Send + Sync {
+    fn marketplace(&self) -> Marketplace;
+
+    /// Página aberta antes da busca para receber os cookies de sessão
+    fn warmup_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Request da página `page` (a partir de 1) da busca do job
+    fn search_request(&self, client: &Client, job: &ScrapingJob, page: u32) -> RequestBuilder;
+
+    /// Produtos da resposta. Respostas de bloqueio ou erro da API são `Err`.
+    fn parse_response(&self, job: &ScrapingJob, page: u32, body: &Value) -> Result<Vec<ScrapedProduct>>;
+
+    /// Existe página depois de `page`?
+    fn has_next_page(&self, body: &Value, page: u32) -> bool;
+}
+
+/// Posição do item na busca, contando as páginas anteriores (a partir de 1)
+pub fn search_rank(page: u32, page_size: u32, index: usize) -> i32 {
+    ((page - 1) * page_size) as i32 + index as i32 + 1
+}
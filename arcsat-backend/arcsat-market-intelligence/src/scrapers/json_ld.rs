@@ -0,0 +1,124 @@
+//! Dados estruturados (`application/ld+json`, schema.org `Product`) das
+//! páginas de produto. A maioria dos marketplaces publica marca, GTIN,
+//! avaliações e a oferta principal nesse bloco, que é a extração padrão do
+//! detalhe (`MarketplaceScraper::parse_detail`).
+
+use super::details::{set_extra, StockStatus};
+use crate::models::*;
+use arcsat_core::{Currency, Money};
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+/// Completa o produto com o `Product` do JSON-LD da página, se houver
+pub fn apply(product: &mut ScrapedProduct, document: &Html) {
+    let Some(node) = product_node(document) else {
+        return;
+    };
+
+    if let Some(brand) = name_of(&node["brand"]) {
+        product.brand = Some(brand);
+    }
+
+    if let Some(gtin) = ["gtin13", "gtin", "gtin14", "gtin12", "gtin8"]
+        .iter()
+        .find_map(|key| text_of(&node[key]))
+    {
+        set_extra(product, "gtin", gtin.into());
+    }
+
+    if product.image_url.is_none() {
+        product.image_url = first(&node["image"]).and_then(|image| text_of(image).or_else(|| text_of(&image["url"])));
+    }
+
+    let rating = &node["aggregateRating"];
+    if let Some(value) = number_of(&rating["ratingValue"]) {
+        product.rating = Some(value);
+    }
+    if let Some(count) = number_of(&rating["reviewCount"]).or_else(|| number_of(&rating["ratingCount"])) {
+        product.num_reviews = product.num_reviews.max(count as i32);
+    }
+
+    let offers = &node["offers"];
+    if let Some(count) = number_of(&offers["offerCount"]) {
+        set_extra(product, "offer_count", (count as i64).into());
+    }
+
+    // AggregateOffer traz as ofertas em `offers`; a primeira ganha a vitrine
+    let offer = first(&offers["offers"]).or_else(|| first(offers));
+    if let Some(offer) = offer {
+        if let Some(seller) = name_of(&offer["seller"]) {
+            set_extra(product, "buy_box_seller", seller.clone().into());
+            product.seller_name = seller;
+        }
+
+        if let Some(availability) = text_of(&offer["availability"]) {
+            let status = StockStatus::from_schema(&availability);
+            product.availability = status != StockStatus::OutOfStock;
+            set_extra(product, "stock_status", status.as_str().into());
+        }
+
+        let rate = &offer["shippingDetails"]["shippingRate"];
+        let currency = text_of(&rate["currency"])
+            .and_then(|code| Currency::new(&code))
+            .unwrap_or(product.price.currency());
+        if let Some(cost) = number_of(&rate["value"]).and_then(|value| Money::from_f64(value, currency)) {
+            set_extra(product, "shipping_cost", serde_json::json!(cost));
+        }
+    }
+}
+
+/// Primeiro nó `Product`: o bloco pode ser um objeto, uma lista ou um `@graph`
+fn product_node(document: &Html) -> Option<Value> {
+    let selector = Selector::parse("script[type='application/ld+json']").expect("valid selector");
+
+    document
+        .select(&selector)
+        .filter_map(|script| serde_json::from_str::<Value>(&script.text().collect::<String>()).ok())
+        .flat_map(|value| match value {
+            Value::Array(items) => items,
+            Value::Object(ref map) if map.contains_key("@graph") => {
+                map["@graph"].as_array().cloned().unwrap_or_default()
+            }
+            other => vec![other],
+        })
+        .find(is_product)
+}
+
+fn is_product(node: &Value) -> bool {
+    match &node["@type"] {
+        Value::String(kind) => kind == "Product",
+        Value::Array(kinds) => kinds.iter().any(|kind| kind == "Product"),
+        _ => false,
+    }
+}
+
+/// Objeto ou primeiro item de uma lista
+fn first(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.first(),
+        Value::Null => None,
+        other => Some(other),
+    }
+}
+
+fn text_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.trim().to_string()).filter(|text| !text.is_empty()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Nome de uma marca/organização, como texto ou `{ "name": ... }`
+fn name_of(value: &Value) -> Option<String> {
+    text_of(value).or_else(|| text_of(&value["name"]))
+}
+
+/// Número, aceitando string ("4.6", "4,6")
+fn number_of(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().replace(',', ".").parse().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,134 @@
+//! Magazine Luiza
+//!
+//! Os cards da busca trazem o preço à vista (PIX) em destaque e, ao lado, o
+//! preço a prazo com o parcelamento. `price` é o preço a prazo (o "cheio");
+//! PIX, preço de lista e parcelas vão para `pricing`.
+
+use super::{listing_product, parse_brl_amount, parse_brl_price, BrlPrice, CardFields, MarketplaceScraper, PageStats, Selectors, SelectorSpec};
+use crate::models::*;
+use arcsat_core::Result;
+use scraper::Html;
+
+/// Produtos vendidos pela própria Magalu não mostram vendedor no card
+const DEFAULT_SELLER: &str = "Magazine Luiza";
+
+const SELECTORS: &SelectorSpec = &[
+    ("card", "a[data-testid='product-card-container']"),
+    ("title", "[data-testid='product-title']"),
+    ("image", "img[data-testid='image']"),
+    ("list_price", "[data-testid='price-original']"),
+    ("cash_price", "[data-testid='price-value']"),
+    ("installment", "[data-testid='installment']"),
+    ("seller", "[data-testid='seller-name']"),
+    ("rating", "[data-testid='review'] [data-testid='rating-value']"),
+    ("reviews", "[data-testid='review'] [data-testid='rating-count']"),
+    // Botão "próxima página", desabilitado na última
+    ("next_page", "[data-testid='pagination'] [aria-label='Próxima página']:not([disabled]):not([aria-disabled='true'])"),
+];
+
+pub struct MagaluScraper {
+    selectors: Selectors,
+}
+
+impl MagaluScraper {
+    pub fn new() -> Result<Self> {
+        Ok(Self { selectors: Selectors::compile(SELECTORS)? })
+    }
+}
+
+impl MarketplaceScraper for MagaluScraper {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::Magalu
+    }
+
+    fn search_url(&self, job: &ScrapingJob, page: u32) -> String {
+        format!(
+            "{}/busca/{}/?page={}",
+            Marketplace::Magalu.base_url(),
+            urlencoding::encode(&job.search_query),
+            page
+        )
+    }
+
+    fn ready_selector(&self) -> Option<&str> {
+        Some("a[data-testid='product-card-container']")
+    }
+
+    /// "Sua busca por ... não encontrou resultado algum"
+    fn no_results_selector(&self) -> Option<&str> {
+        Some("[data-testid='no-results']")
+    }
+
+    fn parse_listing(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct> {
+        let base_url = Marketplace::Magalu.base_url();
+
+        self.selectors
+            .all(document, "card")
+            .filter_map(|card| {
+                let title = self.selectors.text(card, "title")?;
+
+                // "ou R$ 854,05 no Pix" e "R$ 899,00 em 10x de R$ 89,90 sem
+                // juros": o preço a prazo é o de venda. Sem preço o produto
+                // está indisponível e não entra na coleta.
+                let text = ["cash_price", "installment"]
+                    .iter()
+                    .filter_map(|field| self.selectors.text(card, field))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let BrlPrice { price, mut pricing } = parse_brl_price(&text)?;
+                pricing.list_price = self.selectors
+                    .text(card, "list_price")
+                    .and_then(|text| parse_brl_amount(&text))
+                    .filter(|list| *list > price);
+
+                let href = card.value().attr("href").unwrap_or_default();
+                let url = if href.starts_with("http") {
+                    href.to_string()
+                } else {
+                    format!("{}{}", base_url, href)
+                };
+
+                let rating = self.selectors
+                    .text(card, "rating")
+                    .and_then(|text| text.replace(',', ".").parse::<f64>().ok());
+                let num_reviews = self.selectors
+                    .text(card, "reviews")
+                    .map(|text| text.chars().filter(char::is_ascii_digit).collect::<String>())
+                    .and_then(|digits| digits.parse::<i32>().ok())
+                    .unwrap_or(0);
+
+                Some(ScrapedProduct {
+                    external_id: product_id(&url).unwrap_or_default(),
+                    image_url: self.selectors.attr(card, "image", "src"),
+                    seller_name: self.selectors
+                        .text(card, "seller")
+                        .map(|seller| seller.trim_start_matches("Vendido por").trim().to_string())
+                        .unwrap_or_else(|| DEFAULT_SELLER.to_string()),
+                    rating,
+                    num_reviews,
+                    pricing,
+                    ..listing_product(job, title, price, url)
+                })
+            })
+            .collect()
+    }
+
+    fn page_stats(&self, document: &Html) -> Option<PageStats> {
+        Some(PageStats::from_cards(self.selectors.all(document, "card"), |card| CardFields {
+            title: self.selectors.exists_in(card, "title"),
+            price: self.selectors.exists_in(card, "installment") || self.selectors.exists_in(card, "cash_price"),
+            url: card.value().attr("href").is_some_and(|href| !href.is_empty()),
+        }))
+    }
+
+    fn has_next_page(&self, document: &Html, _page: u32) -> bool {
+        self.selectors.exists(document, "next_page")
+    }
+}
+
+/// Id do produto na URL: `/<slug>/p/<id>/<categoria>/<subcategoria>/`
+fn product_id(url: &str) -> Option<String> {
+    let mut segments = url.split('/');
+    segments.find(|segment| *segment == "p")?;
+    segments.next().filter(|id| !id.is_empty()).map(str::to_string)
+}
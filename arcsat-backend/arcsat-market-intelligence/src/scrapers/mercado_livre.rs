@@ -0,0 +1,147 @@
+//! Mercado Livre
+
+use super::details::{count_in, set_extra, set_shipping, set_stock};
+use super::{detect_currency, json_ld, listing_product, parse_brl_parts, CardFields, MarketplaceScraper, PageStats, Selectors, SelectorSpec};
+use crate::models::*;
+use arcsat_core::{Money, Result};
+use scraper::{ElementRef, Html};
+
+/// Resultados por página da busca (`_Desde_` é o offset)
+const PAGE_SIZE: u32 = 50;
+
+const SELECTORS: &SelectorSpec = &[
+    ("card", "li.ui-search-layout__item"),
+    ("title", "h2.ui-search-item__title"),
+    // Preço atual e o anterior (riscado), com o símbolo e os centavos em spans à parte
+    ("price", ".ui-search-price__part:not(.andes-money-amount--previous)"),
+    ("list_price", ".andes-money-amount--previous"),
+    ("symbol", ".andes-money-amount__currency-symbol"),
+    ("fraction", ".andes-money-amount__fraction"),
+    ("cents", ".andes-money-amount__cents"),
+    ("link", "a.ui-search-link"),
+    // "Seguinte" da paginação, desabilitado na última página
+    ("next_page", "li.andes-pagination__button--next:not(.andes-pagination__button--disabled) a"),
+    // Página de produto
+    ("detail_seller", ".ui-pdp-seller__link-trigger"),
+    ("detail_stock", ".ui-pdp-buybox__quantity__available"),
+    ("detail_shipping", "#shipping_summary .ui-pdp-media__title"),
+    ("detail_reviews", ".ui-pdp-review__amount"),
+];
+
+pub struct MercadoLivreScraper {
+    selectors: Selectors,
+}
+
+impl MercadoLivreScraper {
+    pub fn new() -> Result<Self> {
+        Ok(Self { selectors: Selectors::compile(SELECTORS)? })
+    }
+
+    /// Valor do primeiro `andes-money-amount` do campo dentro do card
+    fn amount(&self, card: ElementRef, field: &str) -> Option<Money> {
+        let amount = card.select(self.selectors.get(field)).next()?;
+        let whole = self.selectors.text(amount, "fraction")?;
+        let currency = self.selectors
+            .text(amount, "symbol")
+            .and_then(|symbol| detect_currency(&symbol))
+            .unwrap_or_default();
+
+        parse_brl_parts(&whole, self.selectors.text(amount, "cents").as_deref(), currency)
+    }
+}
+
+impl MarketplaceScraper for MercadoLivreScraper {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::MercadoLivre
+    }
+
+    fn search_url(&self, job: &ScrapingJob, page: u32) -> String {
+        format!(
+            "https://lista.mercadolivre.com.br/{}/_Desde_{}",
+            urlencoding::encode(&job.search_query.replace(' ', "-")),
+            (page - 1) * PAGE_SIZE
+        )
+    }
+
+    fn ready_selector(&self) -> Option<&str> {
+        Some("li.ui-search-layout__item")
+    }
+
+    /// "Não há anúncios que correspondam à sua busca"
+    fn no_results_selector(&self) -> Option<&str> {
+        Some(".ui-search-rescue")
+    }
+
+    fn parse_listing(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct> {
+        self.selectors
+            .all(document, "card")
+            .filter_map(|card| {
+                let title = self.selectors.text(card, "title")?;
+                let price = self.amount(card, "price").filter(|price| price.is_positive())?;
+                let url = self.selectors.attr(card, "link", "href").unwrap_or_default();
+
+                Some(ScrapedProduct {
+                    external_id: item_id(&url),
+                    seller_name: "Mercado Livre".to_string(),
+                    pricing: Pricing {
+                        list_price: self.amount(card, "list_price").filter(|list| *list > price),
+                        ..Pricing::default()
+                    },
+                    ..listing_product(job, title, price, url)
+                })
+            })
+            .collect()
+    }
+
+    fn page_stats(&self, document: &Html) -> Option<PageStats> {
+        Some(PageStats::from_cards(self.selectors.all(document, "card"), |card| CardFields {
+            title: self.selectors.exists_in(card, "title"),
+            price: self.amount(card, "price").is_some(),
+            url: self.selectors.attr(card, "link", "href").is_some(),
+        }))
+    }
+
+    fn has_next_page(&self, document: &Html, _page: u32) -> bool {
+        self.selectors.exists(document, "next_page")
+    }
+
+    fn parse_detail(&self, product: &mut ScrapedProduct, document: &Html) {
+        json_ld::apply(product, document);
+
+        let page = document.root_element();
+        let selectors = &self.selectors;
+
+        // "Vendido por LOJA X": o JSON-LD nem sempre traz o vendedor
+        if let Some(seller) = selectors.text(page, "detail_seller") {
+            let seller = seller.trim_start_matches("Vendido por").trim().to_string();
+            set_extra(product, "buy_box_seller", seller.clone().into());
+            product.seller_name = seller;
+        }
+        if let Some(stock) = selectors.text(page, "detail_stock") {
+            set_stock(product, &stock);
+        }
+        if let Some(shipping) = selectors.text(page, "detail_shipping") {
+            set_shipping(product, &shipping);
+        }
+        if let Some(reviews) = selectors.text(page, "detail_reviews").and_then(|text| count_in(&text)) {
+            product.num_reviews = product.num_reviews.max(reviews as i32);
+        }
+    }
+}
+
+/// Código do anúncio na URL: `/p/MLB19523021` (catálogo) ou
+/// `/MLB-3456789012-notebook-..._JM` (anúncio) → `MLB19523021`, `MLB3456789012`
+fn item_id(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+
+    path.match_indices("MLB")
+        .find_map(|(start, _)| {
+            let digits: String = path[start + 3..]
+                .trim_start_matches('-')
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            (!digits.is_empty()).then(|| format!("MLB{}", digits))
+        })
+        .unwrap_or_else(|| path.rsplit('/').next().unwrap_or("").to_string())
+}
@@ -0,0 +1,584 @@
+//! Scrapers de marketplaces
+//!
+//! Cada marketplace implementa `MarketplaceScraper` (URL de busca,
+//! paginação, extração da listagem e do detalhe) num módulo próprio; o
+//! `ScraperRegistry` guarda um scraper por `Marketplace` e executa o laço de
+//! páginas no navegador. Marketplaces que servem a busca em JSON implementam
+//! `ApiScraper` e são chamados direto por HTTP. Marketplace novo é um `impl`
+//! novo registrado em `ScraperRegistry::new`.
+//!
+//! Jobs com `enrich_details` passam por uma segunda etapa que visita a
+//! página de cada produto (`details`). As páginas de busca vêm por HTTP ou
+//! pelo navegador conforme a `FetchStrategy` (`fetch`); os navegadores vêm de
+//! um `BrowserPool` compartilhado pelos jobs.
+
+mod aliexpress;
+mod amazon;
+mod americanas;
+mod browser_pool;
+mod details;
+mod fetch;
+mod health;
+mod http;
+mod json_api;
+mod json_ld;
+mod magalu;
+mod mercado_livre;
+mod price;
+mod selectors;
+mod shopee;
+
+pub use aliexpress::AliExpressScraper;
+pub use amazon::AmazonScraper;
+pub use americanas::AmericanasScraper;
+pub use browser_pool::{BrowserLease, BrowserPool, BrowserPoolConfig, BrowserPoolMetrics};
+pub use details::{DetailLimits, StockStatus};
+pub use fetch::{is_bot_challenge, unready_page_error};
+pub use health::{CardFields, PageStats, SearchResult, SearchStats};
+pub use json_api::ApiScraper;
+pub use magalu::MagaluScraper;
+pub use mercado_livre::MercadoLivreScraper;
+pub use price::{detect_currency, parse_brl_amount, parse_brl_parts, parse_brl_price, BrlPrice};
+pub use selectors::{SelectorSpec, Selectors};
+pub use shopee::ShopeeScraper;
+
+use arcsat_core::{ArcsatError, Money, Result};
+use crate::models::*;
+use crate::proxy::{ProxyConfig, ProxyPool};
+use fetch::HttpPage;
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Extração de um marketplace. Só faz parsing: a navegação fica com o
+/// `ScraperRegistry`, então os métodos rodam sobre HTML salvo.
+pub trait MarketplaceScraper: Send + Sync {
+    fn marketplace(&self) -> Marketplace;
+
+    /// URL da página `page` (a partir de 1) da busca do job
+    fn search_url(&self, job: &ScrapingJob, page: u32) -> String;
+
+    /// Elemento que indica que os resultados carregaram: o navegador espera
+    /// por ele e, no HTTP, a página sem ele vai para o navegador. `None` não
+    /// exige nada.
+    fn ready_selector(&self) -> Option<&str> {
+        None
+    }
+
+    /// Elemento da página de "nenhum resultado" da busca. O navegador espera
+    /// por ele ou pelo `ready_selector`.
+    fn no_results_selector(&self) -> Option<&str> {
+        None
+    }
+
+    /// A página é a busca sem resultados? Por padrão, o
+    /// `no_results_selector` está nela.
+    fn is_no_results(&self, document: &Html) -> bool {
+        self.no_results_selector().is_some_and(|selector| matches_selector(document, selector))
+    }
+
+    /// Estratégia de download quando o job não escolhe uma
+    fn fetch_strategy(&self) -> FetchStrategy {
+        FetchStrategy::Auto
+    }
+
+    /// Produtos da página de busca
+    fn parse_listing(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct>;
+
+    /// Cards da página e campos ausentes neles, para o monitor de
+    /// seletores. `None` conta só os produtos extraídos.
+    fn page_stats(&self, _document: &Html) -> Option<PageStats> {
+        None
+    }
+
+    /// Existe página depois de `page`? Por padrão segue até `max_pages`.
+    fn has_next_page(&self, _document: &Html, _page: u32) -> bool {
+        true
+    }
+
+    /// Completa o produto com os dados da página de detalhe. O padrão lê o
+    /// `Product` do JSON-LD da página.
+    fn parse_detail(&self, product: &mut ScrapedProduct, document: &Html) {
+        json_ld::apply(product, document);
+    }
+
+    /// Extrai a página `page` da busca a partir do HTML baixado (pelo
+    /// `reqwest`, pelo navegador ou salvo em `tests/fixtures`)
+    fn parse_search(&self, job: &ScrapingJob, html: &str, page: u32) -> SearchPage {
+        let document = Html::parse_document(html);
+
+        let mut products = self.parse_listing(job, &document);
+        let mut stats = self.page_stats(&document).unwrap_or_else(|| PageStats::from_products(&products));
+
+        // Sem id no marketplace não há como gravar o produto sem colidir com
+        // os outros sem id; fica de fora e conta na queda do rendimento
+        let listed = products.len();
+        products.retain(|product| !product.external_id.is_empty());
+        stats.missing_id = listed - products.len();
+        stats.products = products.len();
+
+        // Busca sem resultados: página carregada, sem produtos nem próxima
+        stats.no_results = stats.cards == 0 && self.is_no_results(&document);
+        let ready = stats.no_results
+            || self.ready_selector().is_none_or(|selector| matches_selector(&document, selector));
+
+        SearchPage {
+            products,
+            has_next: !stats.no_results && self.has_next_page(&document, page),
+            ready,
+            stats,
+        }
+    }
+}
+
+/// Algum elemento do documento casa com o seletor?
+fn matches_selector(document: &Html, selector: &str) -> bool {
+    Selector::parse(selector).is_ok_and(|selector| document.select(&selector).next().is_some())
+}
+
+/// Página de busca extraída
+#[derive(Debug, Clone)]
+pub struct SearchPage {
+    pub products: Vec<ScrapedProduct>,
+    pub has_next: bool,
+    /// O `ready_selector` está na página (sempre, sem seletor) ou ela é a
+    /// busca sem resultados
+    pub ready: bool,
+    pub stats: PageStats,
+}
+
+/// Origem das páginas de um job: cliente HTTP, navegador emprestado e
+/// quantas páginas vieram de cada um
+struct PageSource {
+    client: Option<reqwest::Client>,
+    strategy: FetchStrategy,
+    use_browser: bool,
+    lease: Option<BrowserLease>,
+    /// A linha de base do marketplace tem cards (ver `scrape_search_expecting`)
+    cards_expected: bool,
+    http_pages: u32,
+    browser_pages: u32,
+}
+
+/// Atraso base entre páginas; cada espera soma um jitter de até 1,5x
+const PAGE_DELAY: Duration = Duration::from_secs(2);
+
+/// Registry de scrapers por marketplace
+pub struct ScraperRegistry {
+    scrapers: HashMap<Marketplace, Arc<dyn MarketplaceScraper>>,
+    api_scrapers: HashMap<Marketplace, Arc<dyn ApiScraper>>,
+    proxy_config: Option<ProxyConfig>,
+    page_delay: Duration,
+    detail_limits: DetailLimits,
+    browser_pool: BrowserPool,
+}
+
+impl ScraperRegistry {
+    pub fn new(proxy_config: Option<ProxyConfig>) -> Result<Self> {
+        let mut registry = Self::empty(proxy_config);
+
+        registry.register(AmazonScraper::new()?);
+        registry.register(MercadoLivreScraper::new()?);
+        registry.register(MagaluScraper::new()?);
+        registry.register(AmericanasScraper::new()?);
+        registry.register_api(ShopeeScraper::new());
+        registry.register_api(AliExpressScraper::new());
+
+        Ok(registry)
+    }
+
+    /// Registry sem nenhum scraper
+    pub fn empty(proxy_config: Option<ProxyConfig>) -> Self {
+        let proxies = ProxyPool::new(proxy_config.iter().cloned().collect());
+
+        Self {
+            scrapers: HashMap::new(),
+            api_scrapers: HashMap::new(),
+            proxy_config,
+            page_delay: PAGE_DELAY,
+            detail_limits: DetailLimits::default(),
+            browser_pool: BrowserPool::new(BrowserPoolConfig::default(), proxies),
+        }
+    }
+
+    pub fn with_page_delay(mut self, page_delay: Duration) -> Self {
+        self.page_delay = page_delay;
+        self
+    }
+
+    /// Concorrência e intervalo do enriquecimento (`MI_DETAIL_*`)
+    pub fn set_detail_limits(&mut self, detail_limits: DetailLimits) {
+        self.detail_limits = detail_limits;
+    }
+
+    /// Troca o pool de navegadores (tamanho e proxies do worker)
+    pub fn set_browser_pool(&mut self, browser_pool: BrowserPool) {
+        self.browser_pool = browser_pool;
+    }
+
+    pub fn browser_pool(&self) -> &BrowserPool {
+        &self.browser_pool
+    }
+
+    /// Registra (ou substitui) o scraper do seu marketplace
+    pub fn register(&mut self, scraper: impl MarketplaceScraper + 'static) {
+        self.api_scrapers.remove(&scraper.marketplace());
+        self.scrapers.insert(scraper.marketplace(), Arc::new(scraper));
+    }
+
+    /// Registra (ou substitui) o scraper de API JSON do seu marketplace
+    pub fn register_api(&mut self, scraper: impl ApiScraper + 'static) {
+        self.scrapers.remove(&scraper.marketplace());
+        self.api_scrapers.insert(scraper.marketplace(), Arc::new(scraper));
+    }
+
+    pub fn get(&self, marketplace: Marketplace) -> Option<Arc<dyn MarketplaceScraper>> {
+        self.scrapers.get(&marketplace).cloned()
+    }
+
+    pub fn get_api(&self, marketplace: Marketplace) -> Option<Arc<dyn ApiScraper>> {
+        self.api_scrapers.get(&marketplace).cloned()
+    }
+
+    /// Marketplace sem scraper registrado é `Unsupported`
+    pub fn ensure_supported(&self, marketplace: Marketplace) -> Result<()> {
+        if self.scrapers.contains_key(&marketplace) || self.api_scrapers.contains_key(&marketplace) {
+            Ok(())
+        } else {
+            Err(unsupported(marketplace))
+        }
+    }
+
+    /// Executa o scraping do job (e o enriquecimento, se pedido). Com
+    /// `cancel` acionado, para antes da próxima página e devolve o que já
+    /// coletou.
+    pub async fn scrape(&self, job: &ScrapingJob, cancel: &CancellationToken) -> Result<Vec<ScrapedProduct>> {
+        Ok(self.scrape_search(job, cancel).await?.products)
+    }
+
+    /// Como `scrape`, com as estatísticas de extração da busca
+    pub async fn scrape_search(&self, job: &ScrapingJob, cancel: &CancellationToken) -> Result<SearchResult> {
+        self.scrape_search_expecting(job, cancel, false).await
+    }
+
+    /// Como `scrape_search`. Com `cards_expected` (a linha de base do
+    /// marketplace tem cards), página que carregou no navegador sem desafio
+    /// e sem o `ready_selector` é mudança de layout (`Parse`, definitiva); sem
+    /// ele, é falha transitória (`Scraping`).
+    pub async fn scrape_search_expecting(
+        &self,
+        job: &ScrapingJob,
+        cancel: &CancellationToken,
+        cards_expected: bool,
+    ) -> Result<SearchResult> {
+        info!("Starting scraping job {} for {:?}", job.id, job.marketplace);
+
+        let mut result = match self.get_api(job.marketplace) {
+            Some(scraper) => self.scrape_api(scraper.as_ref(), job, cancel).await?,
+            None => {
+                // Erro explícito: um job sem scraper nunca termina como `Completed`
+                let scraper = self.get(job.marketplace).ok_or_else(|| unsupported(job.marketplace))?;
+                self.scrape_pages(scraper, job, cancel, cards_expected).await?
+            }
+        };
+
+        if job.enrich_details && !cancel.is_cancelled() {
+            self.enrich_details(job, &mut result.products, cancel).await?;
+        }
+
+        Ok(result)
+    }
+
+    /// Visita a página de cada produto e completa os dados com o
+    /// `parse_detail` do scraper do marketplace. Marketplaces de API JSON
+    /// já trazem esses dados na busca e ficam como estão.
+    pub async fn enrich_details(
+        &self,
+        job: &ScrapingJob,
+        products: &mut [ScrapedProduct],
+        cancel: &CancellationToken,
+    ) -> Result<usize> {
+        let Some(scraper) = self.get(job.marketplace) else {
+            return Ok(0);
+        };
+
+        let client = http::http_client(self.proxy_config.as_ref())?;
+
+        Ok(details::enrich(scraper.as_ref(), &client, self.detail_limits, products, cancel).await)
+    }
+
+    /// Laço de páginas da busca, por HTTP ou no navegador conforme a
+    /// `FetchStrategy` do job (ou a padrão do scraper). No `Auto`, a primeira
+    /// página que precisa do navegador passa o resto do job para ele. Uma
+    /// página depois da primeira que não pode ser lida encerra a busca com o
+    /// que já foi coletado.
+    async fn scrape_pages(
+        &self,
+        scraper: Arc<dyn MarketplaceScraper>,
+        job: &ScrapingJob,
+        cancel: &CancellationToken,
+        cards_expected: bool,
+    ) -> Result<SearchResult> {
+        let strategy = job.fetch_strategy.unwrap_or_else(|| scraper.fetch_strategy());
+        let mut source = PageSource {
+            client: match strategy {
+                FetchStrategy::Browser => None,
+                FetchStrategy::Http | FetchStrategy::Auto => Some(http::http_client(self.proxy_config.as_ref())?),
+            },
+            strategy,
+            use_browser: strategy == FetchStrategy::Browser,
+            lease: None,
+            cards_expected,
+            http_pages: 0,
+            browser_pages: 0,
+        };
+
+        let shared_job = Arc::new(job.clone());
+        let mut products = Vec::new();
+        let mut stats = SearchStats::default();
+
+        for page in 1..=job.max_pages {
+            if cancel.is_cancelled() {
+                info!("Job {} cancelled after {} pages", job.id, page - 1);
+                break;
+            }
+
+            info!("Scraping {:?} page {} of {}", job.marketplace, page, job.max_pages);
+
+            let (parsed, body) = match self.fetch_search_page(&mut source, &scraper, &shared_job, page, cancel).await {
+                Ok(Some(fetched)) => fetched,
+                Ok(None) => break,
+                Err(e) if page > 1 => {
+                    warn!(
+                        "{:?} page {} could not be read, keeping the {} products from earlier pages: {}",
+                        job.marketplace,
+                        page,
+                        products.len(),
+                        e
+                    );
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            stats.record(job, page, &parsed.stats, scraper.search_url(job, page), body);
+            products.extend(parsed.products);
+
+            if !parsed.has_next {
+                break;
+            }
+        }
+
+        info!(
+            "Scraped {} products from {:?} ({} pages over HTTP, {} in the browser)",
+            products.len(),
+            job.marketplace,
+            source.http_pages,
+            source.browser_pages
+        );
+        Ok(SearchResult { products, stats })
+    }
+
+    /// Página `page` da busca e o HTML dela, por HTTP ou no navegador. `None`
+    /// se o job foi cancelado esperando um navegador.
+    async fn fetch_search_page(
+        &self,
+        source: &mut PageSource,
+        scraper: &Arc<dyn MarketplaceScraper>,
+        job: &Arc<ScrapingJob>,
+        page: u32,
+        cancel: &CancellationToken,
+    ) -> Result<Option<(SearchPage, String)>> {
+        if let (false, Some(client)) = (source.use_browser, &source.client) {
+            if page > 1 {
+                tokio::time::sleep(self.page_delay()).await;
+            }
+
+            match fetch::fetch_page(client, scraper.as_ref(), job, page).await? {
+                HttpPage::Parsed { page: parsed, body } => {
+                    source.http_pages += 1;
+                    return Ok(Some((parsed, body)));
+                }
+                HttpPage::NeedsBrowser(reason) if source.strategy == FetchStrategy::Auto => {
+                    info!("{:?} page {} needs a browser ({}), switching job {} to it", job.marketplace, page, reason, job.id);
+                    source.use_browser = true;
+                }
+                HttpPage::NeedsBrowser(reason) => {
+                    return Err(ArcsatError::Scraping(format!(
+                        "{:?} page {} needs a browser: {}",
+                        job.marketplace, page, reason
+                    )));
+                }
+            }
+        }
+
+        // Emprestado do pool só quando uma página precisa do navegador
+        if source.lease.is_none() {
+            source.lease = tokio::select! {
+                lease = self.browser_pool.acquire() => Some(lease?),
+                _ = cancel.cancelled() => {
+                    info!("Job {} cancelled while waiting for a browser", job.id);
+                    return Ok(None);
+                }
+            };
+        }
+        let browser = source.lease.as_mut().expect("browser lease was just acquired");
+
+        let fetched = self
+            .browser_page(browser, scraper.clone(), job.clone(), page, source.cards_expected)
+            .await?;
+        source.browser_pages += 1;
+        Ok(Some(fetched))
+    }
+
+    /// Uma página da busca numa aba emprestada do `BrowserPool`. A API do
+    /// `headless_chrome` é bloqueante: cada chamada roda em `spawn_blocking` e
+    /// a espera entre elas é do tokio, para não travar as threads do runtime
+    /// (e os outros jobs do worker).
+    async fn browser_page(
+        &self,
+        lease: &mut BrowserLease,
+        scraper: Arc<dyn MarketplaceScraper>,
+        job: Arc<ScrapingJob>,
+        page: u32,
+        cards_expected: bool,
+    ) -> Result<(SearchPage, String)> {
+        let tab = lease.tab();
+
+        let url = scraper.search_url(&job, page);
+        // Resultados ou a página de "nenhum resultado", o que vier primeiro
+        let selector = match (scraper.ready_selector(), scraper.no_results_selector()) {
+            (Some(ready), Some(no_results)) => Some(format!("{}, {}", ready, no_results)),
+            (ready, _) => ready.map(str::to_string),
+        };
+
+        let found = blocking({
+            let (tab, selector) = (tab.clone(), selector.clone());
+            move || {
+                tab.navigate_to(&url).map_err(|e| ArcsatError::Scraping(e.to_string()))?;
+                Ok(selector.is_none_or(|selector| tab.wait_for_element(&selector).is_ok()))
+            }
+        })
+        .await?;
+        lease.record_navigation();
+
+        // Delay aleatório humanizado
+        tokio::time::sleep(self.page_delay()).await;
+
+        // `Html` não é `Send`: o parsing fica na mesma thread que lê a página
+        blocking(move || {
+            let html = tab.get_content().map_err(|e| ArcsatError::Scraping(e.to_string()))?;
+            let parsed = scraper.parse_search(&job, &html, page);
+
+            // O seletor não apareceu no prazo (nem depois da espera)
+            if !found && !parsed.ready {
+                let loaded = tab
+                    .evaluate("document.readyState", false)
+                    .is_ok_and(|state| state.value.as_ref().and_then(|v| v.as_str()) == Some("complete"));
+                let selector = selector.unwrap_or_default();
+                return Err(fetch::unready_page_error(&html, loaded, &selector, cards_expected));
+            }
+
+            Ok((parsed, html))
+        })
+        .await
+    }
+
+    /// Laço de páginas de um `ApiScraper`: uma sessão HTTP por job, com os
+    /// cookies da `warmup_url`
+    async fn scrape_api(
+        &self,
+        scraper: &dyn ApiScraper,
+        job: &ScrapingJob,
+        cancel: &CancellationToken,
+    ) -> Result<SearchResult> {
+        let client = http::http_client(self.proxy_config.as_ref())?;
+
+        if let Some(url) = scraper.warmup_url() {
+            client.get(url).send().await?.error_for_status()?;
+        }
+
+        let mut products = Vec::new();
+        let mut stats = SearchStats::default();
+
+        for page in 1..=job.max_pages {
+            if cancel.is_cancelled() {
+                info!("Job {} cancelled after {} pages", job.id, page - 1);
+                break;
+            }
+
+            if page > 1 {
+                tokio::time::sleep(self.page_delay()).await;
+            }
+
+            info!("Fetching {:?} search API page {} of {}", job.marketplace, page, job.max_pages);
+
+            let request = scraper.search_request(&client, job, page).build()?;
+            let url = request.url().to_string();
+            let text = client.execute(request).await?.error_for_status()?.text().await?;
+            let body: serde_json::Value = serde_json::from_str(&text)?;
+
+            let found = scraper.parse_response(job, page, &body)?;
+            stats.record(job, page, &PageStats::from_products(&found), url, text);
+            products.extend(found);
+
+            if !scraper.has_next_page(&body, page) {
+                break;
+            }
+        }
+
+        info!("Scraped {} products from {:?}", products.len(), job.marketplace);
+        Ok(SearchResult { products, stats })
+    }
+
+    /// Espera humanizada entre páginas: `page_delay` mais jitter
+    fn page_delay(&self) -> Duration {
+        let jitter_ms = self.page_delay.as_millis() as u64 * 3 / 2;
+        self.page_delay + Duration::from_millis(rand::random::<u64>() % (jitter_ms + 1))
+    }
+}
+
+/// Executa uma chamada bloqueante (navegador) no pool de `spawn_blocking`
+async fn blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ArcsatError::Internal(format!("Browser task failed: {}", e)))?
+}
+
+fn unsupported(marketplace: Marketplace) -> ArcsatError {
+    ArcsatError::Unsupported(format!("{:?} scraper is not implemented", marketplace))
+}
+
+/// Produto da listagem com os campos comuns preenchidos; o scraper completa
+/// o resto (`external_id`, vendedor, avaliação) com `..listing_product(..)`
+pub fn listing_product(job: &ScrapingJob, title: String, price: Money, url: String) -> ScrapedProduct {
+    ScrapedProduct {
+        id: uuid::Uuid::new_v4(),
+        job_id: job.id,
+        marketplace: job.marketplace,
+        external_id: String::new(),
+        title,
+        price,
+        url,
+        image_url: None,
+        pricing: Pricing::default(),
+        seller_name: String::new(),
+        seller_id: None,
+        seller_rating: None,
+        sales_rank: None,
+        rating: None,
+        num_reviews: 0,
+        availability: true,
+        category: job.category.clone(),
+        brand: None,
+        scraped_at: chrono::Utc::now(),
+        extra: serde_json::json!({}),
+    }
+}
@@ -0,0 +1,245 @@
+//! Preços como aparecem nas páginas dos marketplaces
+//!
+//! O mesmo texto de card mistura o preço "de/por", o desconto no PIX, o
+//! parcelamento ("12x de R$ 29,90") e às vezes uma faixa ("R$ 10 a R$ 20").
+//! `parse_brl_price` classifica cada valor pelas palavras em volta dele;
+//! `parse_brl_amount` lê um valor só. Os valores vão direto do texto para
+//! `Decimal`, sem passar por `f64`. Texto sem preço é `None`, nunca 0.
+//!
+//! O número é sempre lido no formato brasileiro, mas a moeda vem do símbolo
+//! antes dele: produtos importados aparecem como "US$ 25,99" ou "€ 19,90".
+//! Sem símbolo, o valor é em reais.
+
+use crate::models::{InstallmentPlan, Pricing};
+use arcsat_core::{Currency, Money};
+use rust_decimal::Decimal;
+
+/// Símbolos de moeda reconhecidos; no mesmo ponto do texto vale o mais longo
+/// ("US$" antes de "$")
+const SYMBOLS: &[(&str, Currency)] = &[
+    ("R$", Currency::BRL),
+    ("US$", Currency::USD),
+    ("U$", Currency::USD),
+    ("€", Currency::EUR),
+    ("$", Currency::USD),
+];
+
+/// Preço de um anúncio: o de venda e as demais condições
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrlPrice {
+    pub price: Money,
+    pub pricing: Pricing,
+}
+
+/// Papel de um valor no texto do preço
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    /// "De R$ 2.199,00"
+    List,
+    /// "por R$ 1.799,00"
+    Sale,
+    /// "R$ 1.619,10 no Pix"
+    Pix,
+    /// "12x de R$ 149,92"
+    Installment(u32),
+    /// "a R$ 20,00" depois de outro valor
+    RangeEnd,
+    /// Valor sem palavra-chave; o primeiro é o preço de venda
+    Plain,
+}
+
+/// Lê o preço estruturado do texto do card. Sem nenhum símbolo de moeda, o
+/// texto todo é o preço de venda em reais (ex.: a fração do Mercado Livre).
+pub fn parse_brl_price(text: &str) -> Option<BrlPrice> {
+    let amounts = amounts(text);
+    if amounts.is_empty() {
+        return parse_brl_amount(text)
+            .filter(Money::is_positive)
+            .map(|price| BrlPrice { price, pricing: Pricing::default() });
+    }
+
+    let mut sale = None;
+    let mut plain = None;
+    let mut pricing = Pricing::default();
+
+    for (index, amount) in amounts.iter().enumerate() {
+        match role(&amount.before, &amount.after, index == 0) {
+            Role::List => pricing.list_price = pricing.list_price.or(Some(amount.value)),
+            Role::Sale => sale = sale.or(Some(amount.value)),
+            Role::Pix => pricing.pix_price = pricing.pix_price.or(Some(amount.value)),
+            Role::Installment(count) => {
+                pricing.installments = pricing.installments.or(Some(InstallmentPlan {
+                    count,
+                    amount: amount.value,
+                    interest_free: amount.after.contains("sem juros"),
+                }))
+            }
+            Role::RangeEnd => pricing.max_price = pricing.max_price.or(Some(amount.value)),
+            Role::Plain => plain = plain.or(Some(amount.value)),
+        }
+    }
+
+    // Sem preço "cheio" no texto: o PIX, ou o total das parcelas
+    let price = sale
+        .or(plain)
+        .or(pricing.pix_price)
+        .or_else(|| pricing.installments.map(|i| i.amount.scale(Decimal::from(i.count))))
+        .filter(Money::is_positive)?;
+
+    // Só vale o que é coerente com o preço de venda
+    pricing.list_price = pricing.list_price.filter(|list| *list > price);
+    pricing.pix_price = pricing.pix_price.filter(|pix| *pix < price);
+    pricing.max_price = pricing.max_price.filter(|max| *max > price);
+
+    Some(BrlPrice { price, pricing })
+}
+
+/// Primeiro valor do texto: "ou R$ 1.799,10 no Pix" → BRL 1799.10.
+/// Aceita milhar com ponto ("4.499"), centavos com vírgula ou ponto
+/// ("29,9", "1299.90") e separador no fim ("249,").
+pub fn parse_brl_amount(text: &str) -> Option<Money> {
+    let (text, currency) = match symbols(text).first() {
+        Some(&(start, _, currency)) => (&text[start..], currency),
+        None => (text, Currency::BRL),
+    };
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+
+    parse_number(number_at(&text[start..])).map(|amount| Money::new(amount, currency))
+}
+
+/// Valor com os centavos num elemento à parte, como nos cards da Amazon
+/// (`a-price-whole`/`a-price-fraction`) e do Mercado Livre
+/// (`andes-money-amount__fraction`/`__cents`). A moeda vem do símbolo, que
+/// também fica num elemento à parte.
+pub fn parse_brl_parts(whole: &str, cents: Option<&str>, currency: Currency) -> Option<Money> {
+    let whole = parse_brl_amount(whole)?.amount().trunc();
+
+    let cents = match cents.map(str::trim).filter(|cents| !cents.is_empty()) {
+        None => Decimal::ZERO,
+        Some(cents) if cents.len() <= 2 && cents.chars().all(|c| c.is_ascii_digit()) => {
+            Decimal::new(format!("{:0<2}", cents).parse().ok()?, 2)
+        }
+        Some(_) => return None,
+    };
+
+    Some(Money::new(whole + cents, currency))
+}
+
+/// Moeda do primeiro símbolo do texto ("US$" → USD); `None` sem símbolo
+pub fn detect_currency(text: &str) -> Option<Currency> {
+    symbols(text).first().map(|&(_, _, currency)| currency)
+}
+
+/// (início, fim, moeda) de cada símbolo de moeda do texto, em ordem
+fn symbols(text: &str) -> Vec<(usize, usize, Currency)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some((start, symbol, currency)) = SYMBOLS
+        .iter()
+        .filter_map(|&(symbol, currency)| text[offset..].find(symbol).map(|at| (offset + at, symbol, currency)))
+        .min_by_key(|&(start, symbol, _)| (start, std::cmp::Reverse(symbol.len())))
+    {
+        offset = start + symbol.len();
+        found.push((start, offset, currency));
+    }
+
+    found
+}
+
+/// Um valor precedido de um símbolo de moeda e o texto entre ele e os vizinhos
+struct Amount {
+    value: Money,
+    /// Desde o fim do valor anterior, em minúsculas
+    before: String,
+    /// Até o próximo símbolo, em minúsculas
+    after: String,
+}
+
+fn amounts(text: &str) -> Vec<Amount> {
+    // (início do símbolo, fim do número, valor)
+    let spans: Vec<(usize, usize, Money)> = symbols(text)
+        .into_iter()
+        .filter_map(|(symbol, symbol_end, currency)| {
+            let rest = &text[symbol_end..];
+            let start = symbol_end + (rest.len() - rest.trim_start().len());
+            let number = number_at(&text[start..]);
+
+            parse_number(number).map(|value| (symbol, start + number.len(), Money::new(value, currency)))
+        })
+        .collect();
+
+    spans
+        .iter()
+        .enumerate()
+        .map(|(index, &(symbol, end, value))| {
+            let previous_end = index.checked_sub(1).map_or(0, |previous| spans[previous].1);
+            let next_symbol = spans.get(index + 1).map_or(text.len(), |next| next.0);
+
+            Amount {
+                value,
+                before: text[previous_end..symbol].trim().to_lowercase(),
+                after: text[end..next_symbol].trim().to_lowercase(),
+            }
+        })
+        .collect()
+}
+
+fn role(before: &str, after: &str, first: bool) -> Role {
+    if let Some(count) = installment_count(before) {
+        return Role::Installment(count);
+    }
+
+    let after = after.trim_start_matches(['(', '-', ' ']);
+    if ["no pix", "via pix", "com pix", "à vista", "a vista", "no boleto"]
+        .iter()
+        .any(|keyword| after.starts_with(keyword))
+    {
+        return Role::Pix;
+    }
+
+    match before.split_whitespace().last().map(|word| word.trim_end_matches(':')) {
+        Some("de" | "antes") => Role::List,
+        Some("por") => Role::Sale,
+        Some("a" | "até" | "-" | "–") if !first => Role::RangeEnd,
+        _ => Role::Plain,
+    }
+}
+
+/// Parcelas no texto antes do valor: "em 12x de", "10 x", "em até 6x"
+fn installment_count(before: &str) -> Option<u32> {
+    let mut words = before.split_whitespace().rev().skip_while(|word| *word == "de");
+
+    let count = match words.next()?.strip_suffix('x')? {
+        "" => words.next()?,
+        count => count,
+    };
+
+    count.parse().ok().filter(|count| *count > 0)
+}
+
+/// Número no início do texto (dígitos e separadores), sem separador no fim
+fn number_at(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(text.len());
+
+    text[..end].trim_end_matches(['.', ','])
+}
+
+/// Número no formato brasileiro. Com vírgula, ela é o decimal e os pontos
+/// são milhar; só com pontos, grupos de 3 dígitos são milhar ("1.299") e o
+/// resto é decimal ("29.90").
+fn parse_number(number: &str) -> Option<Decimal> {
+    if !number.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let normalized = match number.rfind(',') {
+        Some(comma) => format!("{}.{}", number[..comma].replace(['.', ','], ""), &number[comma + 1..]),
+        None if number.split('.').skip(1).all(|group| group.len() == 3) => number.replace('.', ""),
+        None => number.to_string(),
+    };
+
+    normalized.parse().ok()
+}
@@ -0,0 +1,72 @@
+//! Seletores CSS como dados
+//!
+//! Cada scraper declara uma tabela `campo → seletor`, compilada uma vez na
+//! criação do scraper. Mudou o markup do marketplace, muda a tabela.
+
+use arcsat_core::{ArcsatError, Result};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+/// Tabela de seletores de um scraper: `(campo, seletor CSS)`
+pub type SelectorSpec = [(&'static str, &'static str)];
+
+/// Seletores compilados, consultados pelo nome do campo
+#[derive(Debug, Clone)]
+pub struct Selectors {
+    selectors: HashMap<&'static str, Selector>,
+}
+
+impl Selectors {
+    pub fn compile(spec: &SelectorSpec) -> Result<Self> {
+        let selectors = spec
+            .iter()
+            .map(|(field, css)| {
+                Selector::parse(css)
+                    .map(|selector| (*field, selector))
+                    .map_err(|e| ArcsatError::Config(format!("Invalid selector {:?} for {}: {}", css, field, e)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { selectors })
+    }
+
+    /// Seletor do campo; campo fora da tabela é erro de programação
+    pub fn get(&self, field: &str) -> &Selector {
+        self.selectors
+            .get(field)
+            .unwrap_or_else(|| panic!("selector {:?} is not declared", field))
+    }
+
+    /// Elementos do documento que casam com o campo (ex.: os cards da busca)
+    pub fn all<'a>(&'a self, document: &'a Html, field: &str) -> impl Iterator<Item = ElementRef<'a>> + 'a {
+        document.select(self.get(field))
+    }
+
+    pub fn exists(&self, document: &Html, field: &str) -> bool {
+        self.all(document, field).next().is_some()
+    }
+
+    /// O campo aparece dentro de `element` (com texto, como em `text`)?
+    pub fn exists_in(&self, element: ElementRef, field: &str) -> bool {
+        self.text(element, field).is_some()
+    }
+
+    /// Texto do primeiro elemento do campo dentro de `element`, sem espaços
+    /// nas pontas; `None` se não houver elemento ou o texto for vazio
+    pub fn text(&self, element: ElementRef, field: &str) -> Option<String> {
+        element
+            .select(self.get(field))
+            .next()
+            .map(|e| e.text().collect::<String>().trim().to_string())
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Atributo do primeiro elemento do campo dentro de `element`
+    pub fn attr(&self, element: ElementRef, field: &str, attr: &str) -> Option<String> {
+        element
+            .select(self.get(field))
+            .next()
+            .and_then(|e| e.value().attr(attr))
+            .map(str::to_string)
+    }
+}
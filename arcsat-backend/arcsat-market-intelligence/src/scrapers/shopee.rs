@@ -0,0 +1,176 @@
+//! Shopee
+//!
+//! Busca pela API `api/v4/search/search_items`, a mesma que a página usa.
+//! A API exige os cookies de sessão da home (`warmup_url`) e os headers
+//! do front-end; sem eles responde com `error` e nenhum item.
+
+use super::json_api::{search_rank, ApiScraper};
+use super::listing_product;
+use crate::models::*;
+use arcsat_core::{ArcsatError, Currency, Money, Result};
+use reqwest::{Client, RequestBuilder};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Itens por página da busca (`newest` é o offset)
+const PAGE_SIZE: u32 = 60;
+
+/// Preços da API vêm multiplicados por 100.000 (5 casas decimais)
+const PRICE_SCALE: u32 = 5;
+
+const IMAGE_URL: &str = "https://down-br.img.susercontent.com/file";
+
+pub struct ShopeeScraper {
+    base_url: String,
+}
+
+impl ShopeeScraper {
+    pub fn new() -> Self {
+        Self::with_base_url(Marketplace::Shopee.base_url())
+    }
+
+    /// Scraper apontando para outro host (ex.: servidor de testes)
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self { base_url: base_url.into() }
+    }
+}
+
+impl Default for ShopeeScraper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApiScraper for ShopeeScraper {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::Shopee
+    }
+
+    fn warmup_url(&self) -> Option<String> {
+        Some(format!("{}/", self.base_url))
+    }
+
+    fn search_request(&self, client: &Client, job: &ScrapingJob, page: u32) -> RequestBuilder {
+        let offset = (page - 1) * PAGE_SIZE;
+
+        client
+            .get(format!("{}/api/v4/search/search_items", self.base_url))
+            .query(&[
+                ("by", "relevancy"),
+                ("keyword", job.search_query.as_str()),
+                ("limit", &PAGE_SIZE.to_string()),
+                ("newest", &offset.to_string()),
+                ("order", "desc"),
+                ("page_type", "search"),
+                ("scenario", "PAGE_GLOBAL_SEARCH"),
+                ("version", "2"),
+            ])
+            .header("x-api-source", "pc")
+            .header("x-shopee-language", "pt-BR")
+            .header("x-requested-with", "XMLHttpRequest")
+            .header(
+                reqwest::header::REFERER,
+                format!("{}/search?keyword={}", self.base_url, urlencoding::encode(&job.search_query)),
+            )
+    }
+
+    fn parse_response(&self, job: &ScrapingJob, page: u32, body: &Value) -> Result<Vec<ScrapedProduct>> {
+        // Bloqueio anti-bot vem como 200 com `error` preenchido e `items: null`
+        if let Some(error) = body["error"].as_i64().filter(|code| *code != 0) {
+            return Err(ArcsatError::Scraping(format!("Shopee search API returned error {}", error)));
+        }
+
+        let response: SearchResponse = serde_json::from_value(body.clone())?;
+
+        let products = response
+            .items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let item = item.item_basic;
+                let url = format!("{}/product/{}/{}", Marketplace::Shopee.base_url(), item.shopid, item.itemid);
+                let currency = item.currency.as_deref().and_then(Currency::new).unwrap_or(Currency::BRL);
+                let price = Money::new(Decimal::new(item.price, PRICE_SCALE), currency);
+
+                ScrapedProduct {
+                    external_id: format!("{}.{}", item.shopid, item.itemid),
+                    image_url: item.image.map(|image| format!("{}/{}", IMAGE_URL, image)),
+                    seller_name: item.shop_name.unwrap_or_else(|| "Shopee".to_string()),
+                    seller_id: Some(item.shopid.to_string()),
+                    sales_rank: Some(search_rank(page, PAGE_SIZE, index)),
+                    rating: item.item_rating.as_ref().map(|rating| rating.rating_star).filter(|star| *star > 0.0),
+                    num_reviews: item
+                        .item_rating
+                        .as_ref()
+                        .and_then(|rating| rating.rating_count.first().copied())
+                        .unwrap_or(0),
+                    availability: item.stock.is_none_or(|stock| stock > 0),
+                    brand: item.brand.filter(|brand| !brand.is_empty()),
+                    pricing: Pricing {
+                        list_price: item.price_before_discount
+                            .filter(|list| *list > item.price)
+                            .map(|list| Money::new(Decimal::new(list, PRICE_SCALE), currency)),
+                        ..Pricing::default()
+                    },
+                    extra: serde_json::json!({
+                        "sold": item.sold,
+                        "historical_sold": item.historical_sold,
+                        "shop_location": item.shop_location,
+                    }),
+                    ..listing_product(job, item.name, price, url)
+                }
+            })
+            .collect();
+
+        Ok(products)
+    }
+
+    fn has_next_page(&self, body: &Value, page: u32) -> bool {
+        if body["nomore"].as_bool() == Some(true) {
+            return false;
+        }
+
+        match body["total_count"].as_u64() {
+            Some(total) => ((page * PAGE_SIZE) as u64) < total,
+            None => body["items"].as_array().is_some_and(|items| items.len() as u32 >= PAGE_SIZE),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    items: Vec<SearchItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchItem {
+    item_basic: ItemBasic,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemBasic {
+    itemid: u64,
+    shopid: u64,
+    name: String,
+    price: i64,
+    price_before_discount: Option<i64>,
+    currency: Option<String>,
+    image: Option<String>,
+    shop_name: Option<String>,
+    shop_location: Option<String>,
+    brand: Option<String>,
+    stock: Option<i64>,
+    sold: Option<i64>,
+    historical_sold: Option<i64>,
+    item_rating: Option<ItemRating>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemRating {
+    rating_star: f64,
+    /// Total seguido da contagem por estrela (1 a 5)
+    #[serde(default)]
+    rating_count: Vec<i32>,
+}
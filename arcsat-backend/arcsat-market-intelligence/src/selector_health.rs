@@ -0,0 +1,206 @@
+//! Monitor de seletores: detecta mudança de layout pela queda da extração
+//!
+//! Cada job concluído informa quantos cards a busca encontrou e quantos
+//! viraram produto (`scrapers::SearchStats`). Os jobs saudáveis alimentam uma
+//! média móvel por marketplace (`selector_health`, global) e deixam a última
+//! página com produtos como amostra do tenant (`selector_samples`). Depois
+//! de `min_samples` jobs, um job com rendimento abaixo de `drift_ratio` da
+//! média gera um alerta `SelectorDrift` para o tenant, em vez de um job
+//! concluído vazio passar despercebido.
+
+use crate::models::*;
+use crate::repository::JobRepository;
+use crate::scrapers::SearchStats;
+use arcsat_core::config::MarketIntelligenceConfig;
+use arcsat_core::Result;
+use chrono::Utc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Peso de cada job na média móvel da linha de base
+const BASELINE_WEIGHT: f64 = 0.2;
+
+/// Um alerta de drift aberto (não lido) por tenant e marketplace nesse período
+pub const ALERT_COOLDOWN: chrono::Duration = chrono::Duration::hours(24);
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelectorHealthConfig {
+    /// Fração da linha de base abaixo da qual o job é drift
+    pub drift_ratio: f64,
+    /// Jobs saudáveis antes de a linha de base valer
+    pub min_samples: u32,
+}
+
+impl Default for SelectorHealthConfig {
+    fn default() -> Self {
+        Self {
+            drift_ratio: 0.5,
+            min_samples: 5,
+        }
+    }
+}
+
+impl SelectorHealthConfig {
+    pub fn from_config(config: &MarketIntelligenceConfig) -> Self {
+        Self {
+            drift_ratio: config.selector_drift_ratio,
+            min_samples: config.selector_min_samples,
+        }
+    }
+
+    /// A linha de base já vale e o marketplace costuma ter cards: página de
+    /// busca carregada sem nenhum é mudança de layout
+    pub fn expects_cards(&self, baseline: Option<&SelectorBaseline>) -> bool {
+        baseline.is_some_and(|baseline| {
+            baseline.samples >= self.min_samples as i32 && baseline.parse_yield > 0.0
+        })
+    }
+
+    /// Compara o rendimento do job com a linha de base do marketplace. Busca
+    /// sem cards que o marketplace reconheceu como sem resultado não é drift.
+    pub fn evaluate(&self, baseline: Option<&SelectorBaseline>, stats: &SearchStats) -> Verdict {
+        let Some(baseline) = baseline.filter(|baseline| baseline.samples >= self.min_samples as i32) else {
+            return Verdict::Learning;
+        };
+
+        if stats.totals.cards == 0 && stats.totals.no_results {
+            return Verdict::Healthy;
+        }
+
+        let actual = stats.parse_yield().unwrap_or(0.0);
+        if actual < baseline.parse_yield * self.drift_ratio {
+            Verdict::Drift { expected: baseline.parse_yield, actual }
+        } else {
+            Verdict::Healthy
+        }
+    }
+}
+
+/// Veredito da extração de um job
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    /// Linha de base ainda em formação
+    Learning,
+    Healthy,
+    /// Rendimento (produtos por card; 0 sem cards) abaixo do esperado
+    Drift { expected: f64, actual: f64 },
+}
+
+/// Job saudável (ou com a linha de base em formação) entra na média e deixa
+/// a amostra de HTML do tenant; queda de rendimento gera um alerta
+/// `SelectorDrift` para o tenant (um por `ALERT_COOLDOWN`) e não mexe na
+/// linha de base.
+pub async fn check(
+    repository: &JobRepository,
+    config: &SelectorHealthConfig,
+    job: &ScrapingJob,
+    stats: &SearchStats,
+) -> Result<Verdict> {
+    let baseline = repository.get_selector_baseline(job.marketplace).await?;
+    let verdict = config.evaluate(baseline.as_ref(), stats);
+
+    match verdict {
+        Verdict::Drift { expected, actual } => {
+            warn!(
+                "Selector drift on {:?}: job {} parsed {:.0}% of the cards (baseline {:.0}%)",
+                job.marketplace,
+                job.id,
+                actual * 100.0,
+                expected * 100.0
+            );
+
+            let alert = drift_alert(job, stats, expected, actual);
+            repository.save_alert_unless_open(&alert, alert.created_at - ALERT_COOLDOWN).await?;
+        }
+        Verdict::Learning | Verdict::Healthy => {
+            if let Some(baseline) = updated_baseline(job.marketplace, baseline, stats) {
+                repository.save_selector_baseline(&baseline).await?;
+
+                if let Some(sample) = sample(job, stats) {
+                    repository.save_selector_sample(&sample).await?;
+                }
+            }
+        }
+    }
+
+    Ok(verdict)
+}
+
+/// Linha de base com o job somado à média. `None` se o job não tem o que
+/// ensinar (nenhum produto extraído).
+pub fn updated_baseline(
+    marketplace: Marketplace,
+    baseline: Option<SelectorBaseline>,
+    stats: &SearchStats,
+) -> Option<SelectorBaseline> {
+    let parse_yield = stats.parse_yield().filter(|_| stats.totals.products > 0)?;
+    let now = Utc::now();
+
+    Some(match baseline {
+        Some(baseline) if baseline.samples > 0 => SelectorBaseline {
+            parse_yield: baseline.parse_yield * (1.0 - BASELINE_WEIGHT) + parse_yield * BASELINE_WEIGHT,
+            samples: baseline.samples + 1,
+            updated_at: now,
+            ..baseline
+        },
+        _ => SelectorBaseline {
+            marketplace,
+            parse_yield,
+            samples: 1,
+            updated_at: now,
+        },
+    })
+}
+
+/// Última página com produtos do job como amostra do tenant
+pub fn sample(job: &ScrapingJob, stats: &SearchStats) -> Option<SelectorSample> {
+    let (url, html) = stats.sample.clone()?;
+
+    Some(SelectorSample {
+        tenant_id: job.tenant_id,
+        marketplace: job.marketplace,
+        job_id: job.id,
+        url,
+        html,
+        captured_at: Utc::now(),
+    })
+}
+
+/// Alerta de drift para o tenant do job
+pub fn drift_alert(job: &ScrapingJob, stats: &SearchStats, expected: f64, actual: f64) -> MarketAlert {
+    let totals = &stats.totals;
+
+    // Nenhum produto: os jobs do marketplace estão saindo vazios
+    let severity = if totals.products == 0 {
+        AlertSeverity::Critical
+    } else {
+        AlertSeverity::Warning
+    };
+
+    MarketAlert {
+        id: Uuid::new_v4(),
+        tenant_id: job.tenant_id,
+        marketplace: job.marketplace,
+        alert_type: AlertType::SelectorDrift,
+        product_id: None,
+        title: format!("Possível mudança de layout em {:?}", job.marketplace),
+        message: format!(
+            "A busca \"{}\" extraiu {} produtos de {} cards em {} páginas: rendimento de {:.0}%, \
+             contra {:.0}% na linha de base. Cards sem título: {}, sem preço: {}, sem link: {}, \
+             sem id: {}.",
+            job.search_query,
+            totals.products,
+            totals.cards,
+            stats.pages,
+            actual * 100.0,
+            expected * 100.0,
+            totals.missing_title,
+            totals.missing_price,
+            totals.missing_url,
+            totals.missing_id
+        ),
+        severity,
+        read_at: None,
+        created_at: Utc::now(),
+    }
+}
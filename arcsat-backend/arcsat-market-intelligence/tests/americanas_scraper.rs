@@ -0,0 +1,93 @@
+//! Extração da busca da Americanas sobre HTML salvo (`tests/fixtures/americanas`)
+
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{AmericanasScraper, MarketplaceScraper, ScraperRegistry};
+use arcsat_core::{ArcsatError, Money};
+use rust_decimal_macros::dec;
+use scraper::Html;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+const SEARCH: &str = include_str!("fixtures/americanas/search.html");
+const SEARCH_WITHOUT_STATE: &str = include_str!("fixtures/americanas/search_without_state.html");
+
+fn job() -> ScrapingJob {
+    ScrapingJob::new(Uuid::new_v4(), Marketplace::B2W, "smart tv".to_string(), 3)
+}
+
+fn parse(fixture: &str) -> Vec<ScrapedProduct> {
+    AmericanasScraper::new().unwrap().parse_listing(&job(), &Html::parse_document(fixture))
+}
+
+#[test]
+fn products_come_from_the_embedded_next_state() {
+    let products = parse(SEARCH);
+
+    // O produto sem ofertas (indisponível) fica de fora
+    assert_eq!(products.len(), 2);
+
+    let tv = &products[0];
+    assert_eq!(tv.marketplace, Marketplace::B2W);
+    assert_eq!(tv.external_id, "5448293812");
+    assert_eq!(tv.title, "Smart TV 50\" Samsung Crystal UHD 4K");
+    assert_eq!(tv.price, Money::brl(dec!(2299.9)));
+    assert_eq!(tv.url, "https://www.americanas.com.br/produto/5448293812");
+    assert_eq!(tv.seller_name, "Americanas");
+    assert_eq!(tv.seller_id.as_deref(), Some("00776574000156"));
+    assert_eq!(tv.brand.as_deref(), Some("Samsung"));
+    assert_eq!(tv.rating, Some(4.6));
+    assert_eq!(tv.num_reviews, 128);
+    assert!(tv.image_url.as_deref().unwrap().ends_with("5448293812_1_large.jpg"));
+    assert_eq!(tv.pricing.list_price, Some(Money::brl(dec!(2999.0))));
+    assert_eq!(tv.pricing.pix_price, Some(Money::brl(dec!(2184.9))));
+    assert_eq!(tv.pricing.installments.map(|i| (i.count, i.interest_free)), Some((10, true)));
+
+    let soundbar = &products[1];
+    assert_eq!(soundbar.seller_name, "TudoTech Eletrônicos");
+    assert_eq!(soundbar.url, "https://www.americanas.com.br/produto/7012345678");
+    assert!(soundbar.image_url.as_deref().unwrap().ends_with("_small.jpg"));
+    assert_eq!(soundbar.rating, None);
+    assert_eq!(soundbar.pricing.pix_price, None);
+    assert_eq!(soundbar.pricing.installments.map(|i| i.interest_free), Some(false));
+}
+
+#[test]
+fn css_cards_are_the_fallback_without_embedded_state() {
+    let products = parse(SEARCH_WITHOUT_STATE);
+
+    // O card sem preço fica de fora
+    assert_eq!(products.len(), 1);
+
+    let fone = &products[0];
+    assert_eq!(fone.external_id, "2211334455");
+    assert_eq!(fone.title, "Fone de Ouvido Bluetooth Xiaomi Redmi Buds 4");
+    assert_eq!(fone.price, Money::brl(dec!(149.99)));
+    assert_eq!(fone.url, "https://www.americanas.com.br/produto/2211334455?chave=busca");
+    assert!(fone.image_url.is_some());
+}
+
+#[test]
+fn pagination_follows_the_search_total() {
+    let scraper = AmericanasScraper::new().unwrap();
+    let document = Html::parse_document(SEARCH);
+
+    // total = 50, 24 por página
+    assert!(scraper.has_next_page(&document, 1));
+    assert!(scraper.has_next_page(&document, 2));
+    assert!(!scraper.has_next_page(&document, 3));
+
+    assert_eq!(
+        scraper.search_url(&job(), 2),
+        "https://www.americanas.com.br/busca/smart-tv?limit=24&offset=24"
+    );
+}
+
+#[tokio::test]
+async fn marketplaces_without_a_scraper_are_unsupported() {
+    let registry = ScraperRegistry::empty(None);
+    let job = ScrapingJob::new(Uuid::new_v4(), Marketplace::Shopee, "fone".to_string(), 1);
+
+    let result = registry.scrape(&job, &CancellationToken::new()).await;
+
+    assert!(matches!(result, Err(ArcsatError::Unsupported(_))));
+}
@@ -0,0 +1,195 @@
+//! Scrapers de API JSON (Shopee, AliExpress) contra um servidor local que
+//! serve respostas gravadas (`tests/fixtures/shopee`, `tests/fixtures/aliexpress`)
+
+use arcsat_core::{ArcsatError, Currency, Money};
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{AliExpressScraper, ScraperRegistry, ShopeeScraper};
+use rust_decimal_macros::dec;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SESSION_COOKIE: &str = "SPC_F=kH2p9xQ7mZ";
+
+fn json(fixture: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(fixture, "application/json")
+}
+
+/// Home que entrega o cookie de sessão
+async fn mount_warmup(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).insert_header("set-cookie", format!("{}; Path=/", SESSION_COOKIE)))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+fn registry() -> ScraperRegistry {
+    ScraperRegistry::empty(None).with_page_delay(Duration::ZERO)
+}
+
+async fn scrape(registry: &ScraperRegistry, marketplace: Marketplace, max_pages: u32) -> arcsat_core::Result<Vec<ScrapedProduct>> {
+    let job = ScrapingJob::new(Uuid::new_v4(), marketplace, "fone bluetooth".to_string(), max_pages);
+    registry.scrape(&job, &CancellationToken::new()).await
+}
+
+#[tokio::test]
+async fn shopee_search_is_paginated_with_session_cookies() {
+    let server = MockServer::start().await;
+    mount_warmup(&server).await;
+
+    for (offset, fixture) in [
+        ("0", include_str!("fixtures/shopee/search_page1.json")),
+        ("60", include_str!("fixtures/shopee/search_page2.json")),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/api/v4/search/search_items"))
+            .and(query_param("keyword", "fone bluetooth"))
+            .and(query_param("newest", offset))
+            .and(header("x-api-source", "pc"))
+            .and(header("cookie", SESSION_COOKIE))
+            .respond_with(json(fixture))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let mut registry = registry();
+    registry.register_api(ShopeeScraper::with_base_url(server.uri()));
+
+    // `nomore` na segunda página encerra antes de max_pages
+    let products = scrape(&registry, Marketplace::Shopee, 5).await.unwrap();
+    assert_eq!(products.len(), 3);
+
+    let tws = &products[0];
+    assert_eq!(tws.external_id, "812734561.22873465210");
+    assert_eq!(tws.title, "Fone de Ouvido Bluetooth TWS Pro 5.3 com Case");
+    assert_eq!(tws.price, Money::brl(dec!(49.90)));
+    assert_eq!(tws.url, "https://shopee.com.br/product/812734561/22873465210");
+    assert_eq!(tws.seller_id.as_deref(), Some("812734561"));
+    assert_eq!(tws.seller_name, "Shopee");
+    assert_eq!(tws.sales_rank, Some(1));
+    assert_eq!(tws.rating, Some(4.78));
+    assert_eq!(tws.num_reviews, 9321);
+    assert!(tws.availability);
+    assert_eq!(tws.brand, None);
+    assert_eq!(
+        tws.image_url.as_deref(),
+        Some("https://down-br.img.susercontent.com/file/br-11134207-7r98o-lq9m1x2y3z4a5b")
+    );
+    assert_eq!(tws.extra["sold"], 1203);
+    assert_eq!(tws.extra["historical_sold"], 18452);
+    assert_eq!(tws.pricing.list_price, Some(Money::brl(dec!(89.90))));
+
+    let jbl = &products[1];
+    assert_eq!(jbl.seller_name, "JBL Store Oficial");
+    assert_eq!(jbl.brand.as_deref(), Some("JBL"));
+    assert_eq!(jbl.rating, None);
+    assert!(!jbl.availability);
+    assert_eq!(jbl.pricing.list_price, None);
+
+    // Rank continua da página anterior (60 por página)
+    assert_eq!(products[2].sales_rank, Some(61));
+}
+
+#[tokio::test]
+async fn shopee_api_errors_fail_the_job() {
+    let server = MockServer::start().await;
+    mount_warmup(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v4/search/search_items"))
+        .respond_with(json(include_str!("fixtures/shopee/blocked.json")))
+        .mount(&server)
+        .await;
+
+    let mut registry = registry();
+    registry.register_api(ShopeeScraper::with_base_url(server.uri()));
+
+    let error = scrape(&registry, Marketplace::Shopee, 1).await.unwrap_err();
+    assert!(matches!(error, ArcsatError::Scraping(_)));
+    assert!(error.is_retryable());
+}
+
+#[tokio::test]
+async fn aliexpress_search_keeps_currency_and_sold_counts() {
+    let server = MockServer::start().await;
+    mount_warmup(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/fn/search-pc/index"))
+        .and(header("cookie", SESSION_COOKIE))
+        .and(body_partial_json(serde_json::json!({ "data": { "SearchText": "fone bluetooth", "page": 1 } })))
+        .respond_with(json(include_str!("fixtures/aliexpress/search.json")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut registry = registry();
+    registry.register_api(AliExpressScraper::with_base_url(server.uri()));
+
+    // totalResults = 2 cabe na primeira página
+    let products = scrape(&registry, Marketplace::AliExpress, 3).await.unwrap();
+    assert_eq!(products.len(), 2);
+
+    let lenovo = &products[0];
+    assert_eq!(lenovo.external_id, "1005006158364712");
+    assert_eq!(lenovo.price, Money::new(dec!(4.89), Currency::USD));
+    assert_eq!(lenovo.url, "https://pt.aliexpress.com/item/1005006158364712.html");
+    assert_eq!(lenovo.seller_name, "Lenovo Official Store");
+    assert_eq!(lenovo.seller_id.as_deref(), Some("1101234567"));
+    assert_eq!(lenovo.sales_rank, Some(1));
+    assert_eq!(lenovo.rating, Some(4.7));
+    assert_eq!(
+        lenovo.image_url.as_deref(),
+        Some("https://ae01.alicdn.com/kf/S8e3c1a6f2b4d4c0e9f1a2b3c4d5e6f7g.jpg")
+    );
+    assert_eq!(lenovo.extra["sold"], 10000);
+    assert_eq!(lenovo.pricing.list_price, Some(Money::new(dec!(12.31), Currency::USD)));
+
+    let cabo = &products[1];
+    assert_eq!(cabo.price.currency(), Currency::BRL);
+    assert_eq!(cabo.seller_name, "AliExpress");
+    assert_eq!(cabo.sales_rank, Some(2));
+    assert!(cabo.extra["sold"].is_null());
+}
+
+#[tokio::test]
+async fn aliexpress_block_page_fails_the_job() {
+    let server = MockServer::start().await;
+    mount_warmup(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/fn/search-pc/index"))
+        .respond_with(json(include_str!("fixtures/aliexpress/blocked.json")))
+        .mount(&server)
+        .await;
+
+    let mut registry = registry();
+    registry.register_api(AliExpressScraper::with_base_url(server.uri()));
+
+    let error = scrape(&registry, Marketplace::AliExpress, 1).await.unwrap_err();
+    assert!(matches!(error, ArcsatError::Scraping(_)));
+}
+
+#[tokio::test]
+async fn upstream_server_errors_are_retryable() {
+    let server = MockServer::start().await;
+    mount_warmup(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v4/search/search_items"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let mut registry = registry();
+    registry.register_api(ShopeeScraper::with_base_url(server.uri()));
+
+    let error = scrape(&registry, Marketplace::Shopee, 1).await.unwrap_err();
+    assert!(matches!(error, ArcsatError::Http(_)));
+    assert!(error.is_retryable());
+}
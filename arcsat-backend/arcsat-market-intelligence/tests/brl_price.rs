@@ -0,0 +1,128 @@
+//! Leitura de preços em reais (`scrapers::parse_brl_price`) nos formatos dos cards
+
+use arcsat_core::{Currency, Money};
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{detect_currency, parse_brl_amount, parse_brl_parts, parse_brl_price, BrlPrice};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn price(text: &str) -> BrlPrice {
+    parse_brl_price(text).unwrap_or_else(|| panic!("no price in {:?}", text))
+}
+
+fn brl(amount: Decimal) -> Money {
+    Money::brl(amount)
+}
+
+#[test]
+fn amounts_keep_cents_and_thousands() {
+    assert_eq!(parse_brl_amount("R$ 1.299,90"), Some(brl(dec!(1299.9))));
+    assert_eq!(parse_brl_amount("R$\u{a0}249,00"), Some(brl(dec!(249.0))));
+    assert_eq!(parse_brl_amount("4.499"), Some(brl(dec!(4499.0))));
+    assert_eq!(parse_brl_amount("1.234.567,89"), Some(brl(dec!(1234567.89))));
+    assert_eq!(parse_brl_amount("29,9"), Some(brl(dec!(29.9))));
+    assert_eq!(parse_brl_amount("1299.90"), Some(brl(dec!(1299.9))));
+    assert_eq!(parse_brl_amount("ou R$ 1.799,10 no Pix"), Some(brl(dec!(1799.1))));
+
+    // Texto sem número não vira 0
+    assert_eq!(parse_brl_amount("Preço indisponível"), None);
+    assert_eq!(parse_brl_price("Preço indisponível"), None);
+    assert_eq!(parse_brl_price("R$ 0,00"), None);
+}
+
+#[test]
+fn cents_in_a_separate_span_are_joined() {
+    // Amazon: "1.299<span class="a-price-decimal">,</span>" + "90"
+    assert_eq!(parse_brl_parts("1.299,", Some("90"), Currency::BRL), Some(brl(dec!(1299.9))));
+    // Mercado Livre: fração sem centavos
+    assert_eq!(parse_brl_parts("4.499", None, Currency::BRL), Some(brl(dec!(4499.0))));
+    assert_eq!(parse_brl_parts("129", Some("9"), Currency::BRL), Some(brl(dec!(129.9))));
+    assert_eq!(parse_brl_parts("129", Some("abc"), Currency::BRL), None);
+}
+
+#[test]
+fn list_and_sale_prices() {
+    assert_eq!(
+        price("De R$ 2.199,00 por R$ 1.799,00"),
+        BrlPrice {
+            price: brl(dec!(1799.0)),
+            pricing: Pricing {
+                list_price: Some(brl(dec!(2199.0))),
+                ..Pricing::default()
+            },
+        }
+    );
+
+    // Preço "de" que não é maior que o de venda é descartado
+    assert_eq!(price("De: R$ 99,90 Por: R$ 99,90").pricing.list_price, None);
+
+    // O mesmo valor repetido (texto visível + leitor de tela) é um preço só
+    assert_eq!(price("R$ 249,00R$249,00"), BrlPrice { price: brl(dec!(249.0)), pricing: Pricing::default() });
+}
+
+#[test]
+fn pix_discount_and_installments() {
+    assert_eq!(
+        price("De R$ 2.199,00 por R$ 1.799,00 ou R$ 1.619,10 no Pix em até 12x de R$ 149,92 sem juros"),
+        BrlPrice {
+            price: brl(dec!(1799.0)),
+            pricing: Pricing {
+                list_price: Some(brl(dec!(2199.0))),
+                pix_price: Some(brl(dec!(1619.1))),
+                max_price: None,
+                installments: Some(InstallmentPlan {
+                    count: 12,
+                    amount: brl(dec!(149.92)),
+                    interest_free: true,
+                }),
+            },
+        }
+    );
+
+    // Magalu: PIX em destaque e o preço a prazo junto do parcelamento
+    let magalu = price("ou R$ 237,49 no Pix R$ 249,99 em 5x de R$ 52,38 com juros");
+    assert_eq!(magalu.price, brl(dec!(249.99)));
+    assert_eq!(magalu.pricing.pix_price, Some(brl(dec!(237.49))));
+    assert_eq!(
+        magalu.pricing.installments,
+        Some(InstallmentPlan {
+            count: 5,
+            amount: brl(dec!(52.38)),
+            interest_free: false,
+        })
+    );
+
+    // Só o PIX, ou só as parcelas: viram o preço de venda
+    assert_eq!(price("R$ 89,90 à vista").price, brl(dec!(89.9)));
+    assert_eq!(price("R$ 89,90 à vista").pricing.pix_price, None);
+    assert_eq!(price("12x de R$ 29,90").price, brl(dec!(358.8)));
+    assert_eq!(price("10 x R$ 9,99 sem juros").pricing.installments.map(|i| i.count), Some(10));
+}
+
+#[test]
+fn ranges_keep_the_lowest_price_as_sale_price() {
+    let range = price("R$ 10,00 a R$ 20,00");
+    assert_eq!(range.price, brl(dec!(10.0)));
+    assert_eq!(range.pricing.max_price, Some(brl(dec!(20.0))));
+
+    assert_eq!(price("R$ 59,90 - R$ 89,90").pricing.max_price, Some(brl(dec!(89.9))));
+}
+
+#[test]
+fn currency_comes_from_the_symbol() {
+    assert_eq!(parse_brl_amount("US$ 25,99"), Some(Money::new(dec!(25.99), Currency::USD)));
+    assert_eq!(parse_brl_amount("€ 19,90"), Some(Money::new(dec!(19.90), Currency::EUR)));
+    assert_eq!(parse_brl_parts("12", Some("31"), Currency::USD), Some(Money::new(dec!(12.31), Currency::USD)));
+
+    assert_eq!(detect_currency("US$"), Some(Currency::USD));
+    assert_eq!(detect_currency("R$"), Some(Currency::BRL));
+    assert_eq!(detect_currency("12x sem juros"), None);
+
+    // Produto importado: a estrutura de/por vale igual, na moeda do anúncio
+    let imported = price("De US$ 39,90 por US$ 29,90");
+    assert_eq!(imported.price, Money::new(dec!(29.90), Currency::USD));
+    assert_eq!(imported.pricing.list_price, Some(Money::new(dec!(39.90), Currency::USD)));
+
+    // Valor em outra moeda não vira preço de lista do preço em reais
+    assert_eq!(price("De US$ 199,90 por R$ 149,90").pricing.list_price, None);
+}
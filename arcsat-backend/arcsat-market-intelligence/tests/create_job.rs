@@ -0,0 +1,67 @@
+//! Validação do `POST /jobs` antes de o job chegar ao banco ou à fila
+//!
+//! O engine conecta no Redis ao subir, por isso os testes ficam `#[ignore]`:
+//!
+//! ```bash
+//! REDIS_URL=redis://... cargo test -p arcsat-market-intelligence --test create_job -- --ignored
+//! ```
+
+use arcsat_core::auth::{Claims, JwtAuth};
+use arcsat_market_intelligence::MarketIntelligenceEngine;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const JWT_SECRET: &[u8] = b"create-job-test";
+
+fn token() -> String {
+    let claims = Claims {
+        sub: Uuid::new_v4(),
+        tenant_id: Uuid::new_v4(),
+        exp: chrono::Utc::now().timestamp() + 300,
+        iat: None,
+        roles: Vec::new(),
+    };
+
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(JWT_SECRET),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn max_pages_outside_the_configured_range_is_rejected() {
+    let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+    // Requisição recusada não toca o Postgres
+    let pool = PgPoolOptions::new().connect_lazy("postgres://nobody@127.0.0.1:1/none").unwrap();
+    let mut engine = MarketIntelligenceEngine::new(&redis_url, pool, None).await.unwrap();
+    engine.max_pages = 10;
+    let app = arcsat_market_intelligence::router(Arc::new(engine), JwtAuth::hs256(JWT_SECRET));
+
+    for max_pages in [0, 11, u32::MAX] {
+        let body = serde_json::json!({
+            "marketplace": "mercado_livre",
+            "search_query": "notebook",
+            "max_pages": max_pages,
+        });
+        let request = Request::post("/api/v1/market-intelligence/jobs")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token()))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "max_pages = {}", max_pages);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert!(body["error"].as_str().unwrap().contains("between 1 and 10"), "{}", body);
+    }
+}
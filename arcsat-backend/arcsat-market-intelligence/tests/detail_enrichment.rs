@@ -0,0 +1,178 @@
+//! Enriquecimento pela página de produto: extração sobre HTML salvo
+//! (`tests/fixtures/*/detail*.html`) e o passe completo contra um servidor local
+
+use arcsat_core::Money;
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{
+    listing_product, AmazonScraper, DetailLimits, MagaluScraper, MarketplaceScraper, MercadoLivreScraper,
+    ScraperRegistry,
+};
+use rust_decimal_macros::dec;
+use scraper::Html;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn job(marketplace: Marketplace) -> ScrapingJob {
+    let mut job = ScrapingJob::new(Uuid::new_v4(), marketplace, "fone bluetooth".to_string(), 1);
+    job.enrich_details = true;
+    job
+}
+
+fn product(marketplace: Marketplace, url: String) -> ScrapedProduct {
+    listing_product(&job(marketplace), "Fone de Ouvido JBL Tune 520BT".to_string(), Money::brl(dec!(249.99)), url)
+}
+
+fn detail(scraper: &dyn MarketplaceScraper, fixture: &str) -> ScrapedProduct {
+    let mut product = product(scraper.marketplace(), "https://example.com/p/1".to_string());
+    scraper.parse_detail(&mut product, &Html::parse_document(fixture));
+    product
+}
+
+/// Frete gravado em `extra`, no formato de `Money`
+fn shipping_cost(product: &ScrapedProduct) -> Money {
+    serde_json::from_value(product.extra["shipping_cost"].clone()).unwrap()
+}
+
+#[test]
+fn amazon_detail_uses_the_buy_box_seller() {
+    let scraper = AmazonScraper::new().unwrap();
+    let product = detail(&scraper, include_str!("fixtures/amazon/detail.html"));
+
+    assert_eq!(product.seller_name, "Som & Cia Eletrônicos");
+    assert_eq!(product.seller_id.as_deref(), Some("A3K8X2P9QLMZ7T"));
+    assert_eq!(product.extra["buy_box_seller"], "Som & Cia Eletrônicos");
+    assert_eq!(product.brand.as_deref(), Some("JBL"));
+    assert_eq!(product.rating, Some(4.6));
+    assert_eq!(product.num_reviews, 1234);
+    assert_eq!(
+        product.image_url.as_deref(),
+        Some("https://m.media-amazon.com/images/I/61kWB+uzR2L._AC_SL1500_.jpg")
+    );
+    assert_eq!(product.extra["offer_count"], 5);
+    assert_eq!(product.extra["gtin"], "6925281964882");
+    assert_eq!(shipping_cost(&product), Money::brl(dec!(0)));
+    assert_eq!(product.extra["stock_status"], "limited");
+    assert_eq!(product.extra["stock_quantity"], 3);
+    assert!(product.availability);
+}
+
+#[test]
+fn amazon_detail_sold_by_amazon_out_of_stock() {
+    let scraper = AmazonScraper::new().unwrap();
+    let product = detail(&scraper, include_str!("fixtures/amazon/detail_sold_by_amazon.html"));
+
+    assert_eq!(product.seller_name, "Amazon.com.br");
+    assert_eq!(product.seller_id, None);
+    assert_eq!(product.brand.as_deref(), Some("Amazon"));
+    assert_eq!(product.extra["gtin"], "0840268977361");
+    assert_eq!(shipping_cost(&product), Money::brl(dec!(19.9)));
+    assert_eq!(product.extra["stock_status"], "out_of_stock");
+    assert!(product.extra.get("offer_count").is_none());
+    assert!(!product.availability);
+}
+
+#[test]
+fn mercado_livre_detail_combines_json_ld_and_page() {
+    let scraper = MercadoLivreScraper::new().unwrap();
+    let product = detail(&scraper, include_str!("fixtures/mercado_livre/detail.html"));
+
+    // JSON-LD
+    assert_eq!(product.brand.as_deref(), Some("Samsung"));
+    assert_eq!(product.extra["gtin"], "7892509124821");
+    assert_eq!(product.rating, Some(4.8));
+    assert_eq!(
+        product.image_url.as_deref(),
+        Some("https://http2.mlstatic.com/D_NQ_NP_612345-MLA74563219876_022024-O.webp")
+    );
+
+    // Página: vendedor, frete, estoque e o total de opiniões mais recente
+    assert_eq!(product.seller_name, "MEGATECH STORE");
+    assert_eq!(product.extra["buy_box_seller"], "MEGATECH STORE");
+    assert_eq!(shipping_cost(&product), Money::brl(dec!(0)));
+    assert_eq!(product.extra["stock_status"], "in_stock");
+    assert_eq!(product.extra["stock_quantity"], 23);
+    assert_eq!(product.num_reviews, 1532);
+}
+
+#[test]
+fn json_ld_graph_with_aggregate_offer() {
+    let scraper = MagaluScraper::new().unwrap();
+    let product = detail(&scraper, include_str!("fixtures/magalu/detail.html"));
+
+    assert_eq!(product.brand.as_deref(), Some("JBL"));
+    assert_eq!(product.extra["gtin"], "6925281964882");
+    assert_eq!(product.rating, Some(4.7));
+    assert_eq!(product.num_reviews, 389);
+    assert_eq!(product.seller_name, "Loja Som Total");
+    assert_eq!(product.extra["offer_count"], 3);
+    assert_eq!(shipping_cost(&product), Money::brl(dec!(12.9)));
+    assert_eq!(product.extra["stock_status"], "limited");
+}
+
+#[tokio::test]
+async fn enrichment_is_rate_limited_and_skips_failed_pages() {
+    let server = MockServer::start().await;
+
+    for id in ["1", "2"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/p/{}/", id)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(include_str!("fixtures/magalu/detail.html"), "text/html"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/p/3/"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let interval = Duration::from_millis(200);
+    let mut registry = ScraperRegistry::empty(None);
+    registry.register(MagaluScraper::new().unwrap());
+    registry.set_detail_limits(DetailLimits { concurrency: 2, interval });
+
+    let job = job(Marketplace::Magalu);
+    let mut products: Vec<_> = ["1", "2", "3"]
+        .iter()
+        .map(|id| product(Marketplace::Magalu, format!("{}/p/{}/", server.uri(), id)))
+        .collect();
+
+    let started = Instant::now();
+    let enriched = registry
+        .enrich_details(&job, &mut products, &CancellationToken::new())
+        .await
+        .unwrap();
+
+    assert_eq!(enriched, 2);
+    // Três requisições espaçadas por `interval`, mesmo com duas em paralelo
+    assert!(started.elapsed() >= interval * 2);
+
+    assert_eq!(products[0].brand.as_deref(), Some("JBL"));
+    assert_eq!(products[1].extra["gtin"], "6925281964882");
+
+    // A página que falhou deixa o produto como veio da busca
+    assert_eq!(products[2].brand, None);
+    assert!(products[2].extra.get("gtin").is_none());
+}
+
+#[tokio::test]
+async fn cancelled_job_is_not_enriched() {
+    let mut registry = ScraperRegistry::empty(None);
+    registry.register(MagaluScraper::new().unwrap());
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+
+    let job = job(Marketplace::Magalu);
+    let mut products = vec![product(Marketplace::Magalu, "http://127.0.0.1:9/p/1/".to_string())];
+
+    assert_eq!(registry.enrich_details(&job, &mut products, &cancel).await.unwrap(), 0);
+    assert_eq!(products[0].brand, None);
+}
@@ -0,0 +1,252 @@
+//! Busca por HTTP com fallback para o navegador (`FetchStrategy`), contra um
+//! servidor local que serve as páginas gravadas em `tests/fixtures`
+
+use arcsat_core::ArcsatError;
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::proxy::ProxyPool;
+use arcsat_market_intelligence::scrapers::{
+    is_bot_challenge, unready_page_error, BrowserPool, BrowserPoolConfig, MagaluScraper, MarketplaceScraper,
+    ScraperRegistry,
+};
+use reqwest::StatusCode;
+use scraper::Html;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SEARCH: &str = include_str!("fixtures/magalu/search.html");
+const ROBOT_CHECK: &str = include_str!("fixtures/challenges/amazon_robot_check.html");
+const CLOUDFLARE: &str = include_str!("fixtures/challenges/cloudflare.html");
+
+/// Scraper da Magalu buscando no servidor de testes
+struct LocalMagalu {
+    base_url: String,
+    inner: MagaluScraper,
+}
+
+impl MarketplaceScraper for LocalMagalu {
+    fn marketplace(&self) -> Marketplace {
+        Marketplace::Magalu
+    }
+
+    fn search_url(&self, _job: &ScrapingJob, page: u32) -> String {
+        format!("{}/busca/?page={}", self.base_url, page)
+    }
+
+    fn ready_selector(&self) -> Option<&str> {
+        self.inner.ready_selector()
+    }
+
+    fn no_results_selector(&self) -> Option<&str> {
+        self.inner.no_results_selector()
+    }
+
+    fn parse_listing(&self, job: &ScrapingJob, document: &Html) -> Vec<ScrapedProduct> {
+        self.inner.parse_listing(job, document)
+    }
+
+    fn has_next_page(&self, document: &Html, page: u32) -> bool {
+        self.inner.has_next_page(document, page)
+    }
+}
+
+/// Registry com a Magalu local. O pool não tem navegadores: um job que
+/// precisar de um fica esperando até ser cancelado.
+fn registry(server: &MockServer) -> ScraperRegistry {
+    let mut registry = ScraperRegistry::empty(None).with_page_delay(Duration::ZERO);
+    registry.register(LocalMagalu {
+        base_url: server.uri(),
+        inner: MagaluScraper::new().unwrap(),
+    });
+    registry.set_browser_pool(BrowserPool::new(
+        BrowserPoolConfig { size: 0, max_navigations: 1 },
+        ProxyPool::new(Vec::new()),
+    ));
+    registry
+}
+
+fn job(strategy: FetchStrategy, max_pages: u32) -> ScrapingJob {
+    let mut job = ScrapingJob::new(Uuid::new_v4(), Marketplace::Magalu, "smartphone".to_string(), max_pages);
+    job.fetch_strategy = Some(strategy);
+    job
+}
+
+async fn serve(server: &MockServer, page: &str, response: ResponseTemplate, times: u64) {
+    Mock::given(method("GET"))
+        .and(path("/busca/"))
+        .and(query_param("page", page))
+        .respond_with(response)
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+fn html(status: u16, body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_raw(body, "text/html; charset=utf-8")
+}
+
+/// Cancela o job depois de `after` (o tempo de o job chegar ao navegador)
+fn cancel_after(after: Duration) -> CancellationToken {
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(after).await;
+        token.cancel();
+    });
+    cancel
+}
+
+#[tokio::test]
+async fn server_rendered_pages_are_fetched_without_a_browser() {
+    let server = MockServer::start().await;
+    let registry = registry(&server);
+
+    for strategy in [FetchStrategy::Http, FetchStrategy::Auto] {
+        server.reset().await;
+        serve(&server, "1", html(200, SEARCH), 1).await;
+        serve(&server, "2", html(200, SEARCH), 1).await;
+
+        let products = registry.scrape(&job(strategy, 2), &CancellationToken::new()).await.unwrap();
+
+        assert_eq!(products.len(), 6, "{:?}", strategy);
+        assert_eq!(products[0].external_id, "237412400");
+    }
+
+    assert_eq!(registry.browser_pool().metrics().leases, 0);
+}
+
+#[tokio::test]
+async fn http_strategy_fails_when_the_page_needs_a_browser() {
+    let server = MockServer::start().await;
+    let registry = registry(&server);
+
+    for (response, reason) in [
+        (html(200, ROBOT_CHECK), "bot challenge"),
+        (html(403, CLOUDFLARE), "bot challenge"),
+        // Resultados montados no cliente: o HTML vem sem os cards
+        (html(200, "<html><body><div id=\"root\"></div></body></html>"), "required selector missing"),
+    ] {
+        server.reset().await;
+        serve(&server, "1", response, 1).await;
+
+        match registry.scrape(&job(FetchStrategy::Http, 1), &CancellationToken::new()).await {
+            Err(ArcsatError::Scraping(message)) => assert!(message.contains(reason), "{}", message),
+            other => panic!("expected a scraping error, got {:?}", other.map(|products| products.len())),
+        }
+    }
+}
+
+#[tokio::test]
+async fn http_errors_that_are_not_challenges_fail_the_job() {
+    // 5xx passa numa nova tentativa; 404 não
+    for (status, retryable) in [(500, true), (404, false)] {
+        let server = MockServer::start().await;
+        serve(&server, "1", html(status, "error"), 1).await;
+
+        let error = registry(&server).scrape(&job(FetchStrategy::Auto, 1), &CancellationToken::new()).await.unwrap_err();
+
+        assert!(matches!(&error, ArcsatError::Http(e) if e.status().map(|s| s.as_u16()) == Some(status)));
+        assert_eq!(error.is_retryable(), retryable, "HTTP {}", status);
+    }
+}
+
+#[tokio::test]
+async fn auto_strategy_switches_to_the_browser_after_a_challenge() {
+    let server = MockServer::start().await;
+    serve(&server, "1", html(200, SEARCH), 1).await;
+    serve(&server, "2", html(200, ROBOT_CHECK), 1).await;
+    // Depois do desafio o resto do job vai pelo navegador
+    serve(&server, "3", html(200, SEARCH), 0).await;
+
+    let registry = registry(&server);
+    let products = registry
+        .scrape(&job(FetchStrategy::Auto, 3), &cancel_after(Duration::from_millis(300)))
+        .await
+        .unwrap();
+
+    // Só a página 1 (HTTP); o job ficou esperando um navegador até o cancelamento
+    assert_eq!(products.len(), 3);
+}
+
+#[tokio::test]
+async fn a_later_page_that_fails_keeps_the_earlier_products() {
+    let server = MockServer::start().await;
+    serve(&server, "1", html(200, SEARCH), 1).await;
+    serve(&server, "2", html(500, "error"), 1).await;
+
+    let products = registry(&server)
+        .scrape(&job(FetchStrategy::Http, 3), &CancellationToken::new())
+        .await
+        .unwrap();
+
+    assert_eq!(products.len(), 3);
+}
+
+#[tokio::test]
+async fn a_search_without_results_completes_without_a_browser() {
+    let server = MockServer::start().await;
+    let registry = registry(&server);
+    let no_results = r#"<html><body><div data-testid="no-results">Sua busca por "xyzzy" não encontrou resultado algum</div></body></html>"#;
+
+    for strategy in [FetchStrategy::Http, FetchStrategy::Auto] {
+        server.reset().await;
+        serve(&server, "1", html(200, no_results), 1).await;
+        serve(&server, "2", html(200, no_results), 0).await;
+
+        let result = registry
+            .scrape_search(&job(strategy, 2), &CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert!(result.products.is_empty(), "{:?}", strategy);
+        assert!(result.stats.totals.no_results);
+    }
+
+    assert_eq!(registry.browser_pool().metrics().leases, 0);
+}
+
+#[tokio::test]
+async fn browser_strategy_never_fetches_over_http() {
+    let server = MockServer::start().await;
+    serve(&server, "1", html(200, SEARCH), 0).await;
+
+    let products = registry(&server)
+        .scrape(&job(FetchStrategy::Browser, 1), &cancel_after(Duration::from_millis(100)))
+        .await
+        .unwrap();
+
+    assert!(products.is_empty());
+}
+
+#[test]
+fn bot_challenges_are_detected() {
+    assert!(is_bot_challenge(StatusCode::OK, ROBOT_CHECK));
+    assert!(is_bot_challenge(StatusCode::OK, CLOUDFLARE));
+    assert!(is_bot_challenge(StatusCode::TOO_MANY_REQUESTS, ""));
+    assert!(is_bot_challenge(StatusCode::SERVICE_UNAVAILABLE, "<html></html>"));
+
+    assert!(!is_bot_challenge(StatusCode::OK, SEARCH));
+    assert!(!is_bot_challenge(StatusCode::NOT_FOUND, "<html>Página não encontrada</html>"));
+}
+
+#[test]
+fn browser_pages_without_the_ready_selector_are_retried_unless_the_layout_changed() {
+    let selector = "a[data-testid='product-card-container']";
+    let redesigned = "<html><body><ul><li class=\"sc-product-card\">Smartphone</li></ul></body></html>";
+
+    // Desafio renderizado no navegador: outra tentativa (outro IP) pode passar
+    let error = unready_page_error(CLOUDFLARE, true, selector, true);
+    assert!(matches!(&error, ArcsatError::Scraping(message) if message.contains("bot challenge")));
+
+    // Página ainda carregando (lenta, proxy instável) ou marketplace sem
+    // linha de base: transitório
+    assert!(unready_page_error(redesigned, false, selector, true).is_retryable());
+    assert!(unready_page_error(redesigned, true, selector, false).is_retryable());
+
+    // Carregou, sem desafio, e a linha de base tem cards: layout mudou
+    let error = unready_page_error(redesigned, true, selector, true);
+    assert!(matches!(error, ArcsatError::Parse(_)));
+    assert!(!error.is_retryable());
+}
@@ -0,0 +1,124 @@
+//! Fixtures versionadas sem rastreamento nem dado pessoal
+//!
+//! Usa a mesma limpeza do `examples/capture_fixture`: uma fixture nova que
+//! não passou por ela (ou foi editada à mão depois) falha aqui. Os desafios
+//! anti-bot em `challenges/` ficam de fora, já que reproduzem o script da
+//! página de bloqueio de propósito.
+
+#[path = "../examples/capture_fixture/sanitize.rs"]
+mod sanitize;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn html_fixtures(dir: &Path, found: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "challenges") {
+                html_fixtures(&path, found);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "html") {
+            found.push(path);
+        }
+    }
+}
+
+#[test]
+fn sanitize_strips_tracking_and_personal_data_but_keeps_page_state() {
+    let page = r#"<html><head>
+<script src="https://www.googletagmanager.com/gtag/js?id=G-1"></script>
+<script>window.dataLayer = []; gtag('config', 'G-1');</script>
+<script id="__NEXT_DATA__" type="application/json">{"props":{"products":[]}}</script>
+<script type="application/ld+json">{"@type":"Product","name":"Fone"}</script>
+</head><body>
+<noscript><img src="https://www.facebook.com/tr?id=1&ev=PageView"></noscript>
+<iframe src="https://ads.example.net/frame"></iframe>
+<img src="https://t.example.net/p.gif" width="1" height="1">
+<img src="https://img.example.net/fone@2x.png" alt="Fone">
+<form><input type="hidden" name="csrf" value="abc123"><input type="text" name="q"></form>
+<span class="nav-line-1"> Olá, Maria Silva</span>
+<a href="/produto/fone?utm_source=google&amp;utm_medium=cpc&amp;id=42&amp;gclid=xyz#reviews">Fone</a>
+<a href="/produto/cabo?pd_rd_w=abc&amp;pf_rd_p=def">Cabo</a>
+<p>maria.silva@gmail.com · 123.456.789-09 · (11) 98765-4321 · CEP 01310-100</p>
+</body></html>"#;
+
+    let clean = sanitize::sanitize(page);
+
+    assert!(!clean.contains("googletagmanager"));
+    assert!(!clean.contains("dataLayer"));
+    assert!(clean.contains(r#"<script id="__NEXT_DATA__" type="application/json">{"props":{"products":[]}}</script>"#));
+    assert!(clean.contains(r#"{"@type":"Product","name":"Fone"}"#));
+    assert!(!clean.contains("facebook.com/tr"));
+    assert!(!clean.contains("ads.example.net"));
+    assert!(!clean.contains("p.gif"));
+    assert!(clean.contains("fone@2x.png"), "imagem não é email");
+    assert!(!clean.contains("csrf"));
+    assert!(clean.contains(r#"<input type="text" name="q">"#));
+    assert!(clean.contains("> Olá, visitante</span>"));
+    assert!(!clean.contains("Maria"));
+    assert!(clean.contains(r#"href="/produto/fone?id=42#reviews""#));
+    assert!(clean.contains(r#"href="/produto/cabo""#));
+    assert!(clean.contains("usuario@example.com · 000.000.000-00 · (00) 00000-0000 · CEP 00000-000"));
+
+    assert_eq!(sanitize::problems(&clean), Vec::<String>::new());
+    assert_eq!(sanitize::sanitize(&clean), clean, "limpar de novo não muda nada");
+    assert!(!sanitize::problems(page).is_empty());
+}
+
+#[test]
+fn committed_fixtures_have_no_tracking_or_personal_data() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut fixtures = Vec::new();
+    html_fixtures(&root, &mut fixtures);
+    assert!(!fixtures.is_empty());
+
+    for fixture in fixtures {
+        let problems = sanitize::problems(&fs::read_to_string(&fixture).unwrap());
+        assert!(
+            problems.is_empty(),
+            "{} needs capture_fixture: {:?}",
+            fixture.strip_prefix(&root).unwrap().display(),
+            problems
+        );
+    }
+}
+
+#[test]
+fn synthetic_fixtures_are_listed_until_captured() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let listed: Vec<String> = fs::read_to_string(root.join("SYNTHETIC"))
+        .unwrap()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+
+    let mut fixtures = Vec::new();
+    html_fixtures(&root, &mut fixtures);
+
+    for fixture in &fixtures {
+        let name = fixture.strip_prefix(&root).unwrap().to_string_lossy().replace('\\', "/");
+        let captured = fs::read_to_string(fixture).unwrap().starts_with(sanitize::PROVENANCE_PREFIX);
+
+        assert_eq!(
+            captured,
+            !listed.contains(&name),
+            "{}: captured fixtures start with the capture_fixture line, the others are listed in SYNTHETIC",
+            name
+        );
+    }
+    for name in &listed {
+        assert!(root.join(name).is_file(), "SYNTHETIC lists {}, which does not exist", name);
+    }
+}
+
+#[test]
+fn provenance_line_drops_tracking_from_the_url() {
+    let line = sanitize::provenance("https://www.amazon.com.br/s?k=fone&utm_source=x", "2026-10-18");
+
+    assert_eq!(line, "<!-- capture_fixture: https://www.amazon.com.br/s?k=fone em 2026-10-18 -->\n");
+    assert!(line.starts_with(sanitize::PROVENANCE_PREFIX));
+    assert!(sanitize::problems(&line).is_empty());
+}
@@ -0,0 +1,284 @@
+//! Câmbio: leitura do CSV de cotações, cotação do dia e normalização dos
+//! preços para a moeda base; no Postgres, a tabela `fx_rates`, a moeda base
+//! do tenant e quem pode carregar cotações pela API.
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... REDIS_URL=redis://... \
+//!     cargo test -p arcsat-market-intelligence --test fx_rates -- --ignored
+//! ```
+
+use arcsat_core::auth::{Claims, JwtAuth, PLATFORM_ADMIN_ROLE};
+use arcsat_core::{Currency, Money};
+use arcsat_market_intelligence::fx::{self, FxRate, FxRates};
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::repository::JobRepository;
+use arcsat_market_intelligence::MarketIntelligenceEngine;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const CSV: &str = "\
+date,base,quote,rate
+# fechamento PTAX
+2026-10-15,USD,BRL,5.40
+2026-10-16,USD,BRL,5.4312
+2026-10-16,EUR,BRL,6.30
+";
+
+fn day(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+fn rates() -> FxRates {
+    FxRates::new(fx::parse_csv(CSV).unwrap())
+}
+
+fn product(price: Money, scraped_on: NaiveDate) -> ScrapedProduct {
+    ScrapedProduct {
+        id: Uuid::new_v4(),
+        job_id: Uuid::new_v4(),
+        marketplace: Marketplace::Amazon,
+        external_id: "B0TEST".to_string(),
+        title: "Fone importado".to_string(),
+        price,
+        url: "https://www.amazon.com.br/dp/B0TEST".to_string(),
+        image_url: None,
+        pricing: Pricing {
+            list_price: Some(price.scale(dec!(1.5))),
+            installments: Some(InstallmentPlan {
+                count: 2,
+                amount: price.scale(dec!(0.5)),
+                interest_free: true,
+            }),
+            ..Pricing::default()
+        },
+        seller_name: "Loja".to_string(),
+        seller_id: None,
+        seller_rating: None,
+        sales_rank: None,
+        rating: None,
+        num_reviews: 0,
+        availability: true,
+        category: None,
+        brand: None,
+        scraped_at: Utc.from_utc_datetime(&scraped_on.and_hms_opt(12, 0, 0).unwrap()),
+        extra: serde_json::json!({}),
+    }
+}
+
+#[test]
+fn csv_rows_become_rates() {
+    let rates = fx::parse_csv(CSV).unwrap();
+
+    assert_eq!(rates.len(), 3);
+    assert_eq!(
+        rates[1],
+        FxRate {
+            date: day(16),
+            base: Currency::USD,
+            quote: Currency::BRL,
+            rate: dec!(5.4312),
+        }
+    );
+
+    // Sem cabeçalho também vale
+    assert_eq!(fx::parse_csv("2026-10-16, usd, brl, 5.43\n").unwrap()[0].base, Currency::USD);
+}
+
+#[test]
+fn invalid_csv_lines_are_rejected_with_the_line_number() {
+    for (csv, problem) in [
+        ("2026-10-16,USD,BRL\n", "expected date,base,quote,rate"),
+        ("16/10/2026,USD,BRL,5.43\n", "date must be YYYY-MM-DD"),
+        ("2026-10-16,DOLAR,BRL,5.43\n", "invalid base currency"),
+        ("2026-10-16,USD,BRL,cinco\n", "invalid rate"),
+        ("2026-10-16,BRL,BRL,1\n", "base and quote must differ"),
+        ("2026-10-16,USD,BRL,0\n", "rate must be positive"),
+    ] {
+        let error = fx::parse_csv(&format!("date,base,quote,rate\n{}", csv)).unwrap_err();
+        assert_eq!(error.to_string(), format!("Validation error: FX rates line 2: {}", problem));
+    }
+}
+
+#[test]
+fn rate_is_the_latest_up_to_the_day() {
+    let rates = rates();
+
+    assert_eq!(rates.rate(Currency::USD, Currency::BRL, day(15)), Some(dec!(5.40)));
+    assert_eq!(rates.rate(Currency::USD, Currency::BRL, day(16)), Some(dec!(5.4312)));
+    // Fim de semana: vale a de sexta
+    assert_eq!(rates.rate(Currency::USD, Currency::BRL, day(18)), Some(dec!(5.4312)));
+    // Antes da primeira cotação não há o que usar
+    assert_eq!(rates.rate(Currency::USD, Currency::BRL, day(14)), None);
+    assert_eq!(rates.rate(Currency::USD, Currency::EUR, day(16)), None);
+
+    assert_eq!(rates.rate(Currency::BRL, Currency::BRL, day(1)), Some(Decimal::ONE));
+}
+
+#[test]
+fn missing_direction_uses_the_inverse() {
+    let rates = rates();
+
+    assert_eq!(
+        rates.convert(Money::brl(dec!(54.312)), Currency::USD, day(16)),
+        Some(Money::new(dec!(10.00), Currency::USD))
+    );
+
+    // Com os dois sentidos, vale o mais recente
+    let both = FxRates::new([
+        FxRate { date: day(15), base: Currency::USD, quote: Currency::BRL, rate: dec!(5) },
+        FxRate { date: day(16), base: Currency::BRL, quote: Currency::USD, rate: dec!(0.25) },
+    ]);
+    assert_eq!(both.rate(Currency::USD, Currency::BRL, day(16)), Some(dec!(4)));
+    assert_eq!(both.rate(Currency::USD, Currency::BRL, day(15)), Some(dec!(5)));
+}
+
+#[test]
+fn products_are_normalized_to_the_base_currency() {
+    let products = [
+        product(Money::new(dec!(10.00), Currency::USD), day(16)),
+        product(Money::brl(dec!(99.90)), day(16)),
+        product(Money::new(dec!(10.00), Currency::new("GBP").unwrap()), day(16)),
+    ];
+
+    let normalized = fx::normalize(&products, &rates(), Currency::BRL);
+
+    assert_eq!(normalized.missing, vec![Currency::new("GBP").unwrap()]);
+    assert_eq!(normalized.products.len(), 2);
+
+    let imported = &normalized.products[0];
+    assert_eq!(imported.price, Money::brl(dec!(54.31)));
+    assert_eq!(imported.pricing.list_price, Some(Money::brl(dec!(81.47))));
+    assert_eq!(imported.pricing.installments.map(|plan| plan.amount), Some(Money::brl(dec!(27.16))));
+
+    assert_eq!(normalized.products[1].price, Money::brl(dec!(99.90)));
+}
+
+async fn pool() -> PgPool {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPool::connect(&url).await.expect("connect to Postgres");
+
+    sqlx::migrate!("../migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    pool
+}
+
+async fn repository() -> JobRepository {
+    JobRepository::new(pool().await)
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn stored_rates_are_upserted_and_read_by_day() {
+    let repository = repository().await;
+
+    // Códigos reservados para teste (ISO 4217), fora do que a API carrega
+    let xts = Currency::new("XTS").unwrap();
+    let xxx = Currency::new("XXX").unwrap();
+    let rate = |date, rate| FxRate { date, base: xts, quote: xxx, rate };
+
+    let saved = repository
+        .save_fx_rates(&[rate(day(15), dec!(2)), rate(day(16), dec!(3)), rate(day(16), dec!(3.5))])
+        .await
+        .unwrap();
+    assert_eq!(saved, 2);
+
+    // Recarregar o mesmo dia substitui a cotação
+    repository.save_fx_rates(&[rate(day(16), dec!(4))]).await.unwrap();
+
+    let listed = repository.list_fx_rates(day(18)).await.unwrap();
+    assert!(listed.contains(&rate(day(16), dec!(4))));
+
+    let listed = repository.list_fx_rates(day(15)).await.unwrap();
+    assert!(listed.contains(&rate(day(15), dec!(2))));
+
+    // A cotação anterior ao período também entra (coleta de segunda com a de sexta)
+    let rates = repository.fx_rates(day(17), day(18)).await.unwrap();
+    assert_eq!(rates.rate(xts, xxx, day(18)), Some(dec!(4)));
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn tenant_base_currency_defaults_to_brl() {
+    let repository = repository().await;
+    let tenant_id = Uuid::new_v4();
+
+    assert_eq!(repository.get_tenant_settings(tenant_id).await.unwrap().base_currency, Currency::BRL);
+
+    let settings = TenantSettings { base_currency: Currency::USD };
+    repository.save_tenant_settings(tenant_id, &settings).await.unwrap();
+
+    assert_eq!(repository.get_tenant_settings(tenant_id).await.unwrap().base_currency, Currency::USD);
+    // Outro tenant continua no padrão
+    assert_eq!(repository.get_tenant_settings(Uuid::new_v4()).await.unwrap().base_currency, Currency::BRL);
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL) and Redis (REDIS_URL)"]
+async fn loading_rates_requires_the_platform_role() {
+    const SECRET: &[u8] = b"fx-rates-test";
+
+    let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+    let engine = Arc::new(MarketIntelligenceEngine::new(&redis_url, pool().await, None).await.unwrap());
+    let app = arcsat_market_intelligence::router(engine.clone(), JwtAuth::hs256(SECRET));
+
+    let loaded_on = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    sqlx::query("DELETE FROM fx_rates WHERE base = 'XTS' AND rate_date = $1")
+        .bind(loaded_on)
+        .execute(engine.repository.pool())
+        .await
+        .unwrap();
+
+    let load = |role: &str| {
+        let claims = Claims {
+            sub: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            exp: Utc::now().timestamp() + 300,
+            iat: None,
+            roles: vec![role.to_string()],
+        };
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(SECRET),
+        )
+        .unwrap();
+
+        let request = Request::post("/api/v1/market-intelligence/fx-rates")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "text/csv")
+            .body(Body::from("2026-01-05,XTS,XXX,7\n"))
+            .unwrap();
+
+        let app = app.clone();
+        async move {
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+        }
+    };
+
+    let xts = Currency::new("XTS").unwrap();
+    let loaded = |rates: &[FxRate]| rates.iter().any(|rate| rate.base == xts && rate.date == loaded_on);
+
+    // O admin do tenant não escreve na tabela global
+    let (status, body) = load("admin").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "FORBIDDEN");
+    assert!(!loaded(&engine.repository.list_fx_rates(loaded_on).await.unwrap()));
+
+    let (status, body) = load(PLATFORM_ADMIN_ROLE).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["loaded"], 1);
+    assert!(loaded(&engine.repository.list_fx_rates(loaded_on).await.unwrap()));
+}
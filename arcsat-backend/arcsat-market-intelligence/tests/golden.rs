@@ -0,0 +1,159 @@
+//! Regressão dos parsers sobre as páginas salvas em `tests/fixtures/<marketplace>`
+//!
+//! Cada fixture tem ao lado um `.golden.json` com os campos extraídos. Um
+//! seletor quebrado muda a saída (ou zera os produtos) e o teste falha. Depois
+//! de revisar uma mudança intencional, regrave com
+//! `UPDATE_GOLDEN=1 cargo test -p arcsat-market-intelligence --test golden`.
+
+use arcsat_core::Money;
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{
+    listing_product, AliExpressScraper, AmazonScraper, AmericanasScraper, ApiScraper, MagaluScraper,
+    MarketplaceScraper, MercadoLivreScraper, ShopeeScraper,
+};
+use rust_decimal_macros::dec;
+use scraper::Html;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn fixture_path(fixture: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(fixture)
+}
+
+fn read_fixture(fixture: &str) -> String {
+    fs::read_to_string(fixture_path(fixture)).unwrap_or_else(|e| panic!("{}: {}", fixture, e))
+}
+
+fn job(marketplace: Marketplace) -> ScrapingJob {
+    ScrapingJob::new(Uuid::new_v4(), marketplace, "fone bluetooth".to_string(), 1)
+}
+
+/// Campos comparados: tudo menos o que é gerado na extração
+fn golden_product(product: &ScrapedProduct) -> Value {
+    let mut value = serde_json::to_value(product).unwrap();
+    let fields = value.as_object_mut().unwrap();
+    for generated in ["id", "job_id", "scraped_at"] {
+        fields.remove(generated);
+    }
+    value
+}
+
+/// Compara com `<fixture>.golden.json` (ou o regrava, com `UPDATE_GOLDEN`)
+fn assert_golden(fixture: &str, actual: Value) {
+    let golden = fixture_path(fixture).with_extension("golden.json");
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+
+    let expected: Value = fs::read_to_string(&golden)
+        .map(|text| serde_json::from_str(&text).unwrap())
+        .unwrap_or_else(|_| panic!("{} not found; generate it with UPDATE_GOLDEN=1", golden.display()));
+
+    assert!(
+        expected == actual,
+        "{} no longer matches {}\nexpected: {}\nactual: {}",
+        fixture,
+        golden.display(),
+        serde_json::to_string_pretty(&expected).unwrap(),
+        serde_json::to_string_pretty(&actual).unwrap()
+    );
+}
+
+/// Página de busca salva, extraída sem navegador nem rede
+fn search(scraper: &dyn MarketplaceScraper, fixture: &str) -> Value {
+    let page = scraper.parse_search(&job(scraper.marketplace()), &read_fixture(fixture), 1);
+
+    assert!(!page.products.is_empty(), "{}: no products extracted", fixture);
+
+    json!({
+        "ready": page.ready,
+        "has_next": page.has_next,
+        "stats": page.stats,
+        "products": page.products.iter().map(golden_product).collect::<Vec<_>>(),
+    })
+}
+
+/// Página de produto salva aplicada a um produto da busca
+fn detail(scraper: &dyn MarketplaceScraper, fixture: &str) -> Value {
+    let job = job(scraper.marketplace());
+    let mut product = listing_product(
+        &job,
+        "Fone de Ouvido JBL Tune 520BT".to_string(),
+        Money::brl(dec!(249.99)),
+        "https://example.com/p/1".to_string(),
+    );
+
+    scraper.parse_detail(&mut product, &Html::parse_document(&read_fixture(fixture)));
+    golden_product(&product)
+}
+
+/// Resposta salva de uma API de busca
+fn api_search(scraper: &dyn ApiScraper, fixture: &str, page: u32) -> Value {
+    let body: Value = serde_json::from_str(&read_fixture(fixture)).unwrap();
+    let products = scraper.parse_response(&job(scraper.marketplace()), page, &body).unwrap();
+
+    assert!(!products.is_empty(), "{}: no products extracted", fixture);
+
+    json!({
+        "has_next": scraper.has_next_page(&body, page),
+        "products": products.iter().map(golden_product).collect::<Vec<_>>(),
+    })
+}
+
+#[test]
+fn amazon() {
+    let scraper = AmazonScraper::new().unwrap();
+
+    assert_golden("amazon/search.html", search(&scraper, "amazon/search.html"));
+    assert_golden("amazon/detail.html", detail(&scraper, "amazon/detail.html"));
+    assert_golden(
+        "amazon/detail_sold_by_amazon.html",
+        detail(&scraper, "amazon/detail_sold_by_amazon.html"),
+    );
+}
+
+#[test]
+fn mercado_livre() {
+    let scraper = MercadoLivreScraper::new().unwrap();
+
+    assert_golden("mercado_livre/search.html", search(&scraper, "mercado_livre/search.html"));
+    assert_golden("mercado_livre/detail.html", detail(&scraper, "mercado_livre/detail.html"));
+}
+
+#[test]
+fn magalu() {
+    let scraper = MagaluScraper::new().unwrap();
+
+    assert_golden("magalu/search.html", search(&scraper, "magalu/search.html"));
+    assert_golden("magalu/detail.html", detail(&scraper, "magalu/detail.html"));
+}
+
+#[test]
+fn americanas() {
+    let scraper = AmericanasScraper::new().unwrap();
+
+    assert_golden("americanas/search.html", search(&scraper, "americanas/search.html"));
+    assert_golden(
+        "americanas/search_without_state.html",
+        search(&scraper, "americanas/search_without_state.html"),
+    );
+}
+
+#[test]
+fn shopee() {
+    let scraper = ShopeeScraper::new();
+
+    assert_golden("shopee/search_page1.json", api_search(&scraper, "shopee/search_page1.json", 1));
+    assert_golden("shopee/search_page2.json", api_search(&scraper, "shopee/search_page2.json", 2));
+}
+
+#[test]
+fn aliexpress() {
+    let scraper = AliExpressScraper::new();
+
+    assert_golden("aliexpress/search.json", api_search(&scraper, "aliexpress/search.json", 1));
+}
@@ -0,0 +1,318 @@
+//! Leitura de jobs e da listagem paginada de produtos no Postgres.
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... cargo test -p arcsat-market-intelligence --test job_results -- --ignored
+//! ```
+
+use arcsat_core::Money;
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::repository::JobRepository;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn repository() -> JobRepository {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPool::connect(&url).await.expect("connect to Postgres");
+
+    sqlx::migrate!("../migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    JobRepository::new(pool)
+}
+
+fn product(job_id: Uuid, external_id: &str, price: Decimal, seller: &str, available: bool) -> ScrapedProduct {
+    ScrapedProduct {
+        id: Uuid::new_v4(),
+        job_id,
+        marketplace: Marketplace::Amazon,
+        external_id: external_id.to_string(),
+        title: format!("Produto {}", external_id),
+        price: Money::brl(price),
+        url: format!("https://www.amazon.com.br/dp/{}", external_id),
+        image_url: None,
+        pricing: Pricing {
+            list_price: Some(Money::brl(price + dec!(5))),
+            installments: Some(InstallmentPlan {
+                count: 2,
+                amount: Money::brl(price / dec!(2)),
+                interest_free: true,
+            }),
+            ..Pricing::default()
+        },
+        seller_name: seller.to_string(),
+        seller_id: None,
+        seller_rating: Some(4.5),
+        sales_rank: None,
+        rating: Some(4.25),
+        num_reviews: 10,
+        availability: available,
+        category: None,
+        brand: None,
+        scraped_at: chrono::Utc::now(),
+        extra: serde_json::json!({ "sponsored": false }),
+    }
+}
+
+/// Job com cinco produtos: preços 10, 20, 30, 40 e 50
+async fn job_with_products(repository: &JobRepository) -> ScrapingJob {
+    let mut job = ScrapingJob::new(Uuid::new_v4(), Marketplace::Amazon, "fone".to_string(), 1);
+    job.status = JobStatus::Completed;
+    job.attempts = 2;
+    job.record_error("navigation timeout");
+    repository.save_job(&job).await.unwrap();
+
+    let products = [
+        product(job.id, "A", dec!(30), "Loja Azul", true),
+        product(job.id, "B", dec!(10), "Loja Azul", false),
+        product(job.id, "C", dec!(50), "Outra Loja", true),
+        product(job.id, "D", dec!(20), "Outra Loja", true),
+        product(job.id, "E", dec!(40), "Loja Azul", true),
+    ];
+    repository.save_products(job.tenant_id, &products).await.unwrap();
+
+    job
+}
+
+fn prices(products: &[ScrapedProduct]) -> Vec<Decimal> {
+    products.iter().map(|p| p.price.amount()).collect()
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn stored_job_is_returned_with_its_result_count() {
+    let repository = repository().await;
+    let job = job_with_products(&repository).await;
+
+    let stored = repository.get_job(job.tenant_id, job.id).await.unwrap().unwrap();
+    assert_eq!(stored.status, JobStatus::Completed);
+    assert_eq!(stored.attempts, 2);
+    assert_eq!(stored.error.as_deref(), Some("navigation timeout"));
+    assert_eq!(stored.error_history.len(), 1);
+    assert_eq!(repository.count_products(job.tenant_id, job.id).await.unwrap(), 5);
+
+    assert!(repository.get_job(Uuid::new_v4(), job.id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn products_without_an_external_id_are_not_saved() {
+    let repository = repository().await;
+    let job = ScrapingJob::new(Uuid::new_v4(), Marketplace::Magalu, "fone".to_string(), 1);
+    repository.save_job(&job).await.unwrap();
+
+    // Os dois sem id não podem virar uma linha só em `(job_id, "")`
+    let products = [
+        product(job.id, "", dec!(10), "Loja Azul", true),
+        product(job.id, "", dec!(20), "Outra Loja", true),
+        product(job.id, "A", dec!(30), "Loja Azul", true),
+    ];
+
+    assert_eq!(repository.save_products(job.tenant_id, &products).await.unwrap(), 1);
+    assert_eq!(repository.count_products(job.tenant_id, job.id).await.unwrap(), 1);
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn products_are_paginated_by_price() {
+    let repository = repository().await;
+    let job = job_with_products(&repository).await;
+    let all = ProductFilter::default();
+
+    let (first, total) = repository.list_products(job.tenant_id, job.id, &all, 1, 2).await.unwrap().unwrap();
+    assert_eq!(total, 5);
+    assert_eq!(prices(&first), [dec!(10), dec!(20)]);
+    assert_eq!(first[0].rating, Some(4.25));
+    assert_eq!(first[0].extra["sponsored"], false);
+    assert_eq!(first[0].pricing.list_price, Some(Money::brl(dec!(15))));
+    assert_eq!(first[0].pricing.installments.map(|i| (i.count, i.amount)), Some((2, Money::brl(dec!(5)))));
+
+    let (last, _) = repository.list_products(job.tenant_id, job.id, &all, 3, 2).await.unwrap().unwrap();
+    assert_eq!(prices(&last), [dec!(50)]);
+
+    assert!(repository.list_products(Uuid::new_v4(), job.id, &all, 1, 2).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn products_are_filtered_by_price_seller_and_availability() {
+    let repository = repository().await;
+    let job = job_with_products(&repository).await;
+
+    let filter = ProductFilter {
+        min_price: Some(dec!(15)),
+        max_price: Some(dec!(45)),
+        ..Default::default()
+    };
+    let (products, total) = repository.list_products(job.tenant_id, job.id, &filter, 1, 50).await.unwrap().unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(prices(&products), [dec!(20), dec!(30), dec!(40)]);
+
+    let filter = ProductFilter {
+        seller: Some("loja azul".to_string()),
+        available: Some(true),
+        ..Default::default()
+    };
+    let (products, total) = repository.list_products(job.tenant_id, job.id, &filter, 1, 50).await.unwrap().unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(prices(&products), [dec!(30), dec!(40)]);
+}
+
+/// Três jobs de um tenant novo, criados com um segundo de diferença
+async fn tenant_with_jobs(repository: &JobRepository) -> Vec<ScrapingJob> {
+    let tenant_id = Uuid::new_v4();
+    // Segundos inteiros: o Postgres guarda microssegundos
+    let base = chrono::DateTime::from_timestamp(chrono::Utc::now().timestamp() - 3600, 0).unwrap();
+    let mut jobs = Vec::new();
+
+    for (i, (query, priority, status)) in [
+        ("fone bluetooth", 5, JobStatus::Completed),
+        ("Notebook 100%", 9, JobStatus::Failed),
+        ("fone com fio", 5, JobStatus::Pending),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut job = ScrapingJob::new(tenant_id, Marketplace::Amazon, query.to_string(), 1);
+        job.priority = priority;
+        job.status = status;
+        job.created_at = base + chrono::Duration::seconds(i as i64);
+        repository.save_job(&job).await.unwrap();
+        jobs.push(job);
+    }
+
+    jobs
+}
+
+fn queries(jobs: &[JobDetails]) -> Vec<&str> {
+    jobs.iter().map(|j| j.job.search_query.as_str()).collect()
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn jobs_are_listed_with_keyset_cursor() {
+    let repository = repository().await;
+    let jobs = tenant_with_jobs(&repository).await;
+    let tenant_id = jobs[0].tenant_id;
+    let all = JobFilter::default();
+
+    let first = repository
+        .list_jobs(tenant_id, &all, JobSort::CreatedAt, SortOrder::Desc, None, 2)
+        .await
+        .unwrap();
+    assert_eq!(queries(&first), ["fone com fio", "Notebook 100%"]);
+
+    let cursor = JobCursor::decode(&JobCursor::from_job(&first[1].job).encode()).unwrap();
+    let rest = repository
+        .list_jobs(tenant_id, &all, JobSort::CreatedAt, SortOrder::Desc, Some(cursor), 2)
+        .await
+        .unwrap();
+    assert_eq!(queries(&rest), ["fone bluetooth"]);
+
+    let by_priority = repository
+        .list_jobs(tenant_id, &all, JobSort::Priority, SortOrder::Desc, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(queries(&by_priority), ["Notebook 100%", "fone com fio", "fone bluetooth"]);
+
+    let cursor = JobCursor::from_job(&by_priority[1].job);
+    let rest = repository
+        .list_jobs(tenant_id, &all, JobSort::Priority, SortOrder::Desc, Some(cursor), 10)
+        .await
+        .unwrap();
+    assert_eq!(queries(&rest), ["fone bluetooth"]);
+
+    assert!(repository
+        .list_jobs(Uuid::new_v4(), &all, JobSort::CreatedAt, SortOrder::Desc, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn jobs_are_filtered_by_status_date_and_text() {
+    let repository = repository().await;
+    let jobs = tenant_with_jobs(&repository).await;
+    let tenant_id = jobs[0].tenant_id;
+
+    let list = |filter: JobFilter| {
+        let repository = &repository;
+        async move {
+            repository
+                .list_jobs(tenant_id, &filter, JobSort::CreatedAt, SortOrder::Asc, None, 10)
+                .await
+                .unwrap()
+        }
+    };
+
+    let found = list(JobFilter { search: Some("FONE".to_string()), ..Default::default() }).await;
+    assert_eq!(queries(&found), ["fone bluetooth", "fone com fio"]);
+
+    // `%` é literal, não curinga
+    let found = list(JobFilter { search: Some("100%".to_string()), ..Default::default() }).await;
+    assert_eq!(queries(&found), ["Notebook 100%"]);
+    let found = list(JobFilter { search: Some("%".to_string()), ..Default::default() }).await;
+    assert_eq!(found.len(), 1);
+
+    let found = list(JobFilter { status: Some(JobStatus::Pending), ..Default::default() }).await;
+    assert_eq!(queries(&found), ["fone com fio"]);
+
+    let found = list(JobFilter {
+        created_from: Some(jobs[1].created_at),
+        created_to: Some(jobs[2].created_at),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(queries(&found), ["Notebook 100%"]);
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn stored_analyses_are_listed_by_marketplace_and_category() {
+    let repository = repository().await;
+    let tenant_id = Uuid::new_v4();
+
+    let analysis = |marketplace, category: &str| TrendAnalysis {
+        id: Uuid::new_v4(),
+        tenant_id,
+        marketplace,
+        category: category.to_string(),
+        period_start: chrono::Utc::now(),
+        period_end: chrono::Utc::now(),
+        total_products: 3,
+        avg_price: Money::brl(dec!(20)),
+        median_price: Money::brl(dec!(20)),
+        min_price: Money::brl(dec!(10)),
+        max_price: Money::brl(dec!(30)),
+        top_sellers: vec!["Loja Azul".to_string()],
+        trending_keywords: vec!["fone".to_string()],
+        growth_rate: 12.5,
+        competition_level: CompetitionLevel::Medium,
+        analyzed_at: chrono::Utc::now(),
+    };
+
+    let fones = analysis(Marketplace::Amazon, "Fones");
+    repository.save_analysis(&fones).await.unwrap();
+    repository.save_analysis(&analysis(Marketplace::Magalu, "Fones")).await.unwrap();
+    repository.save_analysis(&analysis(Marketplace::Amazon, "Notebooks")).await.unwrap();
+
+    assert_eq!(repository.list_analyses(tenant_id, None, None, 50).await.unwrap().len(), 3);
+
+    let listed = repository
+        .list_analyses(tenant_id, Some(Marketplace::Amazon), Some("fones"), 50)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, fones.id);
+    assert_eq!(listed[0].min_price, Money::brl(dec!(10)));
+    assert_eq!(listed[0].top_sellers, fones.top_sellers);
+    assert_eq!(listed[0].growth_rate, 12.5);
+    assert_eq!(listed[0].competition_level, CompetitionLevel::Medium);
+
+    // Outro tenant não vê as análises
+    assert!(repository.list_analyses(Uuid::new_v4(), None, None, 50).await.unwrap().is_empty());
+}
@@ -0,0 +1,88 @@
+//! Extração da busca da Magalu sobre HTML salvo (`tests/fixtures/magalu`)
+
+use arcsat_core::Money;
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{MagaluScraper, MarketplaceScraper};
+use rust_decimal_macros::dec;
+use scraper::Html;
+use uuid::Uuid;
+
+fn parse(fixture: &str) -> Vec<ScrapedProduct> {
+    let job = ScrapingJob::new(Uuid::new_v4(), Marketplace::Magalu, "smartphone".to_string(), 1);
+    let scraper = MagaluScraper::new().unwrap();
+
+    scraper.parse_listing(&job, &Html::parse_document(fixture))
+}
+
+#[test]
+fn search_page_products_are_extracted() {
+    let products = parse(include_str!("fixtures/magalu/search.html"));
+
+    // O card indisponível (sem preço) fica de fora
+    assert_eq!(products.len(), 3);
+
+    let galaxy = &products[0];
+    assert_eq!(galaxy.external_id, "237412400");
+    assert_eq!(galaxy.title, "Smartphone Samsung Galaxy A15 128GB Azul Escuro 4G");
+    assert_eq!(galaxy.price, Money::brl(dec!(899.0)));
+    assert_eq!(galaxy.seller_name, "Magazine Luiza");
+    assert_eq!(galaxy.rating, Some(4.8));
+    assert_eq!(galaxy.num_reviews, 2351);
+    assert_eq!(
+        galaxy.url,
+        "https://www.magazineluiza.com.br/smartphone-samsung-galaxy-a15-128gb-azul-escuro-4g/p/237412400/te/ga15/"
+    );
+    assert_eq!(
+        galaxy.image_url.as_deref(),
+        Some("https://a-static.mlcdn.com.br/280x210/smartphone-samsung-galaxy-a15/237412400.jpg")
+    );
+    assert_eq!(
+        galaxy.pricing,
+        Pricing {
+            list_price: Some(Money::brl(dec!(1299.0))),
+            pix_price: Some(Money::brl(dec!(854.05))),
+            max_price: None,
+            installments: Some(InstallmentPlan {
+                count: 10,
+                amount: Money::brl(dec!(89.9)),
+                interest_free: true,
+            }),
+        }
+    );
+}
+
+#[test]
+fn third_party_sellers_and_missing_fields_are_handled() {
+    let products = parse(include_str!("fixtures/magalu/search.html"));
+
+    let fone = &products[1];
+    assert_eq!(fone.external_id, "jb3982hj8a");
+    assert_eq!(fone.seller_name, "Loja Som Total");
+    assert_eq!(fone.price, Money::brl(dec!(249.99)));
+    assert_eq!(fone.rating, None);
+    assert_eq!(fone.num_reviews, 0);
+    assert_eq!(fone.pricing.list_price, None);
+    assert_eq!(fone.pricing.installments.map(|i| i.interest_free), Some(false));
+
+    // Só preço à vista: vira o preço do produto
+    let cabo = &products[2];
+    assert_eq!(cabo.price, Money::brl(dec!(19.9)));
+    assert_eq!(cabo.pricing, Pricing::default());
+    assert!(cabo.image_url.is_none());
+}
+
+#[test]
+fn search_url_is_paginated() {
+    let job = ScrapingJob::new(Uuid::new_v4(), Marketplace::Magalu, "fone bluetooth".to_string(), 3);
+    let scraper = MagaluScraper::new().unwrap();
+
+    assert_eq!(
+        scraper.search_url(&job, 2),
+        "https://www.magazineluiza.com.br/busca/fone%20bluetooth/?page=2"
+    );
+}
+
+#[test]
+fn page_without_results_yields_nothing() {
+    assert!(parse("<html><body><p>Nenhum resultado</p></body></html>").is_empty());
+}
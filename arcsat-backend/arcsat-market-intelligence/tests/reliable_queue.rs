@@ -0,0 +1,442 @@
+//! Entrega confiável da fila: prioridades, reaper, tentativas e dead-letter
+//! queue.
+//!
+//! Dependem de Redis, por isso ficam `#[ignore]`:
+//!
+//! ```bash
+//! REDIS_URL=redis://... cargo test -p arcsat-market-intelligence --test reliable_queue -- --ignored
+//! ```
+//!
+//! O dequeue consulta todas as filas, então os testes rodam um de cada vez
+//! (`REDIS_LOCK`); jobs de outras execuções que estiverem na fila são
+//! descartados.
+
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::queue::{Cancellation, JobQueue};
+use arcsat_market_intelligence::scrapers::BrowserPoolMetrics;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+static REDIS_LOCK: Mutex<()> = Mutex::const_new(());
+
+async fn exclusive() -> MutexGuard<'static, ()> {
+    REDIS_LOCK.lock().await
+}
+
+async fn queue() -> JobQueue {
+    let url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+    JobQueue::new(&url).await.expect("connect to Redis")
+}
+
+fn job(priority: u8) -> ScrapingJob {
+    let mut job = ScrapingJob::new(Uuid::new_v4(), Marketplace::MercadoLivre, "notebook".to_string(), 1);
+    job.priority = priority;
+    job
+}
+
+fn worker() -> String {
+    format!("test-worker-{}", Uuid::new_v4())
+}
+
+/// Tira da fila até encontrar `job_id`, confirmando (ack) os demais
+async fn dequeue_job(queue: &JobQueue, worker_id: &str, job_id: Uuid) -> ScrapingJob {
+    loop {
+        let job = queue
+            .dequeue(worker_id, Duration::ZERO)
+            .await
+            .unwrap()
+            .expect("job should be in the queue");
+
+        if job.id == job_id {
+            return job;
+        }
+        queue.ack(worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+    }
+}
+
+/// Esvazia as filas de prioridade
+async fn drain(queue: &JobQueue) {
+    let worker_id = worker();
+    while let Some(job) = queue.dequeue(&worker_id, Duration::ZERO).await.unwrap() {
+        queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn dequeue_takes_the_highest_priority_first_and_fifo_within_a_priority() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+    drain(&queue).await;
+
+    let jobs = [job(3), job(9), job(5), job(9), job(3)];
+    for job in &jobs {
+        queue.enqueue(job.clone()).await.unwrap();
+    }
+
+    let mut order = Vec::new();
+    while let Some(job) = queue.dequeue(&worker_id, Duration::ZERO).await.unwrap() {
+        queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+        order.push(job.id);
+    }
+
+    let expected: Vec<Uuid> = [1, 3, 2, 0, 4].iter().map(|&i| jobs[i].id).collect();
+    assert_eq!(order, expected);
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn blocking_dequeue_wakes_up_when_a_job_arrives() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+    drain(&queue).await;
+
+    let job = job(4);
+    let producer = {
+        let job = job.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            self::queue().await.enqueue(job).await.unwrap();
+        })
+    };
+
+    let started = Instant::now();
+    let dequeued = queue
+        .dequeue(&worker_id, Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("job should arrive before the timeout");
+
+    assert_eq!(dequeued.id, job.id);
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    producer.await.unwrap();
+    queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn blocking_dequeue_times_out_on_empty_queues() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    drain(&queue).await;
+
+    let started = Instant::now();
+    assert!(queue.dequeue(&worker(), Duration::from_millis(500)).await.unwrap().is_none());
+    assert!(started.elapsed() >= Duration::from_millis(400));
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn stalled_worker_jobs_go_back_to_the_queue() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let (crashed, healthy) = (worker(), worker());
+
+    let job = job(9);
+    queue.enqueue(job.clone()).await.unwrap();
+
+    let first = dequeue_job(&queue, &crashed, job.id).await;
+    assert_eq!(first.attempts, 1);
+
+    // O worker morre sem ack; o reaper devolve o job para a fila
+    let reaped = queue.reap_worker(&crashed, 3).await.unwrap();
+    assert_eq!(reaped.len(), 1);
+    assert_eq!(reaped[0].job_id, job.id.to_string());
+    assert!(!reaped[0].dead);
+
+    let second = dequeue_job(&queue, &healthy, job.id).await;
+    assert_eq!(second.attempts, 2);
+    queue.ack(&healthy, job.tenant_id, &job.id.to_string()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn jobs_released_on_shutdown_keep_their_attempt() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let (stopping, next) = (worker(), worker());
+
+    let job = job(9);
+    queue.enqueue(job.clone()).await.unwrap();
+    assert_eq!(dequeue_job(&queue, &stopping, job.id).await.attempts, 1);
+
+    // Mesmo na última tentativa o job volta para a fila, sem ir para a DLQ
+    let released = queue.release_worker(&stopping).await.unwrap();
+    assert_eq!(released.len(), 1);
+    assert!(!released[0].dead);
+    assert!(queue.dead_letters(job.tenant_id).await.unwrap().is_empty());
+
+    let status = queue.get_status(job.tenant_id, &job.id.to_string()).await.unwrap();
+    assert_eq!(status.status, JobStatus::Pending);
+    assert_eq!(status.attempts, 0);
+
+    let retried = dequeue_job(&queue, &next, job.id).await;
+    assert_eq!(retried.attempts, 1);
+    queue.ack(&next, job.tenant_id, &job.id.to_string()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn claim_counts_the_attempt_and_skips_entries_without_a_job() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+    drain(&queue).await;
+
+    // Entrada órfã (hash expirado ou apagado) na frente da fila
+    let url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+    let mut conn = redis::Client::open(url).unwrap().get_multiplexed_async_connection().await.unwrap();
+    let orphan = format!("{}:{}", Uuid::new_v4(), Uuid::new_v4());
+    redis::cmd("LPUSH").arg("queue:priority:10").arg(&orphan).query_async::<_, ()>(&mut conn).await.unwrap();
+
+    assert!(queue.dequeue(&worker_id, Duration::ZERO).await.unwrap().is_none());
+    let processing: usize = redis::cmd("LLEN")
+        .arg(format!("queue:processing:{}", worker_id))
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(processing, 0);
+
+    let job = job(10);
+    queue.enqueue(job.clone()).await.unwrap();
+
+    let claimed = dequeue_job(&queue, &worker_id, job.id).await;
+    assert_eq!(claimed.attempts, 1);
+    // A tentativa já está no hash quando o dequeue retorna
+    assert_eq!(queue.get_status(job.tenant_id, &job.id.to_string()).await.unwrap().attempts, 1);
+
+    queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn jobs_past_their_deadline_are_reaped_while_the_worker_heartbeats() {
+    let _guard = exclusive().await;
+    let mut queue = queue().await;
+    queue.set_job_timeout(Duration::ZERO);
+    let (stuck, healthy) = (worker(), worker());
+
+    let job = job(9);
+    let job_id = job.id.to_string();
+    queue.enqueue(job.clone()).await.unwrap();
+
+    dequeue_job(&queue, &stuck, job.id).await;
+    queue.heartbeat(&stuck).await.unwrap();
+
+    // O worker está vivo: pelo heartbeat nada é recuperado
+    let stalled = queue.reap_stalled(Duration::from_secs(60), 3).await.unwrap();
+    assert!(stalled.iter().all(|reaped| reaped.job_id != job_id));
+
+    let expired = queue.reap_expired(Duration::ZERO, 3).await.unwrap();
+    let reaped = expired.iter().find(|reaped| reaped.job_id == job_id).expect("job past its deadline");
+    assert!(!reaped.dead);
+
+    let second = dequeue_job(&queue, &healthy, job.id).await;
+    assert_eq!(second.attempts, 2);
+
+    // Com ack o prazo sai junto e não há o que recuperar
+    queue.ack(&healthy, job.tenant_id, &job_id).await.unwrap();
+    let expired = queue.reap_expired(Duration::ZERO, 3).await.unwrap();
+    assert!(expired.iter().all(|reaped| reaped.job_id != job_id));
+    assert!(queue.reap_worker(&stuck, 3).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn acked_jobs_are_not_reaped() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+
+    let job = job(8);
+    queue.enqueue(job.clone()).await.unwrap();
+
+    dequeue_job(&queue, &worker_id, job.id).await;
+    queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+
+    assert!(queue.reap_worker(&worker_id, 3).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn jobs_out_of_attempts_go_to_the_dead_letter_queue_and_can_be_replayed() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+
+    let job = job(7);
+    queue.enqueue(job.clone()).await.unwrap();
+
+    let mut running = dequeue_job(&queue, &worker_id, job.id).await;
+    let error = running.record_error("browser crashed");
+    queue.record_error(job.tenant_id, &job.id.to_string(), &error).await.unwrap();
+
+    let reaped = queue.reap_worker(&worker_id, 1).await.unwrap();
+    assert_eq!(reaped.len(), 1);
+    assert!(reaped[0].dead);
+
+    let dead = queue.dead_letters(job.tenant_id).await.unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].id, job.id);
+    assert_eq!(dead[0].attempts, 1);
+    assert_eq!(dead[0].status, JobStatus::Failed);
+    assert_eq!(dead[0].error_history.len(), 1);
+    assert_eq!(dead[0].error.as_deref(), Some("browser crashed"));
+
+    // A DLQ é por tenant
+    assert!(queue.dead_letters(Uuid::new_v4()).await.unwrap().is_empty());
+    assert!(queue.replay_dead_letter(Uuid::new_v4(), &job.id.to_string()).await.is_err());
+
+    let replayed = queue.replay_dead_letter(job.tenant_id, &job.id.to_string()).await.unwrap();
+    assert_eq!(replayed.attempts, 0);
+    assert!(queue.dead_letters(job.tenant_id).await.unwrap().is_empty());
+
+    let retried = dequeue_job(&queue, &worker_id, job.id).await;
+    assert_eq!(retried.attempts, 1);
+    assert_eq!(retried.error_history.len(), 1);
+    queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn replayed_jobs_start_without_the_previous_cancel_request() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+
+    let job = job(7);
+    let job_id = job.id.to_string();
+    queue.enqueue(job.clone()).await.unwrap();
+
+    // Cancelamento pedido e o worker morre antes de ver a marca
+    dequeue_job(&queue, &worker_id, job.id).await;
+    queue.update_status(job.tenant_id, &job_id, JobStatus::Running).await.unwrap();
+    assert_eq!(queue.cancel(job.tenant_id, &job_id).await.unwrap(), Cancellation::Requested);
+    assert!(queue.reap_worker(&worker_id, 1).await.unwrap()[0].dead);
+
+    queue.replay_dead_letter(job.tenant_id, &job_id).await.unwrap();
+    let status = queue.get_status(job.tenant_id, &job_id).await.unwrap();
+    assert_eq!(status.status, JobStatus::Pending);
+    assert!(status.next_retry_at.is_none());
+
+    let next = worker();
+    dequeue_job(&queue, &next, job.id).await;
+    assert!(queue.cancel_requests(&next).await.unwrap().is_empty());
+    queue.ack(&next, job.tenant_id, &job_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn retried_jobs_wait_in_the_delayed_set_until_due() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+
+    let job = job(6);
+    queue.enqueue(job.clone()).await.unwrap();
+
+    let mut running = dequeue_job(&queue, &worker_id, job.id).await;
+    let error = running.record_error("navigation timeout");
+    queue.record_error(job.tenant_id, &job.id.to_string(), &error).await.unwrap();
+    queue.retry_later(&worker_id, job.tenant_id, &job.id.to_string(), Duration::from_secs(3600)).await.unwrap();
+
+    // Fora da lista de processamento: o reaper não o devolve antes da hora
+    assert!(queue.reap_worker(&worker_id, 3).await.unwrap().is_empty());
+
+    let status = queue.get_status(job.tenant_id, &job.id.to_string()).await.unwrap();
+    assert_eq!(status.status, JobStatus::Pending);
+    assert_eq!(status.attempts, 1);
+    assert_eq!(status.last_error.as_deref(), Some("navigation timeout"));
+    assert!(status.next_retry_at.unwrap() > chrono::Utc::now());
+
+    // Com espera zero a retentativa já venceu e volta para a fila
+    queue.retry_later(&worker_id, job.tenant_id, &job.id.to_string(), Duration::ZERO).await.unwrap();
+    assert!(queue.promote_due().await.unwrap() >= 1);
+
+    let retried = dequeue_job(&queue, &worker_id, job.id).await;
+    assert_eq!(retried.attempts, 2);
+    assert!(queue.get_status(job.tenant_id, &job.id.to_string()).await.unwrap().next_retry_at.is_none());
+    queue.ack(&worker_id, job.tenant_id, &job.id.to_string()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn cancelling_a_pending_job_removes_it_from_the_queue() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    drain(&queue).await;
+
+    let job = job(5);
+    let job_id = job.id.to_string();
+    queue.enqueue(job.clone()).await.unwrap();
+
+    assert_eq!(queue.cancel(job.tenant_id, &job_id).await.unwrap(), Cancellation::Dequeued);
+    assert_eq!(queue.get_status(job.tenant_id, &job_id).await.unwrap().status, JobStatus::Cancelled);
+    assert!(queue.dequeue(&worker(), Duration::ZERO).await.unwrap().is_none());
+
+    // Idempotente; outro tenant não enxerga o job
+    assert_eq!(
+        queue.cancel(job.tenant_id, &job_id).await.unwrap(),
+        Cancellation::Finished(JobStatus::Cancelled)
+    );
+    assert!(queue.cancel(Uuid::new_v4(), &job_id).await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn cancelling_a_running_job_flags_it_for_its_worker() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let worker_id = worker();
+
+    let job = job(5);
+    let job_id = job.id.to_string();
+    queue.enqueue(job.clone()).await.unwrap();
+
+    dequeue_job(&queue, &worker_id, job.id).await;
+    queue.update_status(job.tenant_id, &job_id, JobStatus::Running).await.unwrap();
+    assert!(queue.cancel_requests(&worker_id).await.unwrap().is_empty());
+
+    assert_eq!(queue.cancel(job.tenant_id, &job_id).await.unwrap(), Cancellation::Requested);
+    assert_eq!(queue.cancel_requests(&worker_id).await.unwrap(), vec![job.id]);
+    assert!(queue.cancel_requests(&worker()).await.unwrap().is_empty());
+
+    queue.update_status(job.tenant_id, &job_id, JobStatus::Cancelled).await.unwrap();
+    queue.ack(&worker_id, job.tenant_id, &job_id).await.unwrap();
+    assert!(queue.cancel_requests(&worker_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn browser_pool_metrics_are_listed_for_live_workers() {
+    let _guard = exclusive().await;
+    let queue = queue().await;
+    let (live, gone) = (worker(), worker());
+
+    let metrics = BrowserPoolMetrics {
+        size: 3,
+        in_use: 1,
+        launches: 4,
+        navigations: 57,
+        ..Default::default()
+    };
+
+    queue.heartbeat(&live).await.unwrap();
+    queue.publish_browser_metrics(&live, &metrics, Duration::from_secs(60)).await.unwrap();
+    // Publicou, mas saiu de `queue:workers` (reaper)
+    queue.heartbeat(&gone).await.unwrap();
+    queue.publish_browser_metrics(&gone, &metrics, Duration::from_secs(60)).await.unwrap();
+    queue.reap_worker(&gone, 3).await.unwrap();
+
+    let listed = queue.browser_metrics().await.unwrap();
+    let live_metrics = listed.iter().find(|entry| entry.worker_id == live).expect("live worker is listed");
+    assert_eq!(live_metrics.metrics.size, 3);
+    assert_eq!(live_metrics.metrics.in_use, 1);
+    assert_eq!(live_metrics.metrics.navigations, 57);
+    assert!(listed.iter().all(|entry| entry.worker_id != gone));
+
+    queue.reap_worker(&live, 3).await.unwrap();
+}
@@ -0,0 +1,50 @@
+//! Classificação de erros e backoff da política de retentativas
+
+use arcsat_core::ArcsatError;
+use arcsat_market_intelligence::retry::{RetryDecision, RetryPolicy};
+use std::time::Duration;
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_secs(10),
+        max_delay: Duration::from_secs(60),
+    }
+}
+
+#[test]
+fn transient_errors_are_retried_until_attempts_run_out() {
+    let policy = policy();
+    let error = ArcsatError::Scraping("navigation timeout".to_string());
+
+    assert!(matches!(policy.decide(&error, 1), RetryDecision::Retry(_)));
+    assert!(matches!(policy.decide(&error, 2), RetryDecision::Retry(_)));
+    assert_eq!(policy.decide(&error, 3), RetryDecision::DeadLetter);
+}
+
+#[test]
+fn fatal_errors_are_not_retried() {
+    let policy = policy();
+
+    for error in [
+        ArcsatError::Validation("max_pages must be positive".to_string()),
+        ArcsatError::Config("proxy_url is required".to_string()),
+        ArcsatError::Internal("unexpected state".to_string()),
+        // Layout novo: o seletor não vai aparecer na próxima tentativa
+        ArcsatError::Parse("ready selector \"[data-testid=product-card]\" not found".to_string()),
+    ] {
+        assert_eq!(policy.decide(&error, 1), RetryDecision::Fail);
+    }
+}
+
+#[test]
+fn backoff_doubles_with_jitter_and_is_capped() {
+    let policy = policy();
+
+    for (attempt, full) in [(1, 10), (2, 20), (3, 40), (4, 60), (20, 60)] {
+        let delay = policy.backoff(attempt);
+        let full = Duration::from_secs(full);
+
+        assert!(delay >= full / 2 && delay <= full, "attempt {}: {:?}", attempt, delay);
+    }
+}
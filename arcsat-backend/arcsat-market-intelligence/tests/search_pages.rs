@@ -0,0 +1,113 @@
+//! Fim da busca: paginação de cada marketplace e a página de "nenhum
+//! resultado", que conta como carregada (sem navegador) e sem produtos
+
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::scrapers::{
+    AmazonScraper, AmericanasScraper, MagaluScraper, MarketplaceScraper, MercadoLivreScraper, SearchPage,
+};
+use uuid::Uuid;
+
+fn parse(scraper: &dyn MarketplaceScraper, html: &str) -> SearchPage {
+    let job = ScrapingJob::new(Uuid::new_v4(), scraper.marketplace(), "fone bluetooth".to_string(), 5);
+    scraper.parse_search(&job, html, 1)
+}
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// Página de busca com o `next` da paginação trocado por `last`
+fn last_page(name: &str, next: &str, last: &str) -> String {
+    let html = fixture(name);
+    assert!(html.contains(next), "{} has no {:?}", name, next);
+    html.replace(next, last)
+}
+
+#[test]
+fn pagination_stops_on_the_last_page() {
+    let amazon = AmazonScraper::new().unwrap();
+    assert!(parse(&amazon, &fixture("amazon/search.html")).has_next);
+    let last = last_page(
+        "amazon/search.html",
+        r#"<a href="/s?k=fone+bluetooth&amp;page=2" class="s-pagination-item s-pagination-next s-pagination-button s-pagination-separator">Próximo</a>"#,
+        r#"<span class="s-pagination-item s-pagination-next s-pagination-disabled">Próximo</span>"#,
+    );
+    assert!(!parse(&amazon, &last).has_next);
+
+    let mercado_livre = MercadoLivreScraper::new().unwrap();
+    assert!(parse(&mercado_livre, &fixture("mercado_livre/search.html")).has_next);
+    let last = last_page(
+        "mercado_livre/search.html",
+        "andes-pagination__button andes-pagination__button--next",
+        "andes-pagination__button andes-pagination__button--next andes-pagination__button--disabled",
+    );
+    assert!(!parse(&mercado_livre, &last).has_next);
+
+    let magalu = MagaluScraper::new().unwrap();
+    assert!(parse(&magalu, &fixture("magalu/search.html")).has_next);
+    let last = last_page(
+        "magalu/search.html",
+        r#"<a aria-label="Próxima página" href="/busca/smartphone/?page=2">"#,
+        r#"<a aria-label="Próxima página" aria-disabled="true">"#,
+    );
+    assert!(!parse(&magalu, &last).has_next);
+}
+
+#[test]
+fn no_results_pages_are_ready_with_zero_products() {
+    let pages: [(&dyn MarketplaceScraper, &str); 4] = [
+        (
+            &AmazonScraper::new().unwrap(),
+            r#"<div cel_widget_id="MAIN-TOP_BANNER_MESSAGE-1"><span>Nenhum resultado para </span><span class="a-color-state a-text-bold">"xyzzy"</span></div>"#,
+        ),
+        (
+            &MercadoLivreScraper::new().unwrap(),
+            r#"<div class="ui-search-rescue"><h3 class="ui-search-rescue__title">Não há anúncios que correspondam à sua busca.</h3></div>"#,
+        ),
+        (
+            &MagaluScraper::new().unwrap(),
+            r#"<div data-testid="no-results"><h1>Sua busca por "xyzzy" não encontrou resultado algum</h1></div>"#,
+        ),
+        (
+            &AmericanasScraper::new().unwrap(),
+            r#"<script id="__NEXT_DATA__" type="application/json">{"props":{"pageProps":{"search":{"products":[],"total":0}}}}</script>"#,
+        ),
+    ];
+
+    for (scraper, body) in pages {
+        let page = parse(scraper, &format!("<html><body>{}</body></html>", body));
+
+        assert!(page.ready, "{:?}", scraper.marketplace());
+        assert!(page.stats.no_results, "{:?}", scraper.marketplace());
+        assert!(page.products.is_empty());
+        assert!(!page.has_next, "{:?}", scraper.marketplace());
+    }
+}
+
+#[test]
+fn pages_without_cards_or_a_no_results_message_are_not_ready() {
+    // Resultados montados no cliente: nem cards nem a mensagem de busca vazia
+    let empty = "<html><body><div id=\"root\"></div></body></html>";
+
+    for scraper in [
+        &AmazonScraper::new().unwrap() as &dyn MarketplaceScraper,
+        &MercadoLivreScraper::new().unwrap(),
+        &MagaluScraper::new().unwrap(),
+    ] {
+        let page = parse(scraper, empty);
+
+        assert!(!page.ready, "{:?}", scraper.marketplace());
+        assert!(!page.stats.no_results);
+    }
+}
+
+#[test]
+fn mercado_livre_search_url_encodes_the_query() {
+    let scraper = MercadoLivreScraper::new().unwrap();
+    let job = ScrapingJob::new(Uuid::new_v4(), scraper.marketplace(), "fone 100% c/ cabo".to_string(), 5);
+
+    assert_eq!(
+        scraper.search_url(&job, 2),
+        "https://lista.mercadolivre.com.br/fone-100%25-c%2F-cabo/_Desde_50"
+    );
+}
@@ -0,0 +1,231 @@
+//! Monitor de seletores: estatísticas de extração sobre HTML salvo, veredito
+//! contra a linha de base e, no Postgres, a linha de base, a amostra por
+//! tenant e o alerta de drift.
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... cargo test -p arcsat-market-intelligence --test selector_health -- --ignored
+//! ```
+
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::repository::JobRepository;
+use arcsat_market_intelligence::scrapers::{MagaluScraper, MarketplaceScraper, PageStats, SearchStats};
+use arcsat_market_intelligence::selector_health::{self, SelectorHealthConfig, Verdict};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const SEARCH: &str = include_str!("fixtures/magalu/search.html");
+
+const SEARCH_URL: &str = "https://www.magazineluiza.com.br/busca/smartphone/";
+
+/// Magalu depois de trocar os `data-testid` dos cards: a página carrega, mas
+/// nenhum card casa com os seletores
+const REDESIGNED_SEARCH: &str = r#"<html><body><ul>
+    <li class="sc-product-card"><h2>Smartphone Samsung Galaxy A15</h2><p>R$ 899,00</p></li>
+    <li class="sc-product-card"><h2>Fone de Ouvido JBL Tune 520BT</h2><p>R$ 249,99</p></li>
+</ul></body></html>"#;
+
+fn job() -> ScrapingJob {
+    ScrapingJob::new(Uuid::new_v4(), Marketplace::Magalu, "smartphone".to_string(), 1)
+}
+
+/// Estatísticas de um job de uma página sobre o HTML dado
+fn search_stats(job: &ScrapingJob, html: &str) -> SearchStats {
+    let page = MagaluScraper::new().unwrap().parse_search(job, html, 1);
+
+    let mut stats = SearchStats::default();
+    stats.record(job, 1, &page.stats, SEARCH_URL.to_string(), html.to_string());
+    stats
+}
+
+fn baseline(parse_yield: f64, samples: i32) -> SelectorBaseline {
+    SelectorBaseline {
+        marketplace: Marketplace::Magalu,
+        parse_yield,
+        samples,
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn page_stats_count_cards_and_missing_fields() {
+    let page = MagaluScraper::new().unwrap().parse_search(&job(), SEARCH, 1);
+
+    // O card indisponível não tem preço e não vira produto
+    assert_eq!(
+        page.stats,
+        PageStats {
+            cards: 4,
+            products: 3,
+            missing_title: 0,
+            missing_price: 1,
+            missing_url: 0,
+            missing_id: 0,
+            no_results: false,
+        }
+    );
+
+    let page = MagaluScraper::new().unwrap().parse_search(&job(), REDESIGNED_SEARCH, 1);
+    assert_eq!(page.stats, PageStats::default());
+}
+
+#[test]
+fn products_without_an_id_are_dropped_and_counted() {
+    // Link sem `/p/<id>/`: dois produtos que colidiriam em `(job_id, "")`
+    let card = |title: &str| {
+        format!(
+            r#"<a data-testid="product-card-container" href="/busca/oferta/">
+                <h2 data-testid="product-title">{}</h2>
+                <p data-testid="installment">R$ 99,90 em 2x de R$ 49,95 sem juros</p>
+            </a>"#,
+            title
+        )
+    };
+    let html = format!("<html><body>{}{}</body></html>", card("Fone A"), card("Fone B"));
+
+    let page = MagaluScraper::new().unwrap().parse_search(&job(), &html, 1);
+
+    assert!(page.products.is_empty());
+    assert_eq!((page.stats.cards, page.stats.products, page.stats.missing_id), (2, 0, 2));
+}
+
+#[test]
+fn drift_is_judged_against_the_baseline() {
+    let config = SelectorHealthConfig { drift_ratio: 0.5, min_samples: 3 };
+    let job = job();
+    let healthy = search_stats(&job, SEARCH);
+    let broken = search_stats(&job, REDESIGNED_SEARCH);
+
+    // Linha de base em formação: nada é drift
+    assert_eq!(config.evaluate(None, &broken), Verdict::Learning);
+    assert_eq!(config.evaluate(Some(&baseline(0.75, 2)), &broken), Verdict::Learning);
+
+    assert_eq!(config.evaluate(Some(&baseline(0.75, 3)), &healthy), Verdict::Healthy);
+    assert_eq!(
+        config.evaluate(Some(&baseline(0.75, 3)), &broken),
+        Verdict::Drift { expected: 0.75, actual: 0.0 }
+    );
+
+    let alert = selector_health::drift_alert(&job, &broken, 0.75, 0.0);
+    assert_eq!(alert.alert_type, AlertType::SelectorDrift);
+    assert_eq!(alert.severity, AlertSeverity::Critical);
+    assert_eq!(alert.tenant_id, job.tenant_id);
+}
+
+#[test]
+fn searches_without_results_are_not_drift() {
+    let config = SelectorHealthConfig { drift_ratio: 0.5, min_samples: 3 };
+    let job = job();
+    let empty = search_stats(
+        &job,
+        r#"<html><body><div data-testid="no-results"><h1>Sua busca por "xyzzy" não encontrou resultado algum</h1></div></body></html>"#,
+    );
+    assert!(empty.totals.no_results);
+
+    assert_eq!(config.evaluate(Some(&baseline(0.75, 2)), &empty), Verdict::Learning);
+    assert_eq!(config.evaluate(Some(&baseline(0.75, 3)), &empty), Verdict::Healthy);
+    // Não ensina nada à linha de base
+    assert!(selector_health::updated_baseline(Marketplace::Magalu, Some(baseline(0.75, 3)), &empty).is_none());
+}
+
+#[test]
+fn cards_are_expected_once_the_baseline_counts() {
+    let config = SelectorHealthConfig { drift_ratio: 0.5, min_samples: 3 };
+
+    assert!(!config.expects_cards(None));
+    assert!(!config.expects_cards(Some(&baseline(0.75, 2))));
+    assert!(!config.expects_cards(Some(&baseline(0.0, 3))));
+    assert!(config.expects_cards(Some(&baseline(0.75, 3))));
+}
+
+#[test]
+fn healthy_jobs_move_the_baseline_and_keep_the_sample() {
+    let job = job();
+    let stats = search_stats(&job, SEARCH);
+
+    let first = selector_health::updated_baseline(Marketplace::Magalu, None, &stats).unwrap();
+    assert_eq!(first.samples, 1);
+    assert_eq!(first.parse_yield, 0.75);
+
+    // A amostra é do tenant do job, com a URL da busca dele
+    let sample = selector_health::sample(&job, &stats).unwrap();
+    assert_eq!(sample.tenant_id, job.tenant_id);
+    assert_eq!(sample.job_id, job.id);
+    assert_eq!(sample.url, SEARCH_URL);
+    assert_eq!(sample.html, SEARCH);
+
+    let next = selector_health::updated_baseline(Marketplace::Magalu, Some(baseline(1.0, 4)), &stats).unwrap();
+    assert_eq!(next.samples, 5);
+    assert!((next.parse_yield - 0.95).abs() < 1e-9);
+
+    // Sem produtos não há o que aprender
+    let broken = search_stats(&job, REDESIGNED_SEARCH);
+    assert!(selector_health::updated_baseline(Marketplace::Magalu, None, &broken).is_none());
+}
+
+async fn repository() -> JobRepository {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPool::connect(&url).await.expect("connect to Postgres");
+
+    sqlx::migrate!("../migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    JobRepository::new(pool)
+}
+
+#[tokio::test]
+#[ignore = "requires Postgres (DATABASE_URL)"]
+async fn layout_change_fires_one_drift_alert_per_tenant() {
+    let repository = repository().await;
+    let config = SelectorHealthConfig { drift_ratio: 0.5, min_samples: 3 };
+
+    sqlx::query("DELETE FROM selector_health WHERE marketplace = $1")
+        .bind(Marketplace::Magalu.as_str())
+        .execute(repository.pool())
+        .await
+        .unwrap();
+
+    // Jobs saudáveis formam a linha de base
+    for expected in [Verdict::Learning, Verdict::Learning, Verdict::Learning, Verdict::Healthy] {
+        let job = job();
+        let verdict = selector_health::check(&repository, &config, &job, &search_stats(&job, SEARCH)).await.unwrap();
+        assert_eq!(verdict, expected);
+    }
+
+    let learned = repository.get_selector_baseline(Marketplace::Magalu).await.unwrap().unwrap();
+    assert_eq!(learned.samples, 4);
+
+    let healthy_job = job();
+    selector_health::check(&repository, &config, &healthy_job, &search_stats(&healthy_job, SEARCH)).await.unwrap();
+    let learned = repository.get_selector_baseline(Marketplace::Magalu).await.unwrap().unwrap();
+
+    // O layout muda: dois jobs vazios do mesmo tenant, um alerta
+    let broken_job = job();
+    for _ in 0..2 {
+        let verdict = selector_health::check(&repository, &config, &broken_job, &search_stats(&broken_job, REDESIGNED_SEARCH))
+            .await
+            .unwrap();
+        assert!(matches!(verdict, Verdict::Drift { actual, .. } if actual == 0.0));
+    }
+
+    let alerts = repository
+        .list_alerts(broken_job.tenant_id, Some(AlertType::SelectorDrift), 10)
+        .await
+        .unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].marketplace, Marketplace::Magalu);
+    assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+    assert!(alerts[0].message.contains("0 produtos de 0 cards"), "{}", alerts[0].message);
+
+    // O drift não entra na média nem vira amostra
+    let after = repository.get_selector_baseline(Marketplace::Magalu).await.unwrap().unwrap();
+    assert_eq!(after.samples, learned.samples);
+    assert_eq!(after.parse_yield, learned.parse_yield);
+
+    let sample = repository.get_selector_sample(healthy_job.tenant_id, Marketplace::Magalu).await.unwrap().unwrap();
+    assert_eq!(sample.job_id, healthy_job.id);
+    assert_eq!(sample.html, SEARCH);
+    assert!(repository.get_selector_sample(broken_job.tenant_id, Marketplace::Magalu).await.unwrap().is_none());
+}
@@ -0,0 +1,285 @@
+//! Isolamento entre tenants: filtros e RLS no Postgres, namespace no Redis e
+//! 404 na API.
+//!
+//! Dependem de serviços externos, por isso ficam `#[ignore]`:
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... REDIS_URL=redis://... \
+//!     cargo test -p arcsat-market-intelligence --test tenant_isolation -- --ignored
+//! ```
+//!
+//! O teste de RLS precisa de um role sem `BYPASSRLS` (não superusuário).
+
+use arcsat_core::auth::{Claims, JwtAuth};
+use arcsat_core::Money;
+use arcsat_market_intelligence::models::*;
+use arcsat_market_intelligence::queue::JobQueue;
+use arcsat_market_intelligence::repository::JobRepository;
+use arcsat_market_intelligence::MarketIntelligenceEngine;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use rust_decimal_macros::dec;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const JWT_SECRET: &[u8] = b"tenant-isolation-test";
+
+async fn pool() -> PgPool {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPool::connect(&url).await.expect("connect to Postgres");
+
+    sqlx::migrate!("../migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    pool
+}
+
+fn redis_url() -> String {
+    std::env::var("REDIS_URL").expect("REDIS_URL must be set")
+}
+
+fn job(tenant_id: Uuid) -> ScrapingJob {
+    ScrapingJob::new(tenant_id, Marketplace::MercadoLivre, "notebook".to_string(), 1)
+}
+
+fn product(job_id: Uuid) -> ScrapedProduct {
+    ScrapedProduct {
+        id: Uuid::new_v4(),
+        job_id,
+        marketplace: Marketplace::MercadoLivre,
+        external_id: "MLB123".to_string(),
+        title: "Notebook".to_string(),
+        price: Money::brl(dec!(2999.90)),
+        url: "https://www.mercadolivre.com.br/p/MLB123".to_string(),
+        image_url: None,
+        pricing: Pricing::default(),
+        seller_name: "Loja".to_string(),
+        seller_id: None,
+        seller_rating: None,
+        sales_rank: None,
+        rating: None,
+        num_reviews: 0,
+        availability: true,
+        category: None,
+        brand: None,
+        scraped_at: chrono::Utc::now(),
+        extra: serde_json::json!({}),
+    }
+}
+
+fn token(tenant_id: Uuid) -> String {
+    let claims = Claims {
+        sub: Uuid::new_v4(),
+        tenant_id,
+        exp: chrono::Utc::now().timestamp() + 300,
+        iat: None,
+        roles: Vec::new(),
+    };
+
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(JWT_SECRET),
+    )
+    .unwrap()
+}
+
+/// Conta as linhas visíveis com `app.tenant_id` definido (ou não) na transação
+async fn visible_rows(pool: &PgPool, tenant_id: Option<Uuid>, sql: &str, id: Uuid) -> i64 {
+    let mut tx = pool.begin().await.unwrap();
+
+    if let Some(tenant_id) = tenant_id {
+        sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+            .bind(tenant_id.to_string())
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+    }
+
+    sqlx::query_scalar(sql).bind(id).fetch_one(&mut *tx).await.unwrap()
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn repository_does_not_return_other_tenants_jobs() {
+    let repository = JobRepository::new(pool().await);
+    let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let job = job(tenant_a);
+    repository.save_job(&job).await.unwrap();
+
+    assert_eq!(
+        repository.get_status(tenant_a, job.id).await.unwrap().map(|s| s.status),
+        Some(JobStatus::Pending)
+    );
+    assert!(repository.get_status(tenant_b, job.id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL)"]
+async fn row_level_security_hides_other_tenants_rows() {
+    let pool = pool().await;
+
+    let bypasses_rls: bool =
+        sqlx::query_scalar("SELECT rolsuper OR rolbypassrls FROM pg_roles WHERE rolname = current_user")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    if bypasses_rls {
+        eprintln!("skipping: current role bypasses row-level security");
+        return;
+    }
+
+    let repository = JobRepository::new(pool.clone());
+    let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let job = job(tenant_a);
+    repository.save_job(&job).await.unwrap();
+    repository.save_products(tenant_a, &[product(job.id)]).await.unwrap();
+
+    // Sem filtro de tenant na query: só as policies separam os tenants
+    let jobs = "SELECT COUNT(*) FROM scraping_jobs WHERE id = $1";
+    let products = "SELECT COUNT(*) FROM scraped_products WHERE job_id = $1";
+
+    assert_eq!(visible_rows(&pool, Some(tenant_a), jobs, job.id).await, 1);
+    assert_eq!(visible_rows(&pool, Some(tenant_a), products, job.id).await, 1);
+    assert_eq!(visible_rows(&pool, Some(tenant_b), jobs, job.id).await, 0);
+    assert_eq!(visible_rows(&pool, Some(tenant_b), products, job.id).await, 0);
+    assert_eq!(visible_rows(&pool, None, jobs, job.id).await, 0);
+
+    let sample = SelectorSample {
+        tenant_id: tenant_a,
+        marketplace: Marketplace::MercadoLivre,
+        job_id: job.id,
+        url: "https://lista.mercadolivre.com.br/notebook".to_string(),
+        html: "<html></html>".to_string(),
+        captured_at: chrono::Utc::now(),
+    };
+    repository.save_selector_sample(&sample).await.unwrap();
+
+    let samples = "SELECT COUNT(*) FROM selector_samples WHERE job_id = $1";
+    assert_eq!(visible_rows(&pool, Some(tenant_a), samples, job.id).await, 1);
+    assert_eq!(visible_rows(&pool, Some(tenant_b), samples, job.id).await, 0);
+
+    // Produto de um tenant não pode apontar para o job de outro
+    let mut foreign = product(job.id);
+    foreign.external_id = "MLB456".to_string();
+    let result = repository.save_products(tenant_b, &[foreign]).await;
+    assert!(result.is_err(), "product for another tenant's job must be rejected");
+}
+
+#[tokio::test]
+#[ignore = "requires Redis (REDIS_URL)"]
+async fn queue_status_is_namespaced_by_tenant() {
+    let queue = JobQueue::new(&redis_url()).await.unwrap();
+    let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let job = job(tenant_a);
+    let job_id = queue.enqueue(job).await.unwrap();
+    queue.update_status(tenant_a, &job_id, JobStatus::Running).await.unwrap();
+
+    assert_eq!(queue.get_status(tenant_a, &job_id).await.unwrap().status, JobStatus::Running);
+    assert!(matches!(
+        queue.get_status(tenant_b, &job_id).await,
+        Err(arcsat_core::ArcsatError::JobNotFound(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL) and Redis (REDIS_URL)"]
+async fn api_returns_404_for_other_tenants_jobs() {
+    let engine = Arc::new(
+        MarketIntelligenceEngine::new(&redis_url(), pool().await, None)
+            .await
+            .unwrap(),
+    );
+    let app = arcsat_market_intelligence::router(engine.clone(), JwtAuth::hs256(JWT_SECRET));
+    let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let job = job(tenant_a);
+    let job_id = engine.submit_job(job).await.unwrap();
+
+    let status_of = |tenant_id: Uuid| {
+        let app = app.clone();
+        let request = Request::get(format!("/api/v1/market-intelligence/jobs/{}/status", job_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token(tenant_id)))
+            .body(Body::empty())
+            .unwrap();
+
+        async move {
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+        }
+    };
+
+    let (status, _) = status_of(tenant_a).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = status_of(tenant_b).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "MI_JOB_NOT_FOUND");
+}
+
+#[tokio::test]
+#[ignore = "requires PostgreSQL (DATABASE_URL) and Redis (REDIS_URL)"]
+async fn selector_sample_is_served_only_to_its_tenant_as_plain_text() {
+    let engine = Arc::new(
+        MarketIntelligenceEngine::new(&redis_url(), pool().await, None)
+            .await
+            .unwrap(),
+    );
+    let app = arcsat_market_intelligence::router(engine.clone(), JwtAuth::hs256(JWT_SECRET));
+    let (tenant_a, tenant_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let html = "<html><script>alert(document.cookie)</script></html>";
+    engine
+        .repository
+        .save_selector_sample(&SelectorSample {
+            tenant_id: tenant_a,
+            marketplace: Marketplace::Shopee,
+            job_id: Uuid::new_v4(),
+            url: "https://shopee.com.br/search?keyword=termo-do-tenant".to_string(),
+            html: html.to_string(),
+            captured_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let get = |path: &str, tenant_id: Uuid| {
+        let app = app.clone();
+        let request = Request::get(path)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token(tenant_id)))
+            .body(Body::empty())
+            .unwrap();
+
+        async move { app.oneshot(request).await.unwrap() }
+    };
+
+    let sample_path = "/api/v1/market-intelligence/selector-health/shopee/sample";
+
+    let response = get(sample_path, tenant_a).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers[header::CONTENT_TYPE], "text/plain; charset=utf-8");
+    assert_eq!(headers[header::CONTENT_DISPOSITION], "attachment; filename=\"shopee-sample.html\"");
+    assert_eq!(headers[header::CONTENT_SECURITY_POLICY], "sandbox");
+    assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, html.as_bytes());
+
+    assert_eq!(get(sample_path, tenant_b).await.status(), StatusCode::NOT_FOUND);
+
+    // A linha de base global não carrega nada do tenant
+    let response = get("/api/v1/market-intelligence/selector-health", tenant_b).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(!body.contains("termo-do-tenant"), "{}", body);
+    assert!(!body.contains("sample_url"), "{}", body);
+}
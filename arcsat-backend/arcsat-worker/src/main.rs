@@ -3,71 +3,118 @@
 //! Worker que processa jobs de scraping em background
 
 use arcsat_market_intelligence::{MarketIntelligenceEngine, models::*};
-use std::sync::Arc;
+use arcsat_market_intelligence::retry::{RetryDecision, RetryPolicy};
+use arcsat_market_intelligence::proxy::ProxyPool;
+use arcsat_market_intelligence::scrapers::{BrowserPool, BrowserPoolConfig, DetailLimits, SearchResult};
+use arcsat_market_intelligence::selector_health::SelectorHealthConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
 use tracing::{info, error, warn};
 use signal_hook::consts::signal::*;
 use signal_hook_tokio::Signals;
 use futures::stream::StreamExt;
+use sqlx::postgres::PgPoolOptions;
+
+/// Espera máxima por um job quando todas as filas estão vazias
+const IDLE_WAIT: Duration = Duration::from_secs(2);
+
+/// Intervalo de checagem das retentativas agendadas
+const RETRY_POLL: Duration = Duration::from_secs(1);
+
+/// Intervalo de checagem dos cancelamentos pedidos pela API
+const CANCEL_POLL: Duration = Duration::from_secs(1);
+
+/// Tokens de cancelamento dos jobs em execução neste worker
+type Cancellations = Arc<Mutex<HashMap<uuid::Uuid, CancellationToken>>>;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Load .env
-    dotenv::dotenv().ok();
+    // Config (TOML + .env + env), compartilhada com a API
+    let config = arcsat_core::config::Config::load()?;
 
     // Setup logging
     tracing_subscriber::fmt()
         .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "arcsat_worker=debug,arcsat_market_intelligence=debug".into())
+            config
+                .var("RUST_LOG")
+                .and_then(|filter| tracing_subscriber::EnvFilter::try_new(filter).ok())
+                .unwrap_or_else(|| "arcsat_worker=debug,arcsat_market_intelligence=debug".into())
         )
         .json()
         .init();
 
     info!("🔄 Starting Arcsat Worker");
+    config.log_sources();
 
-    // Config
-    let redis_url = std::env::var("REDIS_URL")
-        .unwrap_or_else(|_| "redis://localhost:6379".to_string());
-
-    let proxy_enabled = std::env::var("MI_PROXY_ENABLED")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse::<bool>()
-        .unwrap_or(false);
+    let mi_config = &config.market_intelligence;
+    let proxy_enabled = mi_config.proxy_enabled;
 
-    let proxy_config = if proxy_enabled {
-        std::env::var("MI_PROXY_URL").ok().map(|url| {
-            arcsat_market_intelligence::proxy::ProxyConfig::new(url)
-        })
-    } else {
-        None
+    // Um proxy ou uma lista: cada navegador do pool recebe um em rodízio e as
+    // requisições HTTP usam o primeiro
+    let proxies = match &mi_config.proxy_url {
+        Some(urls) if proxy_enabled => ProxyPool::from_urls(urls),
+        _ => ProxyPool::new(Vec::new()),
     };
+    let proxy_config = proxies.first().cloned();
+    let proxy_count = proxies.count();
+    let browser_pool = BrowserPool::new(BrowserPoolConfig::from_config(mi_config), proxies);
 
-    let max_concurrent = std::env::var("MI_MAX_CONCURRENT_JOBS")
-        .unwrap_or_else(|_| "5".to_string())
-        .parse::<usize>()
-        .unwrap_or(5);
+    let max_concurrent = mi_config.max_concurrent_jobs;
+    let max_attempts = mi_config.max_attempts;
+    let visibility_timeout = Duration::from_secs(mi_config.visibility_timeout_secs);
+    let retry_policy = RetryPolicy::from_config(mi_config);
 
-    // Initialize engine
-    let engine = Arc::new(
-        MarketIntelligenceEngine::new(&redis_url, proxy_config).await?
-    );
+    // Postgres: destino final de jobs e resultados
+    let pool = PgPoolOptions::new()
+        .max_connections(config.database.max_connections)
+        .connect(&config.database.url)
+        .await?;
 
-    info!("✅ Worker initialized (max concurrent: {})", max_concurrent);
+    // Initialize engine
+    let mut engine = MarketIntelligenceEngine::new(&config.redis.url, pool, proxy_config).await?;
+    engine.scrapers.set_detail_limits(DetailLimits::from_config(mi_config));
+    engine.scrapers.set_browser_pool(browser_pool);
+    engine.selector_health = SelectorHealthConfig::from_config(mi_config);
+    engine.queue.set_job_timeout(Duration::from_secs(mi_config.job_timeout_secs));
+    let engine = Arc::new(engine);
+
+    // Identifica a lista de processamento e o heartbeat deste processo
+    let worker_id = format!("worker-{}", uuid::Uuid::new_v4());
+
+    info!("✅ Worker {} initialized (max concurrent: {})", worker_id, max_concurrent);
+    info!("🌐 Browser pool: {} browsers", mi_config.browser_pool_size);
     if proxy_enabled {
-        info!("🎭 Proxy enabled");
+        info!("🎭 Proxy enabled ({} proxies)", proxy_count);
     } else {
         warn!("⚠️  Running WITHOUT proxy - limited to ~10-20 requests");
     }
 
     // Signal handling
-    let signals = Signals::new(&[SIGTERM, SIGINT])?;
+    let signals = Signals::new([SIGTERM, SIGINT])?;
     let handle = signals.handle();
 
     let signals_task = tokio::spawn(handle_signals(signals));
 
+    // Heartbeat antes do primeiro dequeue: sem ele o reaper não enxerga os
+    // jobs deste worker
+    engine.queue.heartbeat(&worker_id).await?;
+    tokio::spawn(heartbeat_loop(engine.clone(), worker_id.clone(), visibility_timeout / 3));
+    tokio::spawn(reaper_loop(engine.clone(), visibility_timeout, max_attempts));
+    tokio::spawn(retry_loop(engine.clone()));
+
+    let cancellations = Cancellations::default();
+    tokio::spawn(cancel_loop(engine.clone(), worker_id.clone(), cancellations.clone()));
+
     // Main worker loop
-    let worker_handle = tokio::spawn(worker_loop(engine.clone(), max_concurrent));
+    let worker_handle = tokio::spawn(worker_loop(
+        engine.clone(),
+        worker_id.clone(),
+        max_concurrent,
+        retry_policy,
+        cancellations,
+    ));
 
     // Wait for either signals or worker to finish
     tokio::select! {
@@ -81,6 +128,13 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Jobs em andamento voltam para a fila sem esperar o visibility timeout
+    match engine.release_worker(&worker_id).await {
+        Ok(0) => {}
+        Ok(released) => info!("↩️  Released {} in-flight jobs", released),
+        Err(e) => error!("❌ Failed to release in-flight jobs: {}", e),
+    }
+
     info!("👋 Worker shutting down gracefully");
     handle.close();
 
@@ -99,7 +153,87 @@ async fn handle_signals(mut signals: Signals) {
     }
 }
 
-async fn worker_loop(engine: Arc<MarketIntelligenceEngine>, max_concurrent: usize) {
+async fn heartbeat_loop(engine: Arc<MarketIntelligenceEngine>, worker_id: String, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = engine.queue.heartbeat(&worker_id).await {
+            error!("❌ Failed to send heartbeat: {}", e);
+        }
+
+        // Métricas do pool expiram junto com o heartbeat (visibility timeout)
+        let metrics = engine.scrapers.browser_pool().metrics();
+        if let Err(e) = engine.queue.publish_browser_metrics(&worker_id, &metrics, every * 3).await {
+            warn!("⚠️  Failed to publish browser pool metrics: {}", e);
+        }
+    }
+}
+
+/// Devolve à fila os jobs de workers que pararam de mandar heartbeat e os
+/// que passaram do prazo
+async fn reaper_loop(engine: Arc<MarketIntelligenceEngine>, visibility_timeout: Duration, max_attempts: u32) {
+    let mut interval = tokio::time::interval(visibility_timeout / 2);
+
+    loop {
+        interval.tick().await;
+
+        match engine.reap_stalled(visibility_timeout, max_attempts).await {
+            Ok(0) => {}
+            Ok(count) => info!("♻️  Recovered {} jobs from stalled workers or past their deadline", count),
+            Err(e) => error!("❌ Reaper failed: {}", e),
+        }
+    }
+}
+
+/// Devolve às filas os jobs cuja retentativa venceu
+async fn retry_loop(engine: Arc<MarketIntelligenceEngine>) {
+    let mut interval = tokio::time::interval(RETRY_POLL);
+
+    loop {
+        interval.tick().await;
+
+        match engine.queue.promote_due().await {
+            Ok(0) => {}
+            Ok(count) => info!("🔁 {} jobs back in the queue for retry", count),
+            Err(e) => error!("❌ Failed to promote delayed jobs: {}", e),
+        }
+    }
+}
+
+/// Aciona o token dos jobs deste worker cancelados pela API
+async fn cancel_loop(engine: Arc<MarketIntelligenceEngine>, worker_id: String, cancellations: Cancellations) {
+    let mut interval = tokio::time::interval(CANCEL_POLL);
+
+    loop {
+        interval.tick().await;
+
+        let requested = match engine.queue.cancel_requests(&worker_id).await {
+            Ok(requested) => requested,
+            Err(e) => {
+                error!("❌ Failed to check cancellations: {}", e);
+                continue;
+            }
+        };
+
+        let cancellations = cancellations.lock().unwrap();
+        for job_id in requested {
+            if let Some(token) = cancellations.get(&job_id).filter(|token| !token.is_cancelled()) {
+                info!("🛑 Cancelling job {}", job_id);
+                token.cancel();
+            }
+        }
+    }
+}
+
+async fn worker_loop(
+    engine: Arc<MarketIntelligenceEngine>,
+    worker_id: String,
+    max_concurrent: usize,
+    retry_policy: RetryPolicy,
+    cancellations: Cancellations,
+) {
     let mut active_tasks = Vec::new();
 
     loop {
@@ -108,33 +242,33 @@ async fn worker_loop(engine: Arc<MarketIntelligenceEngine>, max_concurrent: usiz
 
         // Se temos espaço, buscar novos jobs
         if active_tasks.len() < max_concurrent {
-            // Tentar buscar job de cada prioridade (10 até 1)
-            let mut job_found = false;
-
-            for priority in (1..=10).rev() {
-                match engine.queue.dequeue(priority).await {
-                    Ok(Some(job)) => {
-                        info!("📥 Dequeued job {} (priority {})", job.id, job.priority);
-
-                        let engine_clone = engine.clone();
-                        let task = tokio::spawn(async move {
-                            process_job(engine_clone, job).await;
-                        });
-
-                        active_tasks.push(task);
-                        job_found = true;
-                        break;
-                    }
-                    Ok(None) => continue,
-                    Err(e) => {
-                        error!("❌ Error dequeuing from priority {}: {}", priority, e);
-                    }
+            // Job da maior prioridade disponível; com as filas vazias espera
+            // bloqueado no Redis até IDLE_WAIT no lugar de um sleep
+            match engine.queue.dequeue(&worker_id, IDLE_WAIT).await {
+                Ok(Some(job)) => {
+                    info!("📥 Dequeued job {} (priority {}, attempt {})",
+                        job.id, job.priority, job.attempts);
+
+                    let cancel = CancellationToken::new();
+                    cancellations.lock().unwrap().insert(job.id, cancel.clone());
+
+                    let engine_clone = engine.clone();
+                    let worker_id = worker_id.clone();
+                    let retry_policy = retry_policy.clone();
+                    let cancellations = cancellations.clone();
+                    let task = tokio::spawn(async move {
+                        let job_id = job.id;
+                        process_job(engine_clone, &worker_id, &retry_policy, job, cancel).await;
+                        cancellations.lock().unwrap().remove(&job_id);
+                    });
+
+                    active_tasks.push(task);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!("❌ Error dequeuing: {}", e);
+                    sleep(IDLE_WAIT).await;
                 }
-            }
-
-            if !job_found {
-                // Sem jobs, aguardar um pouco
-                sleep(Duration::from_secs(2)).await;
             }
         } else {
             // Pool cheio, aguardar
@@ -143,7 +277,13 @@ async fn worker_loop(engine: Arc<MarketIntelligenceEngine>, max_concurrent: usiz
     }
 }
 
-async fn process_job(engine: Arc<MarketIntelligenceEngine>, mut job: ScrapingJob) {
+async fn process_job(
+    engine: Arc<MarketIntelligenceEngine>,
+    worker_id: &str,
+    retry_policy: &RetryPolicy,
+    mut job: ScrapingJob,
+    cancel: CancellationToken,
+) {
     info!("🚀 Processing job {}: {} on {:?}",
         job.id, job.search_query, job.marketplace);
 
@@ -151,54 +291,143 @@ async fn process_job(engine: Arc<MarketIntelligenceEngine>, mut job: ScrapingJob
     job.status = JobStatus::Running;
     job.started_at = Some(chrono::Utc::now());
 
-    if let Err(e) = engine.queue.update_status(&job.id.to_string(), JobStatus::Running).await {
+    if let Err(e) = engine.queue.update_status(job.tenant_id, &job.id.to_string(), JobStatus::Running).await {
         error!("❌ Failed to update job status: {}", e);
     }
+    if let Err(e) = engine.repository.save_job(&job).await {
+        error!("❌ Failed to persist job {}: {}", job.id, e);
+    }
 
-    // Execute scraping
-    match engine.scrapers.scrape(&job).await {
-        Ok(products) => {
-            info!("✅ Job {} completed: {} products found", job.id, products.len());
-
-            // Save results
-            if let Err(e) = engine.queue.save_results(&job.id.to_string(), &products).await {
-                error!("❌ Failed to save results: {}", e);
-            }
-
-            // Update status
-            job.status = JobStatus::Completed;
-            job.completed_at = Some(chrono::Utc::now());
+    // Execute scraping; estourar o prazo é falha transitória. O reaper só
+    // recupera o job se nem o timeout conseguir interrompê-lo.
+    let job_timeout = engine.queue.job_timeout();
+    let scraped = tokio::time::timeout(job_timeout, engine.scrape_search(&job, &cancel))
+        .await
+        .unwrap_or_else(|_| {
+            Err(arcsat_core::ArcsatError::Scraping(format!(
+                "job exceeded its {}s timeout",
+                job_timeout.as_secs()
+            )))
+        });
+
+    let SearchResult { products, stats } = match scraped {
+        Ok(result) => result,
+        Err(e) if cancel.is_cancelled() => {
+            // Cancelado: a falha não gera retentativa
+            warn!("🛑 Job {} cancelled ({})", job.id, e);
+            job.status = JobStatus::Cancelled;
+            finish_job(&engine, worker_id, &mut job).await;
+            return;
+        }
+        Err(e) => {
+            error!("❌ Job {} failed: {}", job.id, e);
+            handle_failure(&engine, worker_id, retry_policy, &mut job, e).await;
+            return;
+        }
+    };
 
-            if let Err(e) = engine.queue.update_status(&job.id.to_string(), JobStatus::Completed).await {
-                error!("❌ Failed to update status: {}", e);
-            }
+    let cancelled = cancel.is_cancelled();
+    if cancelled {
+        info!("🛑 Job {} cancelled: keeping {} products found so far", job.id, products.len());
+    } else {
+        info!("✅ Job {} completed: {} products found", job.id, products.len());
+    }
 
-            // Analyze trends
-            if !products.is_empty() {
-                let analysis = engine.analysis.analyze(
-                    job.tenant_id,
-                    job.marketplace,
-                    job.category.as_deref().unwrap_or("general"),
-                    &products
-                );
-
-                info!("📊 Analysis complete: avg_price={:.2}, competition={:?}",
-                    analysis.avg_price, analysis.competition_level);
-            }
+    // Save results (Postgres; Redis guarda só a contagem)
+    let saved = match engine.repository.save_products(job.tenant_id, &products).await {
+        Ok(saved) => saved,
+        Err(e) if cancelled => {
+            error!("❌ Failed to save partial results: {}", e);
+            job.status = JobStatus::Cancelled;
+            finish_job(&engine, worker_id, &mut job).await;
+            return;
         }
         Err(e) => {
-            error!("❌ Job {} failed: {}", job.id, e);
+            error!("❌ Failed to save results: {}", e);
+            handle_failure(&engine, worker_id, retry_policy, &mut job, e).await;
+            return;
+        }
+    };
 
-            job.status = JobStatus::Failed;
-            job.completed_at = Some(chrono::Utc::now());
-            job.error = Some(e.to_string());
+    if let Err(e) = engine.queue.save_results(job.tenant_id, &job.id.to_string(), saved).await {
+        error!("❌ Failed to save results count: {}", e);
+    }
 
-            if let Err(e) = engine.queue.update_status(&job.id.to_string(), JobStatus::Failed).await {
-                error!("❌ Failed to update status: {}", e);
+    // Resultado parcial não entra na análise de tendências
+    if cancelled {
+        job.status = JobStatus::Cancelled;
+        finish_job(&engine, worker_id, &mut job).await;
+        return;
+    }
+
+    job.status = JobStatus::Completed;
+    finish_job(&engine, worker_id, &mut job).await;
+
+    // Job vazio ou com extração abaixo do normal vira alerta de drift
+    if let Err(e) = engine.check_selector_health(&job, &stats).await {
+        error!("❌ Failed to check selector health: {}", e);
+    }
+
+    // Analyze trends (preços convertidos para a moeda base do tenant)
+    match engine.analyze_products(&job, &products).await {
+        Ok(Some(analysis)) => {
+            info!("📊 Analysis complete: avg_price={}, competition={:?}",
+                analysis.avg_price, analysis.competition_level);
+
+            if let Err(e) = engine.repository.save_analysis(&analysis).await {
+                error!("❌ Failed to persist analysis: {}", e);
             }
         }
+        Ok(None) => {}
+        Err(e) => error!("❌ Failed to analyze job {}: {}", job.id, e),
     }
+}
 
-    let duration = job.completed_at.unwrap() - job.started_at.unwrap();
-    info!("⏱️  Job {} finished in {} seconds", job.id, duration.num_seconds());
+/// Grava o status final (`job.status`) e tira o job da lista de processamento
+async fn finish_job(engine: &MarketIntelligenceEngine, worker_id: &str, job: &mut ScrapingJob) {
+    job.completed_at = Some(chrono::Utc::now());
+
+    if let Err(e) = engine.repository.save_job(job).await {
+        error!("❌ Failed to persist job {}: {}", job.id, e);
+    }
+    if let Err(e) = engine.queue.update_status(job.tenant_id, &job.id.to_string(), job.status).await {
+        error!("❌ Failed to update status: {}", e);
+    }
+
+    // Fora da lista de processamento: o reaper não devolve mais este job
+    if let Err(e) = engine.queue.ack(worker_id, job.tenant_id, &job.id.to_string()).await {
+        error!("❌ Failed to ack job {}: {}", job.id, e);
+    }
+
+    if let Some(started_at) = job.started_at {
+        let duration = chrono::Utc::now() - started_at;
+        info!("⏱️  Job {} finished in {} seconds", job.id, duration.num_seconds());
+    }
+}
+
+/// Retentativa, dead-letter queue ou falha definitiva, conforme o erro e as
+/// tentativas restantes
+async fn handle_failure(
+    engine: &MarketIntelligenceEngine,
+    worker_id: &str,
+    retry_policy: &RetryPolicy,
+    job: &mut ScrapingJob,
+    error: arcsat_core::ArcsatError,
+) {
+    match engine.fail_attempt(worker_id, job, &error, retry_policy).await {
+        Ok(RetryDecision::Retry(delay)) => {
+            warn!("🔁 Job {} will retry in {}s (attempt {}/{})",
+                job.id, delay.as_secs(), job.attempts, retry_policy.max_attempts);
+        }
+        Ok(RetryDecision::DeadLetter) => {
+            error!("💀 Job {} moved to the dead-letter queue after {} attempts", job.id, job.attempts);
+        }
+        Ok(RetryDecision::Fail) => {
+            error!("⛔ Job {} failed with a non-retryable error", job.id);
+        }
+        Err(e) => {
+            // O job continua na lista de processamento; o reaper o recupera
+            error!("❌ Failed to handle failure of job {}: {}", job.id, e);
+        }
+    }
 }
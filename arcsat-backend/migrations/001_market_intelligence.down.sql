@@ -0,0 +1,10 @@
+-- Reverte 001_market_intelligence.up.sql
+
+DROP VIEW IF EXISTS category_pricing;
+DROP VIEW IF EXISTS recent_jobs;
+
+DROP TABLE IF EXISTS market_alerts;
+DROP TABLE IF EXISTS proxy_configs;
+DROP TABLE IF EXISTS trend_analyses;
+DROP TABLE IF EXISTS scraped_products;
+DROP TABLE IF EXISTS scraping_jobs;
@@ -14,13 +14,13 @@ CREATE TABLE IF NOT EXISTS scraping_jobs (
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
     started_at TIMESTAMP WITH TIME ZONE,
     completed_at TIMESTAMP WITH TIME ZONE,
-    error TEXT,
-
-    INDEX idx_tenant_id (tenant_id),
-    INDEX idx_status (status),
-    INDEX idx_created_at (created_at)
+    error TEXT
 );
 
+CREATE INDEX IF NOT EXISTS idx_scraping_jobs_tenant_id ON scraping_jobs (tenant_id);
+CREATE INDEX IF NOT EXISTS idx_scraping_jobs_status ON scraping_jobs (status);
+CREATE INDEX IF NOT EXISTS idx_scraping_jobs_created_at ON scraping_jobs (created_at);
+
 -- Tabela de produtos coletados
 CREATE TABLE IF NOT EXISTS scraped_products (
     id UUID PRIMARY KEY,
@@ -42,16 +42,16 @@ CREATE TABLE IF NOT EXISTS scraped_products (
     category VARCHAR(255),
     brand VARCHAR(255),
     scraped_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
-    extra JSONB,
-
-    INDEX idx_job_id (job_id),
-    INDEX idx_marketplace (marketplace),
-    INDEX idx_external_id (external_id),
-    INDEX idx_price (price),
-    INDEX idx_scraped_at (scraped_at),
-    UNIQUE INDEX idx_job_external (job_id, external_id)
+    extra JSONB
 );
 
+CREATE INDEX IF NOT EXISTS idx_scraped_products_job_id ON scraped_products (job_id);
+CREATE INDEX IF NOT EXISTS idx_scraped_products_marketplace ON scraped_products (marketplace);
+CREATE INDEX IF NOT EXISTS idx_scraped_products_external_id ON scraped_products (external_id);
+CREATE INDEX IF NOT EXISTS idx_scraped_products_price ON scraped_products (price);
+CREATE INDEX IF NOT EXISTS idx_scraped_products_scraped_at ON scraped_products (scraped_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_scraped_products_job_external ON scraped_products (job_id, external_id);
+
 -- Tabela de análises de tendência
 CREATE TABLE IF NOT EXISTS trend_analyses (
     id UUID PRIMARY KEY,
@@ -69,14 +69,14 @@ CREATE TABLE IF NOT EXISTS trend_analyses (
     trending_keywords JSONB NOT NULL,
     growth_rate DECIMAL(5, 2) NOT NULL DEFAULT 0.0,
     competition_level VARCHAR(20) NOT NULL,
-    analyzed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
-
-    INDEX idx_tenant_id (tenant_id),
-    INDEX idx_marketplace (marketplace),
-    INDEX idx_category (category),
-    INDEX idx_analyzed_at (analyzed_at)
+    analyzed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
 );
 
+CREATE INDEX IF NOT EXISTS idx_trend_analyses_tenant_id ON trend_analyses (tenant_id);
+CREATE INDEX IF NOT EXISTS idx_trend_analyses_marketplace ON trend_analyses (marketplace);
+CREATE INDEX IF NOT EXISTS idx_trend_analyses_category ON trend_analyses (category);
+CREATE INDEX IF NOT EXISTS idx_trend_analyses_analyzed_at ON trend_analyses (analyzed_at);
+
 -- Tabela de configurações de proxy
 CREATE TABLE IF NOT EXISTS proxy_configs (
     id SERIAL PRIMARY KEY,
@@ -89,12 +89,12 @@ CREATE TABLE IF NOT EXISTS proxy_configs (
     enabled BOOLEAN NOT NULL DEFAULT true,
     last_used TIMESTAMP WITH TIME ZONE,
     success_rate DECIMAL(5, 2) DEFAULT 100.0,
-    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
-
-    INDEX idx_tenant_id (tenant_id),
-    INDEX idx_enabled (enabled)
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
 );
 
+CREATE INDEX IF NOT EXISTS idx_proxy_configs_tenant_id ON proxy_configs (tenant_id);
+CREATE INDEX IF NOT EXISTS idx_proxy_configs_enabled ON proxy_configs (enabled);
+
 -- Tabela de alertas de oportunidades
 CREATE TABLE IF NOT EXISTS market_alerts (
     id UUID PRIMARY KEY,
@@ -106,13 +106,13 @@ CREATE TABLE IF NOT EXISTS market_alerts (
     message TEXT NOT NULL,
     severity VARCHAR(20) NOT NULL DEFAULT 'info', -- 'info', 'warning', 'critical'
     read_at TIMESTAMP WITH TIME ZONE,
-    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
-
-    INDEX idx_tenant_id (tenant_id),
-    INDEX idx_created_at (created_at),
-    INDEX idx_read_at (read_at)
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
 );
 
+CREATE INDEX IF NOT EXISTS idx_market_alerts_tenant_id ON market_alerts (tenant_id);
+CREATE INDEX IF NOT EXISTS idx_market_alerts_created_at ON market_alerts (created_at);
+CREATE INDEX IF NOT EXISTS idx_market_alerts_read_at ON market_alerts (read_at);
+
 -- Views úteis
 
 -- View: Jobs recentes por tenant
@@ -0,0 +1,30 @@
+ALTER VIEW category_pricing RESET (security_invoker);
+ALTER VIEW recent_jobs RESET (security_invoker);
+
+DROP POLICY IF EXISTS tenant_isolation ON market_alerts;
+ALTER TABLE market_alerts NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE market_alerts DISABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS tenant_isolation ON proxy_configs;
+ALTER TABLE proxy_configs NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE proxy_configs DISABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS tenant_isolation ON trend_analyses;
+ALTER TABLE trend_analyses NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE trend_analyses DISABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS tenant_isolation ON scraped_products;
+ALTER TABLE scraped_products NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE scraped_products DISABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS tenant_isolation ON scraping_jobs;
+ALTER TABLE scraping_jobs NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE scraping_jobs DISABLE ROW LEVEL SECURITY;
+
+DROP FUNCTION IF EXISTS current_tenant_id();
+
+ALTER TABLE scraped_products DROP CONSTRAINT IF EXISTS scraped_products_job_tenant_fkey;
+ALTER TABLE scraping_jobs DROP CONSTRAINT IF EXISTS scraping_jobs_id_tenant_key;
+
+DROP INDEX IF EXISTS idx_scraped_products_tenant_id;
+ALTER TABLE scraped_products DROP COLUMN IF EXISTS tenant_id;
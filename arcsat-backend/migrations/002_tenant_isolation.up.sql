@@ -0,0 +1,61 @@
+-- Isolamento por tenant
+--
+-- A aplicação abre cada transação com `set_config('app.tenant_id', <uuid>, true)`;
+-- as policies abaixo só expõem linhas desse tenant. FORCE faz a regra valer
+-- também para o dono das tabelas (o usuário da aplicação).
+
+-- scraped_products herda o tenant do job
+ALTER TABLE scraped_products ADD COLUMN IF NOT EXISTS tenant_id UUID;
+
+UPDATE scraped_products p
+SET tenant_id = j.tenant_id
+FROM scraping_jobs j
+WHERE p.job_id = j.id AND p.tenant_id IS NULL;
+
+ALTER TABLE scraped_products ALTER COLUMN tenant_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_scraped_products_tenant_id ON scraped_products (tenant_id);
+
+-- Produto e job sempre do mesmo tenant
+ALTER TABLE scraping_jobs ADD CONSTRAINT scraping_jobs_id_tenant_key UNIQUE (id, tenant_id);
+ALTER TABLE scraped_products ADD CONSTRAINT scraped_products_job_tenant_fkey
+    FOREIGN KEY (job_id, tenant_id) REFERENCES scraping_jobs (id, tenant_id) ON DELETE CASCADE;
+
+-- Tenant atual da transação (NULL quando não definido: nenhuma linha visível)
+CREATE OR REPLACE FUNCTION current_tenant_id() RETURNS UUID AS $$
+    SELECT NULLIF(current_setting('app.tenant_id', true), '')::uuid
+$$ LANGUAGE SQL STABLE;
+
+ALTER TABLE scraping_jobs ENABLE ROW LEVEL SECURITY;
+ALTER TABLE scraping_jobs FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON scraping_jobs
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());
+
+ALTER TABLE scraped_products ENABLE ROW LEVEL SECURITY;
+ALTER TABLE scraped_products FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON scraped_products
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());
+
+ALTER TABLE trend_analyses ENABLE ROW LEVEL SECURITY;
+ALTER TABLE trend_analyses FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON trend_analyses
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());
+
+ALTER TABLE proxy_configs ENABLE ROW LEVEL SECURITY;
+ALTER TABLE proxy_configs FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON proxy_configs
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());
+
+ALTER TABLE market_alerts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE market_alerts FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON market_alerts
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());
+
+-- As views rodam com as permissões de quem consulta, então também respeitam as policies
+ALTER VIEW recent_jobs SET (security_invoker = true);
+ALTER VIEW category_pricing SET (security_invoker = true);
@@ -0,0 +1,2 @@
+ALTER TABLE scraping_jobs DROP COLUMN IF EXISTS error_history;
+ALTER TABLE scraping_jobs DROP COLUMN IF EXISTS attempts;
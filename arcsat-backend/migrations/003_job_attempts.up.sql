@@ -0,0 +1,3 @@
+-- Tentativas de execução e histórico de erros de cada job
+ALTER TABLE scraping_jobs ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE scraping_jobs ADD COLUMN IF NOT EXISTS error_history JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_scraping_jobs_tenant_priority;
+DROP INDEX IF EXISTS idx_scraping_jobs_tenant_created;
@@ -0,0 +1,5 @@
+-- Listagem de jobs por tenant com paginação por cursor (keyset)
+CREATE INDEX IF NOT EXISTS idx_scraping_jobs_tenant_created
+    ON scraping_jobs (tenant_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_scraping_jobs_tenant_priority
+    ON scraping_jobs (tenant_id, priority DESC, created_at DESC, id DESC);
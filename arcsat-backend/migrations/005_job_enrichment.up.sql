@@ -0,0 +1,2 @@
+-- Segunda etapa opcional do job: visita a página de cada produto
+ALTER TABLE scraping_jobs ADD COLUMN IF NOT EXISTS enrich_details BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1 @@
+ALTER TABLE scraping_jobs DROP COLUMN IF EXISTS fetch_strategy;
@@ -0,0 +1,2 @@
+-- Estratégia de download das páginas de busca; NULL usa a padrão do scraper
+ALTER TABLE scraping_jobs ADD COLUMN IF NOT EXISTS fetch_strategy VARCHAR(20);
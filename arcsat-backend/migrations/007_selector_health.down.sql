@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_market_alerts_type;
+DROP TABLE IF EXISTS selector_health;
@@ -0,0 +1,15 @@
+-- Linha de base da extração da busca por marketplace. Compartilhada pelos
+-- tenants (o markup é o mesmo para todos), por isso sem tenant_id nem RLS.
+CREATE TABLE IF NOT EXISTS selector_health (
+    marketplace VARCHAR(50) PRIMARY KEY,
+    parse_yield DOUBLE PRECISION NOT NULL,
+    samples INTEGER NOT NULL DEFAULT 0,
+    sample_url TEXT,
+    sample_html TEXT,
+    sample_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Deduplicação dos alertas de drift por tenant e marketplace
+CREATE INDEX IF NOT EXISTS idx_market_alerts_type
+    ON market_alerts (tenant_id, marketplace, alert_type, created_at);
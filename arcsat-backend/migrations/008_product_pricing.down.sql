@@ -0,0 +1,4 @@
+ALTER TABLE scraped_products DROP COLUMN IF EXISTS installments;
+ALTER TABLE scraped_products DROP COLUMN IF EXISTS max_price;
+ALTER TABLE scraped_products DROP COLUMN IF EXISTS pix_price;
+ALTER TABLE scraped_products DROP COLUMN IF EXISTS list_price;
@@ -0,0 +1,6 @@
+-- Condições de preço do anúncio além do preço de venda (`price`)
+ALTER TABLE scraped_products ADD COLUMN IF NOT EXISTS list_price DECIMAL(10, 2);
+ALTER TABLE scraped_products ADD COLUMN IF NOT EXISTS pix_price DECIMAL(10, 2);
+ALTER TABLE scraped_products ADD COLUMN IF NOT EXISTS max_price DECIMAL(10, 2);
+-- {"count": 12, "amount": 29.90, "interest_free": true}
+ALTER TABLE scraped_products ADD COLUMN IF NOT EXISTS installments JSONB;
@@ -0,0 +1,5 @@
+UPDATE scraped_products
+SET installments = jsonb_set(installments, '{amount}', to_jsonb((installments->'amount'->>'amount')::numeric))
+WHERE jsonb_typeof(installments->'amount') = 'object';
+
+ALTER TABLE trend_analyses DROP COLUMN IF EXISTS currency;
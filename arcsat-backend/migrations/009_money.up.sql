@@ -0,0 +1,12 @@
+-- Moeda das estatísticas de preço da análise
+ALTER TABLE trend_analyses ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'BRL';
+
+-- Parcela no formato de `Money`:
+-- {"count": 12, "amount": {"amount": "29.90", "currency": "BRL"}, "interest_free": true}
+UPDATE scraped_products
+SET installments = jsonb_set(
+    installments,
+    '{amount}',
+    jsonb_build_object('amount', installments->>'amount', 'currency', currency)
+)
+WHERE jsonb_typeof(installments->'amount') = 'number';
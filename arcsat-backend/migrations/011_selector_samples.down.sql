@@ -0,0 +1,6 @@
+ALTER TABLE selector_health
+    ADD COLUMN IF NOT EXISTS sample_url TEXT,
+    ADD COLUMN IF NOT EXISTS sample_html TEXT,
+    ADD COLUMN IF NOT EXISTS sample_at TIMESTAMP WITH TIME ZONE;
+
+DROP TABLE IF EXISTS selector_samples;
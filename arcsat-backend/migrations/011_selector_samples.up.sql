@@ -0,0 +1,23 @@
+-- Amostra de HTML do monitor de seletores por tenant. A URL e a página trazem
+-- o termo buscado pelo tenant, então saem da tabela global selector_health.
+CREATE TABLE IF NOT EXISTS selector_samples (
+    tenant_id UUID NOT NULL,
+    marketplace VARCHAR(50) NOT NULL,
+    job_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    html TEXT NOT NULL,
+    captured_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tenant_id, marketplace)
+);
+
+ALTER TABLE selector_samples ENABLE ROW LEVEL SECURITY;
+ALTER TABLE selector_samples FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON selector_samples
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());
+
+-- A amostra global não tem dono: descartada, volta no próximo job de cada tenant
+ALTER TABLE selector_health
+    DROP COLUMN IF EXISTS sample_url,
+    DROP COLUMN IF EXISTS sample_html,
+    DROP COLUMN IF EXISTS sample_at;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_scraped_products_tenant_category;
+DROP TABLE IF EXISTS crm_products;
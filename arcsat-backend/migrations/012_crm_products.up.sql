@@ -0,0 +1,26 @@
+-- Catálogo de produtos do CRM, por tenant; os insights de mercado comparam
+-- cada produto com os anúncios coletados na mesma categoria
+CREATE TABLE IF NOT EXISTS crm_products (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL,
+    sku VARCHAR(100) NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    category VARCHAR(255) NOT NULL,
+    price DECIMAL(12, 2) NOT NULL,
+    cost DECIMAL(12, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'BRL',
+    stock INTEGER NOT NULL DEFAULT 0,
+    active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (tenant_id, sku)
+);
+
+CREATE INDEX IF NOT EXISTS idx_scraped_products_tenant_category ON scraped_products (tenant_id, category);
+
+ALTER TABLE crm_products ENABLE ROW LEVEL SECURITY;
+ALTER TABLE crm_products FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON crm_products
+    USING (tenant_id = current_tenant_id())
+    WITH CHECK (tenant_id = current_tenant_id());